@@ -19,7 +19,7 @@ use std::{env, mem, path::PathBuf, sync::Arc, time::Duration};
 use telemetry_events::{
     ActionEvent, AppEvent, AssistantEvent, CallEvent, EditEvent, EditorEvent, Event,
     EventRequestBody, EventWrapper, ExtensionEvent, InlineCompletionEvent, InlineCompletionRating,
-    InlineCompletionRatingEvent, ReplEvent, SettingEvent,
+    InlineCompletionRatingEvent, ReplEvent, ReplExecutionEvent, SettingEvent,
 };
 use util::{ResultExt, TryFutureExt};
 use worktree::{UpdatedEntriesSet, WorktreeId};
@@ -506,6 +506,23 @@ impl Telemetry {
         self.report_event(event)
     }
 
+    pub fn report_repl_execution_event(
+        self: &Arc<Self>,
+        kernel_language: String,
+        repl_session_id: String,
+        success: bool,
+        duration: Duration,
+    ) {
+        let event = Event::ReplExecution(ReplExecutionEvent {
+            kernel_language,
+            repl_session_id,
+            success,
+            duration_ms: duration.as_millis() as u64,
+        });
+
+        self.report_event(event)
+    }
+
     fn report_event(self: &Arc<Self>, event: Event) {
         let mut state = self.state.lock();
 
@@ -1,5 +1,6 @@
 use std::{
     cmp::{self, Reverse},
+    collections::VecDeque,
     sync::Arc,
     time::Duration,
 };
@@ -26,6 +27,7 @@ use zed_actions::{command_palette::Toggle, OpenZedUrl};
 pub fn init(cx: &mut AppContext) {
     client::init_settings(cx);
     cx.set_global(HitCounts::default());
+    cx.set_global(RecentCommands::default());
     command_palette_hooks::init(cx);
     cx.observe_new_views(CommandPalette::register).detach();
 }
@@ -163,6 +165,30 @@ struct HitCounts(HashMap<String, usize>);
 
 impl Global for HitCounts {}
 
+/// The maximum number of commands kept in [`RecentCommands`].
+const MAX_RECENT_COMMANDS: usize = 8;
+
+/// The commands most recently confirmed in the command palette, most recent first.
+#[derive(Default)]
+pub struct RecentCommands(VecDeque<Command>);
+
+impl Global for RecentCommands {}
+
+impl RecentCommands {
+    /// Returns the names and actions of the recent commands, most recent first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &dyn Action)> {
+        self.0
+            .iter()
+            .map(|command| (command.name.as_str(), command.action.as_ref()))
+    }
+
+    fn push(&mut self, command: Command) {
+        self.0.retain(|recent| recent.name != command.name);
+        self.0.push_front(command);
+        self.0.truncate(MAX_RECENT_COMMANDS);
+    }
+}
+
 impl CommandPaletteDelegate {
     fn new(
         command_palette: WeakView<CommandPalette>,
@@ -377,7 +403,10 @@ impl PickerDelegate for CommandPaletteDelegate {
         self.matches.clear();
         self.commands.clear();
         HitCounts::update_global(cx, |hit_counts, _cx| {
-            *hit_counts.0.entry(command.name).or_default() += 1;
+            *hit_counts.0.entry(command.name.clone()).or_default() += 1;
+        });
+        RecentCommands::update_global(cx, |recent_commands, _cx| {
+            recent_commands.push(command.clone());
         });
         let action = command.action;
         cx.focus(&self.previous_focus_handle);
@@ -526,6 +555,17 @@ mod tests {
             assert_eq!(editor.read(cx).text(cx), "ab")
         });
 
+        cx.update(|cx| {
+            let recent_commands = cx.global::<RecentCommands>();
+            assert_eq!(
+                recent_commands
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+                ["editor: backspace"]
+            );
+        });
+
         // Add namespace filter, and redeploy the palette
         cx.update(|cx| {
             CommandPaletteFilter::update_global(cx, |filter, _| {
@@ -221,6 +221,7 @@ gpui::actions!(
         Backspace,
         Cancel,
         CancelLanguageServerWork,
+        ClearBookmarks,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
@@ -239,9 +240,11 @@ gpui::actions!(
         CopyHighlightJson,
         CopyPath,
         CopyPermalinkToLine,
+        CopyPermalinkToSelection,
         CopyRelativePath,
         Cut,
         CutToEndOfLine,
+        DecreaseTabSize,
         Delete,
         DeleteLine,
         DeleteToBeginningOfLine,
@@ -271,6 +274,8 @@ gpui::actions!(
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToNextBookmark,
+        GoToPrevBookmark,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -279,6 +284,7 @@ gpui::actions!(
         HalfPageUp,
         Hover,
         Indent,
+        IncreaseTabSize,
         InsertUuidV4,
         InsertUuidV7,
         JoinLines,
@@ -319,7 +325,9 @@ gpui::actions!(
         PageDown,
         PageUp,
         Paste,
+        PeekDefinition,
         PreviousInlineCompletion,
+        QuickFix,
         Redo,
         RedoSelection,
         Rename,
@@ -328,8 +336,10 @@ gpui::actions!(
         ReverseLines,
         RevertFile,
         ReloadFile,
+        ResetTabSize,
         RevertSelectedHunks,
         Rewrap,
+        RunSelectionInTerminal,
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorCenterTopBottom,
@@ -365,6 +375,7 @@ gpui::actions!(
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
+        ToggleBookmark,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleHunkDiff,
@@ -372,11 +383,13 @@ gpui::actions!(
         ToggleInlayHints,
         ToggleInlineCompletions,
         ToggleLineNumbers,
+        ToggleReadOnly,
         ToggleRelativeLineNumbers,
         ToggleSelectionMenu,
         ToggleSoftWrap,
         ToggleTabBar,
         Transpose,
+        TransposeLines,
         Undo,
         UndoSelection,
         UnfoldAll,
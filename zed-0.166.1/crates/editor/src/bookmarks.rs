@@ -0,0 +1,111 @@
+use gpui::{AppContext, ViewContext};
+use language::Point;
+use multi_buffer::ToPoint as _;
+use theme::ActiveTheme as _;
+
+use crate::{
+    actions::{ClearBookmarks, GoToNextBookmark, GoToPrevBookmark, ToggleBookmark},
+    scroll::Autoscroll,
+    Direction, Editor,
+};
+
+/// Marker type for the gutter highlights used to render bookmarked rows.
+enum BookmarkGutterHighlight {}
+
+impl Editor {
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut rows = self
+            .selections
+            .all::<Point>(cx)
+            .into_iter()
+            .map(|selection| selection.head().row)
+            .collect::<Vec<_>>();
+        rows.dedup();
+
+        for row in rows {
+            if let Some(ix) = self
+                .bookmarks
+                .iter()
+                .position(|bookmark| bookmark.to_point(&buffer).row == row)
+            {
+                self.bookmarks.remove(ix);
+            } else {
+                self.bookmarks
+                    .push(buffer.anchor_before(Point::new(row, 0)));
+            }
+        }
+
+        self.refresh_bookmarks(cx);
+    }
+
+    pub fn go_to_next_bookmark(&mut self, _: &GoToNextBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark(Direction::Next, cx);
+    }
+
+    pub fn go_to_prev_bookmark(&mut self, _: &GoToPrevBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark(Direction::Prev, cx);
+    }
+
+    pub fn clear_bookmarks(&mut self, _: &ClearBookmarks, cx: &mut ViewContext<Self>) {
+        self.bookmarks.clear();
+        self.refresh_bookmarks(cx);
+    }
+
+    /// Returns the rows of all bookmarks in this editor, in ascending order.
+    pub fn bookmarked_rows(&self, cx: &AppContext) -> Vec<u32> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        self.bookmarks
+            .iter()
+            .map(|bookmark| bookmark.to_point(&buffer).row)
+            .collect()
+    }
+
+    fn go_to_bookmark(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        // Edits may have collapsed several bookmarks onto the same row.
+        self.refresh_bookmarks(cx);
+
+        let rows = self.bookmarked_rows(cx);
+        let head_row = self.selections.newest::<Point>(cx).head().row;
+        let target_row = match direction {
+            Direction::Next => rows
+                .iter()
+                .find(|row| **row > head_row)
+                .or_else(|| rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| **row < head_row)
+                .or_else(|| rows.last()),
+        };
+
+        if let Some(row) = target_row.copied() {
+            let point = Point::new(row, 0);
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([point..point]);
+            });
+        }
+    }
+
+    fn refresh_bookmarks(&mut self, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        self.bookmarks.sort_by(|a, b| a.cmp(b, &buffer));
+        self.bookmarks
+            .dedup_by(|a, b| a.to_point(&buffer).row == b.to_point(&buffer).row);
+
+        if self.bookmarks.is_empty() {
+            self.clear_gutter_highlights::<BookmarkGutterHighlight>(cx);
+        } else {
+            let ranges = self
+                .bookmarks
+                .iter()
+                .map(|bookmark| *bookmark..*bookmark)
+                .collect::<Vec<_>>();
+            self.highlight_gutter::<BookmarkGutterHighlight>(
+                &ranges,
+                |cx| cx.theme().status().info,
+                cx,
+            );
+        }
+    }
+}
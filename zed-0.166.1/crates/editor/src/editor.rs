@@ -15,6 +15,7 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod bookmarks;
 mod clangd_ext;
 mod code_context_menus;
 pub mod display_map;
@@ -97,14 +98,17 @@ use inline_completion::{InlineCompletionProvider, InlineCompletionProviderHandle
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{
-    language_settings::{self, all_language_settings, language_settings, InlayHintSettings},
+    language_settings::{
+        self, all_language_settings, language_settings, AllLanguageSettings, InlayHintSettings,
+    },
     markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
     CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
     Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{point_to_lsp, BufferRow, CharClassifier, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
-use mouse_context_menu::MouseContextMenu;
+pub use mouse_context_menu::ContextMenuPlacement;
+use mouse_context_menu::{ContextMenuItems, MouseContextMenu};
 pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
@@ -174,7 +178,9 @@ use workspace::notifications::{DetachAndPromptErr, NotificationId, NotifyTaskExt
 use workspace::{
     searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
 };
-use workspace::{Item as WorkspaceItem, OpenInTerminal, OpenTerminal, TabBarSettings, Toast};
+use workspace::{
+    Item as WorkspaceItem, OpenInTerminal, OpenTerminal, RunInTerminal, TabBarSettings, Toast,
+};
 
 use crate::hover_links::find_url;
 use crate::signature_help::{SignatureHelpHiddenBy, SignatureHelpState};
@@ -520,6 +526,14 @@ struct ResolvedTasks {
     templates: SmallVec<[(TaskSourceKind, ResolvedTask); 1]>,
     position: Anchor,
 }
+/// Run buttons shown in the gutter next to arbitrary rows, e.g. at the start of
+/// every REPL cell.
+#[derive(Clone)]
+struct GutterRunButtons {
+    positions: Vec<Anchor>,
+    on_click: Rc<dyn Fn(Anchor, &mut WindowContext)>,
+}
+
 #[derive(Copy, Clone, Debug)]
 struct MultiBufferOffset(usize);
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -589,6 +603,7 @@ pub struct Editor {
     highlighted_rows: HashMap<TypeId, Vec<RowHighlight>>,
     background_highlights: TreeMap<TypeId, BackgroundHighlight>,
     gutter_highlights: TreeMap<TypeId, GutterHighlight>,
+    bookmarks: Vec<Anchor>,
     scrollbar_marker_state: ScrollbarMarkerState,
     active_indent_guides_state: ActiveIndentGuidesState,
     nav_history: Option<ItemNavHistory>,
@@ -654,6 +669,8 @@ pub struct Editor {
                 + Fn(&mut Self, DisplayPoint, &mut ViewContext<Self>) -> Option<View<ui::ContextMenu>>,
         >,
     >,
+    context_menu_items: Vec<ContextMenuItems>,
+    gutter_run_buttons: Option<GutterRunButtons>,
     last_bounds: Option<Bounds<Pixels>>,
     expect_bounds_change: Option<Bounds<Pixels>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
@@ -1219,6 +1236,7 @@ impl Editor {
             highlighted_rows: HashMap::default(),
             background_highlights: Default::default(),
             gutter_highlights: TreeMap::default(),
+            bookmarks: Vec::new(),
             scrollbar_marker_state: ScrollbarMarkerState::default(),
             active_indent_guides_state: ActiveIndentGuidesState::default(),
             nav_history: None,
@@ -1272,6 +1290,8 @@ impl Editor {
             show_inline_completions_override: None,
             enable_inline_completions: true,
             custom_context_menu: None,
+            context_menu_items: Vec::new(),
+            gutter_run_buttons: None,
             show_git_blame_gutter: false,
             show_git_blame_inline: false,
             show_selection_menu: None,
@@ -1568,6 +1588,20 @@ impl Editor {
         self.custom_context_menu = Some(Box::new(f))
     }
 
+    /// Adds items to the default context menu, at `placement`, rather than
+    /// replacing the whole menu as [`Editor::set_custom_context_menu`] does.
+    /// `f` is called with the menu being built and the clicked point.
+    pub fn add_context_menu_items(
+        &mut self,
+        placement: ContextMenuPlacement,
+        f: impl 'static + Fn(ui::ContextMenu, DisplayPoint, &mut WindowContext) -> ui::ContextMenu,
+    ) {
+        self.context_menu_items.push(ContextMenuItems {
+            placement,
+            build: Rc::new(f),
+        });
+    }
+
     pub fn set_completion_provider(&mut self, provider: Option<Box<dyn CompletionProvider>>) {
         self.completion_provider = provider;
     }
@@ -1673,6 +1707,15 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    pub fn toggle_read_only(&mut self, _: &ToggleReadOnly, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full || self.buffer.read(cx).read_only() {
+            return;
+        }
+        self.read_only = !self.read_only;
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -4045,6 +4088,81 @@ impl Editor {
         .detach_and_log_err(cx);
     }
 
+    /// Shows the quick fixes for the diagnostics under the newest cursor,
+    /// leaving out any other code actions available there.
+    pub fn quick_fix(&mut self, _: &QuickFix, cx: &mut ViewContext<Self>) {
+        let mut task = self.code_actions_task.take();
+        cx.spawn(|editor, mut cx| async move {
+            while let Some(prev_task) = task {
+                prev_task.await.log_err();
+                task = editor.update(&mut cx, |this, _| this.code_actions_task.take())?;
+            }
+
+            editor.update(&mut cx, |editor, cx| editor.show_quick_fixes(cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_quick_fixes(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.focus_handle.is_focused(cx) {
+            return;
+        }
+        let head = self.selections.newest::<usize>(cx).head();
+        let multibuffer = self.buffer.read(cx);
+        if multibuffer
+            .snapshot(cx)
+            .diagnostics_in_range::<_, usize>(head..head, false)
+            .next()
+            .is_none()
+        {
+            return;
+        }
+        let Some((buffer, position)) = multibuffer.text_anchor_for_position(head, cx) else {
+            return;
+        };
+        let Some((location, code_actions)) = self.available_code_actions.clone() else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let position = position.to_offset(&snapshot);
+        let range = location.range.to_offset(&snapshot);
+        if location.buffer != buffer || !(range.start..=range.end).contains(&position) {
+            return;
+        }
+
+        let quick_fixes = code_actions
+            .iter()
+            .filter(|available| {
+                let action = &available.action.lsp_action;
+                action.kind.as_ref().map_or(false, |kind| {
+                    kind.as_str()
+                        .starts_with(lsp::CodeActionKind::QUICKFIX.as_str())
+                }) || action
+                    .diagnostics
+                    .as_ref()
+                    .map_or(false, |diagnostics| !diagnostics.is_empty())
+            })
+            .cloned()
+            .collect::<Arc<[_]>>();
+        if quick_fixes.is_empty() {
+            return;
+        }
+
+        self.completion_tasks.clear();
+        self.discard_inline_completion(false, cx);
+        *self.context_menu.write() = Some(CodeContextMenu::CodeActions(CodeActionsMenu {
+            buffer,
+            actions: CodeActionContents {
+                tasks: None,
+                actions: Some(quick_fixes),
+            },
+            selected_item: Default::default(),
+            scroll_handle: UniformListScrollHandle::default(),
+            deployed_from_indicator: None,
+        }));
+        cx.notify();
+    }
+
     pub fn confirm_code_action(
         &mut self,
         action: &ConfirmCodeAction,
@@ -4950,6 +5068,17 @@ impl Editor {
             }))
     }
 
+    fn render_gutter_run_button(&self, row: DisplayRow, position: Anchor) -> Option<IconButton> {
+        let on_click = self.gutter_run_buttons.as_ref()?.on_click.clone();
+        Some(
+            IconButton::new(("gutter_run_button", row.0 as usize), ui::IconName::Play)
+                .shape(ui::IconButtonShape::Square)
+                .icon_size(IconSize::XSmall)
+                .icon_color(Color::Muted)
+                .on_click(move |_, cx| on_click(position, cx)),
+        )
+    }
+
     pub fn context_menu_visible(&self) -> bool {
         self.context_menu
             .read()
@@ -5810,7 +5939,45 @@ impl Editor {
     }
 
     pub fn open_active_item_in_terminal(&mut self, _: &OpenInTerminal, cx: &mut ViewContext<Self>) {
-        if let Some(working_directory) = self.active_excerpt(cx).and_then(|(_, buffer, _)| {
+        if let Some(working_directory) = self.active_item_directory(cx) {
+            cx.dispatch_action(OpenTerminal { working_directory }.boxed_clone());
+        }
+    }
+
+    pub fn run_selection_in_terminal(
+        &mut self,
+        _: &RunSelectionInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let text = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| {
+                buffer
+                    .text_for_range(selection.start..selection.end)
+                    .collect::<String>()
+            })
+            .join("\n");
+        if text.trim().is_empty() {
+            return;
+        }
+
+        if let Some(working_directory) = self.active_item_directory(cx) {
+            cx.dispatch_action(
+                RunInTerminal {
+                    working_directory,
+                    text,
+                }
+                .boxed_clone(),
+            );
+        }
+    }
+
+    fn active_item_directory(&self, cx: &AppContext) -> Option<PathBuf> {
+        self.active_excerpt(cx).and_then(|(_, buffer, _)| {
             let project_path = buffer.read(cx).project_path(cx)?;
             let project = self.project.as_ref()?.read(cx);
             let entry = project.entry_for_path(&project_path, cx)?;
@@ -5821,9 +5988,7 @@ impl Editor {
             .parent()?
             .to_path_buf();
             Some(parent)
-        }) {
-            cx.dispatch_action(OpenTerminal { working_directory }.boxed_clone());
-        }
+        })
     }
 
     fn gather_revert_changes(
@@ -6401,6 +6566,65 @@ impl Editor {
         });
     }
 
+    /// Swaps each cursor's line with the line above it, leaving the cursor
+    /// after both lines, so that repeated use moves the line above downwards.
+    pub fn transpose_lines(&mut self, _: &TransposeLines, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let max_point = buffer.max_point();
+
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut last_transposed_row = None;
+        for mut selection in self.selections.all::<Point>(cx) {
+            let row = selection.head().row;
+            let previous_line_start = Point::new(row.saturating_sub(1), 0);
+            let line_start = Point::new(row, 0);
+            if row == 0
+                || last_transposed_row.map_or(false, |last_row| row <= last_row + 1)
+                || buffer
+                    .excerpt_boundaries_in_range((
+                        Bound::Excluded(previous_line_start),
+                        Bound::Included(line_start),
+                    ))
+                    .next()
+                    .is_some()
+            {
+                new_selections.push(selection);
+                continue;
+            }
+
+            let previous_line_end = Point::new(row - 1, buffer.line_len(MultiBufferRow(row - 1)));
+            let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+            let previous_line = buffer
+                .text_for_range(previous_line_start..previous_line_end)
+                .collect::<String>();
+            let line = buffer
+                .text_for_range(line_start..line_end)
+                .collect::<String>();
+            edits.push((
+                previous_line_start..line_end,
+                format!("{line}\n{previous_line}"),
+            ));
+            last_transposed_row = Some(row);
+
+            let cursor = if row < max_point.row {
+                Point::new(row + 1, 0)
+            } else {
+                Point::new(row, previous_line.len() as u32)
+            };
+            selection.collapse_to(cursor, SelectionGoal::None);
+            new_selections.push(selection);
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(new_selections);
+            });
+        });
+    }
+
     pub fn rewrap(&mut self, _: &Rewrap, cx: &mut ViewContext<Self>) {
         self.rewrap_impl(IsVimMode::No, cx)
     }
@@ -10882,6 +11106,52 @@ impl Editor {
         });
     }
 
+    /// Returns the tab size in effect for this editor's language.
+    pub fn tab_size(&self, cx: &AppContext) -> NonZeroU32 {
+        self.buffer.read(cx).settings_at(0, cx).tab_size
+    }
+
+    /// Returns whether this editor's tab size differs from the default tab size.
+    pub fn has_adjusted_tab_size(&self, cx: &AppContext) -> bool {
+        self.tab_size(cx) != language_settings(None, None, cx).tab_size
+    }
+
+    pub fn increase_tab_size(&mut self, _: &IncreaseTabSize, cx: &mut ViewContext<Self>) {
+        let tab_size = self.tab_size(cx).saturating_add(1);
+        self.set_language_tab_size(Some(tab_size), cx);
+    }
+
+    pub fn decrease_tab_size(&mut self, _: &DecreaseTabSize, cx: &mut ViewContext<Self>) {
+        if let Some(tab_size) = NonZeroU32::new(self.tab_size(cx).get() - 1) {
+            self.set_language_tab_size(Some(tab_size), cx);
+        }
+    }
+
+    pub fn reset_tab_size(&mut self, _: &ResetTabSize, cx: &mut ViewContext<Self>) {
+        self.set_language_tab_size(None, cx);
+    }
+
+    /// Writes the tab size for this editor's language to the user settings,
+    /// falling back to the default tab size for plain text.
+    fn set_language_tab_size(&self, tab_size: Option<NonZeroU32>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let fs = workspace.read(cx).app_state().fs.clone();
+        let language = self
+            .buffer
+            .read(cx)
+            .language_at(0, cx)
+            .map(|language| language.name());
+        update_settings_file::<AllLanguageSettings>(fs, cx, move |settings, _| {
+            let language_settings = match language {
+                Some(language) => settings.languages.entry(language).or_default(),
+                None => &mut settings.defaults,
+            };
+            language_settings.tab_size = tab_size;
+        });
+    }
+
     pub fn toggle_indent_guides(&mut self, _: &ToggleIndentGuides, cx: &mut ViewContext<Self>) {
         let currently_enabled = self.should_show_indent_guides().unwrap_or_else(|| {
             self.buffer
@@ -10956,6 +11226,28 @@ impl Editor {
         cx.notify();
     }
 
+    /// Shows a run button in the gutter at each of `positions`, replacing any
+    /// buttons set before. `on_click` is called with the position whose
+    /// button was clicked.
+    pub fn set_gutter_run_buttons(
+        &mut self,
+        positions: Vec<Anchor>,
+        on_click: impl 'static + Fn(Anchor, &mut WindowContext),
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.gutter_run_buttons = Some(GutterRunButtons {
+            positions,
+            on_click: Rc::new(on_click),
+        });
+        cx.notify();
+    }
+
+    pub fn clear_gutter_run_buttons(&mut self, cx: &mut ViewContext<Self>) {
+        if self.gutter_run_buttons.take().is_some() {
+            cx.notify();
+        }
+    }
+
     pub fn set_masked(&mut self, masked: bool, cx: &mut ViewContext<Self>) {
         if self.display_map.read(cx).masked != masked {
             self.display_map.update(cx, |map, _| map.masked = masked);
@@ -11141,10 +11433,34 @@ impl Editor {
     }
 
     fn get_permalink_to_line(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<url::Url>> {
-        let buffer_and_selection = maybe!({
-            let selection = self.selections.newest::<Point>(cx);
-            let selection_range = selection.range();
+        let selection = self.selections.newest::<Point>(cx);
+        self.get_permalink(selection.range(), selection.reversed, cx)
+    }
 
+    /// Returns a permalink spanning the lines of all selections. A selection
+    /// ending at the start of a line, as when whole lines are selected, does
+    /// not include that line.
+    fn get_permalink_to_selection(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<url::Url>> {
+        let selections = self.selections.all::<Point>(cx);
+        let (Some(start), Some(mut end)) = (
+            selections.iter().map(|selection| selection.start).min(),
+            selections.iter().map(|selection| selection.end).max(),
+        ) else {
+            return Task::ready(Err(anyhow!("editor has no selections")));
+        };
+        if end.column == 0 && end.row > start.row {
+            end = Point::new(end.row - 1, 0);
+        }
+        self.get_permalink(start..end, false, cx)
+    }
+
+    fn get_permalink(
+        &mut self,
+        selection_range: Range<Point>,
+        reversed: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<url::Url>> {
+        let buffer_and_selection = maybe!({
             let (buffer, selection) = if let Some(buffer) = self.buffer().read(cx).as_singleton() {
                 (buffer, selection_range.start.row..selection_range.end.row)
             } else {
@@ -11153,7 +11469,7 @@ impl Editor {
                     .read(cx)
                     .range_to_buffer_ranges(selection_range, cx);
 
-                let (buffer, range, _) = if selection.reversed {
+                let (buffer, range, _) = if reversed {
                     buffer_ranges.first()
                 } else {
                     buffer_ranges.last()
@@ -11183,6 +11499,23 @@ impl Editor {
 
     pub fn copy_permalink_to_line(&mut self, _: &CopyPermalinkToLine, cx: &mut ViewContext<Self>) {
         let permalink_task = self.get_permalink_to_line(cx);
+        self.copy_permalink(permalink_task, cx);
+    }
+
+    pub fn copy_permalink_to_selection(
+        &mut self,
+        _: &CopyPermalinkToSelection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let permalink_task = self.get_permalink_to_selection(cx);
+        self.copy_permalink(permalink_task, cx);
+    }
+
+    fn copy_permalink(
+        &mut self,
+        permalink_task: Task<Result<url::Url>>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let workspace = self.workspace();
 
         cx.spawn(|_, mut cx| async move {
@@ -31,6 +31,7 @@ pub struct EditorSettings {
     pub search: SearchSettings,
     pub auto_signature_help: bool,
     pub show_signature_help_after_edits: bool,
+    pub hidden_context_menu_actions: Vec<String>,
     pub jupyter: Jupyter,
 }
 
@@ -269,6 +270,12 @@ pub struct EditorSettingsContent {
     /// Default: false
     pub show_signature_help_after_edits: Option<bool>,
 
+    /// The names of actions whose items to leave out of the editor's context
+    /// menu, such as "editor::CopyPermalinkToLine".
+    ///
+    /// Default: []
+    pub hidden_context_menu_actions: Option<Vec<String>>,
+
     /// Jupyter REPL settings.
     pub jupyter: Option<JupyterContent>,
 }
@@ -329,7 +329,7 @@ impl RenderOnce for InlineGitBlameControl {
 }
 
 #[derive(IntoElement)]
-struct LineNumbersControl;
+pub struct LineNumbersControl;
 
 impl EditableSettingControl for LineNumbersControl {
     type Value = bool;
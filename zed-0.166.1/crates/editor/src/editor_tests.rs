@@ -3789,6 +3789,22 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     cx.assert_editor_state(indoc! {"
         «HeLlO, wOrLD!ˇ»
     "});
+
+    // Characters whose case mapping expands to several characters, and
+    // titlecase characters, which are neither upper nor lower case
+    cx.set_state("«Straße ﬁ ǅˇ»");
+    cx.update_editor(|e, cx| e.convert_to_opposite_case(&ConvertToOppositeCase, cx));
+    cx.assert_editor_state("«sTRASSE FI Ǆˇ»");
+
+    // Lowercasing a whole word uses the final form of sigma
+    cx.set_state("«ΟΔΟΣ ΟΔΟΣˇ» ΟΔΟΣ");
+    cx.update_editor(|e, cx| e.convert_to_lower_case(&ConvertToLowerCase, cx));
+    cx.assert_editor_state("«οδος οδοςˇ» ΟΔΟΣ");
+
+    // Lowercasing a dotted capital I adds a combining dot above
+    cx.set_state("«İˇ»");
+    cx.update_editor(|e, cx| e.convert_to_lower_case(&ConvertToLowerCase, cx));
+    cx.assert_editor_state("«i\u{307}ˇ»");
 }
 
 #[gpui::test]
@@ -4185,6 +4201,49 @@ fn test_transpose(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_transpose_lines(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        one
+        twˇo
+        three
+        four"});
+    cx.update_editor(|editor, cx| editor.transpose_lines(&TransposeLines, cx));
+    cx.assert_editor_state(indoc! {"
+        two
+        one
+        ˇthree
+        four"});
+    cx.update_editor(|editor, cx| editor.transpose_lines(&TransposeLines, cx));
+    cx.assert_editor_state(indoc! {"
+        two
+        three
+        one
+        ˇfour"});
+    cx.update_editor(|editor, cx| editor.transpose_lines(&TransposeLines, cx));
+    cx.assert_editor_state(indoc! {"
+        two
+        three
+        four
+        oneˇ"});
+
+    // Cursors on the first line are left alone.
+    cx.set_state(indoc! {"
+        oˇne
+        two
+        thˇree
+        four"});
+    cx.update_editor(|editor, cx| editor.transpose_lines(&TransposeLines, cx));
+    cx.assert_editor_state(indoc! {"
+        oˇne
+        three
+        two
+        ˇfour"});
+}
+
 #[gpui::test]
 async fn test_rewrap(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -5062,6 +5121,41 @@ async fn test_add_selection_above_below(cx: &mut TestAppContext) {
     ));
 }
 
+#[gpui::test]
+async fn test_add_selection_above_below_preserves_direction(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc!(
+        r#"a«ˇbc»
+           defghi
+           "#
+    ));
+    cx.update_editor(|editor, cx| {
+        editor.add_selection_below(&Default::default(), cx);
+    });
+    cx.assert_editor_selection_directions(indoc!(
+        r#"a‹bc›
+           d‹ef›ghi
+           "#
+    ));
+
+    cx.set_state(indoc!(
+        r#"abc
+           d«efˇ»ghi
+           "#
+    ));
+    cx.update_editor(|editor, cx| {
+        editor.add_selection_above(&Default::default(), cx);
+    });
+    cx.assert_editor_selection_directions(indoc!(
+        r#"a«bc»
+           d«ef»ghi
+           "#
+    ));
+}
+
 #[gpui::test]
 async fn test_select_next(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -13880,6 +13974,246 @@ async fn test_find_enclosing_node_with_task(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_toggle_read_only(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("oneˇ");
+
+    cx.update_editor(|editor, cx| editor.toggle_read_only(&ToggleReadOnly, cx));
+    cx.simulate_input("!");
+    cx.assert_editor_state("oneˇ");
+
+    cx.update_editor(|editor, cx| editor.toggle_read_only(&ToggleReadOnly, cx));
+    cx.simulate_input("!");
+    cx.assert_editor_state("one!ˇ");
+}
+
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        oneˇ
+        two
+        three
+        four
+        five
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(3, 0)..Point::new(3, 0)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        assert_eq!(editor.bookmarked_rows(cx), vec![0, 3]);
+    });
+
+    cx.update_editor(|editor, cx| editor.go_to_next_bookmark(&GoToNextBookmark, cx));
+    cx.assert_editor_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+        five
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_prev_bookmark(&GoToPrevBookmark, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        two
+        three
+        ˇfour
+        five
+    "});
+
+    // Bookmarks follow their rows as text is inserted above them.
+    cx.set_selections_state(indoc! {"
+        ˇone
+        two
+        three
+        four
+        five
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("zero\n", cx);
+        assert_eq!(editor.bookmarked_rows(cx), vec![1, 4]);
+
+        // Toggling an existing bookmark removes it.
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(4, 2)..Point::new(4, 2)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, cx);
+        assert_eq!(editor.bookmarked_rows(cx), vec![1]);
+
+        editor.clear_bookmarks(&ClearBookmarks, cx);
+        assert!(editor.bookmarked_rows(cx).is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_assert_inlay_hints(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("let x = 1;\nlet yˇ = x;\n");
+    cx.assert_inlay_hints("let x = 1;\nlet y = x;\n");
+
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor.splice_inlays(
+            Vec::new(),
+            vec![
+                Inlay {
+                    id: InlayId::Hint(0),
+                    position: snapshot.anchor_after(5),
+                    text: ": i32".into(),
+                },
+                Inlay {
+                    id: InlayId::Hint(1),
+                    position: snapshot.anchor_after(16),
+                    text: ": i32".into(),
+                },
+                Inlay {
+                    id: InlayId::Suggestion(2),
+                    position: snapshot.anchor_after(20),
+                    text: "not a hint".into(),
+                },
+            ],
+            cx,
+        );
+    });
+    cx.assert_inlay_hints("let x«: i32» = 1;\nlet y«: i32» = x;\n");
+}
+
+#[gpui::test]
+async fn test_fold_test_helpers(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇfn a() {
+            b();
+        }
+        fn c() {
+            d();
+        }
+    "});
+    cx.assert_folds(indoc! {"
+        fn a() {
+            b();
+        }
+        fn c() {
+            d();
+        }
+    "});
+
+    cx.fold_at(indoc! {"
+        fn a() {«
+            b();
+        »}
+        fn c() {«
+            d();
+        »}
+    "});
+    cx.assert_folds(indoc! {"
+        fn a() {«
+            b();
+        »}
+        fn c() {«
+            d();
+        »}
+    "});
+
+    cx.update_editor(|editor, cx| editor.unfold_ranges(&[0..9], true, false, cx));
+    cx.assert_folds(indoc! {"
+        fn a() {
+            b();
+        }
+        fn c() {«
+            d();
+        »}
+    "});
+}
+
+#[gpui::test]
+async fn test_set_diff_base_for_excerpt(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new_multibuffer(cx, ["«one\ntwo\n»", "«three\nfour\n»"]);
+    let diff_base_for_excerpt = |cx: &mut EditorTestContext, excerpt_index: usize| {
+        cx.update_editor(|editor, cx| {
+            let buffer_id = editor.buffer().read(cx).excerpt_buffer_ids()[excerpt_index];
+            editor
+                .diff_map
+                .diff_bases
+                .get(&buffer_id)
+                .and_then(|state| state.change_set.read(cx).base_text_string(cx))
+        })
+    };
+
+    cx.set_diff_base_for_excerpt(1, "three\n4\n");
+    assert_eq!(diff_base_for_excerpt(&mut cx, 0), None);
+    assert_eq!(
+        diff_base_for_excerpt(&mut cx, 1).as_deref(),
+        Some("three\n4\n")
+    );
+
+    cx.set_diff_base_for_excerpt(1, "3\nfour\n");
+    cx.set_diff_base_for_excerpt(0, "one\n");
+    assert_eq!(diff_base_for_excerpt(&mut cx, 0).as_deref(), Some("one\n"));
+    assert_eq!(
+        diff_base_for_excerpt(&mut cx, 1).as_deref(),
+        Some("3\nfour\n")
+    );
+}
+
+#[gpui::test]
+async fn test_simulate_copy_and_paste(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("«oneˇ» two «threeˇ»");
+    assert_eq!(cx.simulate_copy(), "one\nthree");
+
+    cx.set_state("«oneˇ» two");
+    assert_eq!(cx.simulate_copy(), "one");
+    cx.set_selections_state("one twoˇ");
+    cx.simulate_paste();
+    cx.assert_editor_state("one twooneˇ");
+}
+
+#[gpui::test]
+async fn test_assert_editor_snapshot(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        fn main() {
+            let «valueˇ» = 1;
+            println!(\"{value}\");
+        }
+    "});
+    cx.fold_at(indoc! {"
+        fn main() {
+            let value = 1;«
+            println!(\"{value}\");»
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let range = snapshot.anchor_after(20)..snapshot.anchor_before(25);
+        editor.highlight_background::<DocumentHighlightRead>(
+            &[range],
+            |theme| theme.editor_document_highlight_read_background,
+            cx,
+        );
+    });
+    cx.assert_editor_snapshot("editor_snapshot_with_fold_and_highlight");
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
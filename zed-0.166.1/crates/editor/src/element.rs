@@ -221,6 +221,7 @@ impl EditorElement {
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
+        register_action(view, cx, Editor::transpose_lines);
         register_action(view, cx, Editor::rewrap);
         register_action(view, cx, Editor::cut);
         register_action(view, cx, Editor::kill_ring_cut);
@@ -359,22 +360,33 @@ impl EditorElement {
         register_action(view, cx, Editor::fold_selected_ranges);
         register_action(view, cx, Editor::show_completions);
         register_action(view, cx, Editor::toggle_code_actions);
+        register_action(view, cx, Editor::quick_fix);
         register_action(view, cx, Editor::open_excerpts);
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::open_proposed_changes_editor);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_tab_bar);
         register_action(view, cx, Editor::toggle_line_numbers);
+        register_action(view, cx, Editor::toggle_read_only);
+        register_action(view, cx, Editor::increase_tab_size);
+        register_action(view, cx, Editor::decrease_tab_size);
+        register_action(view, cx, Editor::reset_tab_size);
+        register_action(view, cx, Editor::toggle_bookmark);
+        register_action(view, cx, Editor::go_to_next_bookmark);
+        register_action(view, cx, Editor::go_to_prev_bookmark);
+        register_action(view, cx, Editor::clear_bookmarks);
         register_action(view, cx, Editor::toggle_relative_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, Editor::toggle_inline_completions);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, hover_popover::peek_definition);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
         register_action(view, cx, Editor::copy_highlight_json);
         register_action(view, cx, Editor::copy_permalink_to_line);
+        register_action(view, cx, Editor::copy_permalink_to_selection);
         register_action(view, cx, Editor::open_permalink_to_line);
         register_action(view, cx, Editor::copy_file_location);
         register_action(view, cx, Editor::toggle_git_blame);
@@ -458,6 +470,7 @@ impl EditorElement {
         register_action(view, cx, Editor::apply_all_diff_hunks);
         register_action(view, cx, Editor::apply_selected_diff_hunks);
         register_action(view, cx, Editor::open_active_item_in_terminal);
+        register_action(view, cx, Editor::run_selection_in_terminal);
         register_action(view, cx, Editor::reload_file);
         register_action(view, cx, Editor::spawn_nearest_task);
         register_action(view, cx, Editor::insert_uuid_v4);
@@ -1749,6 +1762,74 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_gutter_run_buttons(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            let Some(buttons) = editor.gutter_run_buttons.as_ref() else {
+                return Vec::new();
+            };
+
+            buttons
+                .positions
+                .iter()
+                .filter_map(|position| {
+                    let multibuffer_point = position.to_point(&snapshot.buffer_snapshot);
+                    let multibuffer_row = MultiBufferRow(multibuffer_point.row);
+                    let display_row = multibuffer_point.to_display_point(snapshot).row();
+                    if !range.contains(&display_row) {
+                        return None;
+                    }
+                    if snapshot.is_line_folded(multibuffer_row) {
+                        // Skip folded buttons, unless it's the starting line of a fold.
+                        if multibuffer_row
+                            .0
+                            .checked_sub(1)
+                            .map_or(false, |previous_row| {
+                                snapshot.is_line_folded(MultiBufferRow(previous_row))
+                            })
+                        {
+                            return None;
+                        }
+                    }
+
+                    // Rows with runnable tasks already show a run indicator.
+                    let (buffer, row_range) = snapshot
+                        .buffer_snapshot
+                        .buffer_line_for_row(multibuffer_row)?;
+                    if editor
+                        .tasks
+                        .contains_key(&(buffer.remote_id(), row_range.start.row))
+                    {
+                        return None;
+                    }
+
+                    let button = editor.render_gutter_run_button(display_row, *position)?;
+                    let button = prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        rows_with_hunk_bounds,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_code_actions_indicator(
         &self,
@@ -2935,7 +3016,11 @@ impl EditorElement {
                     anchored()
                         .position(position)
                         .child(context_menu)
-                        .anchor(AnchorCorner::TopLeft)
+                        .anchor(if i18n::is_rtl() {
+                            AnchorCorner::TopRight
+                        } else {
+                            AnchorCorner::TopLeft
+                        })
                         .snap_to_window_with_margin(px(8.)),
                 )
                 .with_priority(1)
@@ -5780,7 +5865,7 @@ impl Element for EditorElement {
                     }
 
                     let test_indicators = if gutter_settings.runnables {
-                        self.layout_run_indicators(
+                        let mut test_indicators = self.layout_run_indicators(
                             line_height,
                             start_row..end_row,
                             scroll_pixel_position,
@@ -5789,7 +5874,18 @@ impl Element for EditorElement {
                             &rows_with_hunk_bounds,
                             &snapshot,
                             cx,
-                        )
+                        );
+                        test_indicators.extend(self.layout_gutter_run_buttons(
+                            line_height,
+                            start_row..end_row,
+                            scroll_pixel_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &rows_with_hunk_bounds,
+                            &snapshot,
+                            cx,
+                        ));
+                        test_indicators
                     } else {
                         Vec::new()
                     };
@@ -3,7 +3,7 @@ use crate::{
     hover_links::{InlayHighlight, RangeInEditor},
     scroll::ScrollAmount,
     Anchor, AnchorRangeExt, DisplayPoint, DisplayRow, Editor, EditorSettings, EditorSnapshot,
-    Hover, RangeToAnchorExt,
+    GotoDefinitionKind, Hover, PeekDefinition, RangeToAnchorExt,
 };
 use gpui::{
     div, px, AnyElement, AsyncWindowContext, FontWeight, Hsla, InteractiveElement, IntoElement,
@@ -11,7 +11,7 @@ use gpui::{
     StyleRefinement, Styled, Task, TextStyleRefinement, View, ViewContext,
 };
 use itertools::Itertools;
-use language::{Diagnostic, DiagnosticEntry, Language, LanguageRegistry};
+use language::{Diagnostic, DiagnosticEntry, Language, LanguageRegistry, OffsetRangeExt, Point};
 use lsp::DiagnosticSeverity;
 use markdown::{Markdown, MarkdownStyle};
 use multi_buffer::ToOffset;
@@ -30,6 +30,11 @@ pub const MIN_POPOVER_CHARACTER_WIDTH: f32 = 20.;
 pub const MIN_POPOVER_LINE_HEIGHT: Pixels = px(4.);
 pub const HOVER_POPOVER_GAP: Pixels = px(10.);
 
+/// How many rows around a definition are shown when peeking at it.
+const PEEK_DEFINITION_CONTEXT_ROWS: u32 = 2;
+/// The most rows of a definition shown when peeking at it.
+const PEEK_DEFINITION_MAX_ROWS: u32 = 20;
+
 /// Bindable action which uses the most recent selection head to trigger a hover
 pub fn hover(editor: &mut Editor, _: &Hover, cx: &mut ViewContext<Editor>) {
     let head = editor.selections.newest_anchor().head();
@@ -169,6 +174,102 @@ pub fn hover_at_inlay(editor: &mut Editor, inlay_hover: InlayHover, cx: &mut Vie
     }
 }
 
+/// Shows the definition of the symbol under the newest selection head in a
+/// popover, along with a few surrounding rows, without navigating away.
+pub fn peek_definition(editor: &mut Editor, _: &PeekDefinition, cx: &mut ViewContext<Editor>) {
+    if editor.pending_rename.is_some() {
+        return;
+    }
+    let head = editor.selections.newest_anchor().head();
+    let Some((buffer, buffer_position)) = editor.buffer.read(cx).text_anchor_for_position(head, cx)
+    else {
+        return;
+    };
+    let Some((excerpt_id, _, _)) = editor.buffer().read(cx).excerpt_containing(head, cx) else {
+        return;
+    };
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(definitions) = editor.semantics_provider.clone().and_then(|provider| {
+        provider.definitions(&buffer, buffer_position, GotoDefinitionKind::Symbol, cx)
+    }) else {
+        return;
+    };
+
+    hide_hover(editor, cx);
+    let task = cx.spawn(|this, mut cx| {
+        async move {
+            let Some(definition) = definitions.await?.into_iter().next() else {
+                return anyhow::Ok(());
+            };
+
+            let (block, language, origin) = this.update(&mut cx, |this, cx| {
+                let target = definition.target.buffer.read(cx);
+                let snapshot = target.snapshot();
+                let range = definition.target.range.to_point(&snapshot);
+                let start_row = range.start.row.saturating_sub(PEEK_DEFINITION_CONTEXT_ROWS);
+                let end_row = (range.end.row + PEEK_DEFINITION_CONTEXT_ROWS)
+                    .min(range.start.row + PEEK_DEFINITION_MAX_ROWS)
+                    .min(snapshot.max_point().row);
+                let text = snapshot
+                    .text_for_range(
+                        Point::new(start_row, 0)..Point::new(end_row, snapshot.line_len(end_row)),
+                    )
+                    .collect::<String>();
+                let path = target.file().map_or_else(
+                    || "untitled".to_string(),
+                    |file| file.path().to_string_lossy().to_string(),
+                );
+                let language = target.language().cloned();
+                let block = HoverBlock {
+                    text: format!(
+                        "`{path}:{}`\n\n```{}\n{text}\n```",
+                        range.start.row + 1,
+                        language
+                            .as_ref()
+                            .map_or(String::new(), |language| language.name().to_string()),
+                    ),
+                    kind: project::HoverBlockKind::Markdown,
+                };
+
+                let buffer_snapshot = this.buffer.read(cx).snapshot(cx);
+                let origin = definition.origin.and_then(|origin| {
+                    let start =
+                        buffer_snapshot.anchor_in_excerpt(excerpt_id, origin.range.start)?;
+                    let end = buffer_snapshot.anchor_in_excerpt(excerpt_id, origin.range.end)?;
+                    Some(start..end)
+                });
+                (block, language, origin)
+            })?;
+
+            let language_registry = project.update(&mut cx, |p, _| p.languages().clone())?;
+            let parsed_content =
+                parse_blocks(&[block], &language_registry, language, &mut cx).await;
+            let scroll_handle = ScrollHandle::new();
+            let peek_popover = InfoPopover {
+                symbol_range: RangeInEditor::Text(origin.unwrap_or(head..head)),
+                parsed_content,
+                scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+                scroll_handle,
+                // Keep the popover open as the mouse moves, as with keyboard hovers.
+                keyboard_grace: Rc::new(RefCell::new(true)),
+                anchor: Some(head),
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.hover_state.info_popovers = vec![peek_popover];
+                cx.notify();
+            })?;
+
+            anyhow::Ok(())
+        }
+        .log_err()
+    });
+
+    editor.hover_state.info_task = Some(task);
+}
+
 /// Hides the type information popup.
 /// Triggered by the `Hover` action when the cursor is not over a symbol or when the
 /// selections changed.
@@ -1201,6 +1302,62 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_peek_definition(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn test() { do_wˇork(); }
+            fn do_work() { test(); }
+        "});
+        let symbol_range = cx.lsp_range(indoc! {"
+            fn test() { «do_work»(); }
+            fn do_work() { test(); }
+        "});
+        let target_range = cx.lsp_range(indoc! {"
+            fn test() { do_work(); }
+            fn «do_work»() { test(); }
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::GotoDefinition, _, _>(move |url, _, _| async move {
+                Ok(Some(lsp::GotoDefinitionResponse::Link(vec![
+                    lsp::LocationLink {
+                        origin_selection_range: Some(symbol_range),
+                        target_uri: url.clone(),
+                        target_range,
+                        target_selection_range: target_range,
+                    },
+                ])))
+            });
+
+        cx.dispatch_action(PeekDefinition);
+        requests.next().await;
+        cx.condition(|editor, _| editor.hover_state.visible()).await;
+        cx.background_executor.run_until_parked();
+
+        // Peeking doesn't move the cursor.
+        cx.assert_editor_state(indoc! {"
+            fn test() { do_wˇork(); }
+            fn do_work() { test(); }
+        "});
+        cx.editor(|editor, cx| {
+            let rendered_text = editor.hover_state.info_popovers[0].get_rendered_text(cx);
+            assert!(
+                rendered_text.contains("fn do_work() { test(); }"),
+                "{rendered_text:?} doesn't show the definition"
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_empty_hovers_filtered(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
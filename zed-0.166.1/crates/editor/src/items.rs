@@ -652,6 +652,13 @@ impl Item for Editor {
                     .italic(params.preview)
                     .strikethrough(was_deleted),
             )
+            .when(self.read_only, |this| {
+                this.child(
+                    Icon::new(IconName::FileLock)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
             .when_some(description, |this, description| {
                 this.child(
                     Label::new(description)
@@ -1,13 +1,17 @@
 use crate::actions::FormatSelections;
 use crate::{
-    actions::Format, selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine, Cut,
-    DisplayPoint, DisplaySnapshot, Editor, EditorMode, FindAllReferences, GoToDeclaration,
-    GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFileManager,
-    SelectMode, ToDisplayPoint, ToggleCodeActions,
+    actions::Format, selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine,
+    CopyPermalinkToSelection, Cut, DisplayPoint, DisplaySnapshot, Editor, EditorMode,
+    EditorSettings, FindAllReferences, GoToDeclaration, GoToDefinition, GoToDiagnostic,
+    GoToImplementation, GoToPrevDiagnostic, GoToTypeDefinition, Paste, PeekDefinition, QuickFix,
+    Rename, RevealInFileManager, RunSelectionInTerminal, SelectMode, ToDisplayPoint,
+    ToggleCodeActions,
 };
 use gpui::prelude::FluentBuilder;
-use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
-use std::ops::Range;
+use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext, WindowContext};
+use i18n::t;
+use settings::Settings;
+use std::{ops::Range, rc::Rc};
 use text::PointUtf16;
 use workspace::OpenInTerminal;
 
@@ -24,6 +28,51 @@ pub enum MenuPosition {
     },
 }
 
+/// Where items added with [`Editor::add_context_menu_items`] appear in the
+/// default context menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuPlacement {
+    /// Above the default items.
+    Start,
+    /// Below the default items.
+    End,
+}
+
+#[derive(Clone)]
+pub(crate) struct ContextMenuItems {
+    pub(crate) placement: ContextMenuPlacement,
+    pub(crate) build:
+        Rc<dyn Fn(ui::ContextMenu, DisplayPoint, &mut WindowContext) -> ui::ContextMenu>,
+}
+
+/// Adds the items added to the editor at `placement`, separated from the
+/// default items.
+fn add_context_menu_items(
+    mut menu: ui::ContextMenu,
+    items: &[ContextMenuItems],
+    placement: ContextMenuPlacement,
+    point: DisplayPoint,
+    cx: &mut WindowContext,
+) -> ui::ContextMenu {
+    let mut items = items
+        .iter()
+        .filter(|items| items.placement == placement)
+        .peekable();
+    if items.peek().is_none() {
+        return menu;
+    }
+    if placement == ContextMenuPlacement::End {
+        menu = menu.separator();
+    }
+    for items in items {
+        menu = (items.build)(menu, point, cx);
+    }
+    if placement == ContextMenuPlacement::Start {
+        menu = menu.separator();
+    }
+    menu
+}
+
 pub struct MouseContextMenu {
     pub(crate) position: MenuPosition,
     pub(crate) context_menu: View<ui::ContextMenu>,
@@ -135,6 +184,11 @@ pub fn deploy_context_menu(
         let display_map = editor.selections.display_map(cx);
         let buffer = &editor.snapshot(cx).buffer_snapshot;
         let anchor = buffer.anchor_before(point.to_point(&display_map));
+        let has_diagnostics = buffer.has_diagnostics();
+        let has_diagnostic = buffer
+            .diagnostics_in_range::<_, usize>(anchor..anchor, false)
+            .next()
+            .is_some();
         if !display_ranges(&display_map, &editor.selections).any(|r| r.contains(&point)) {
             // Move the cursor to the clicked location so that dispatched actions make sense
             editor.change_selections(None, cx, |s| {
@@ -146,9 +200,9 @@ pub fn deploy_context_menu(
         let focus = cx.focused();
         let has_reveal_target = editor.target_file(cx).is_some();
         let reveal_in_finder_label = if cfg!(target_os = "macos") {
-            "文件管理器打开"
+            t!("context_menu.reveal_in_finder")
         } else {
-            "文件管理器打开"
+            t!("context_menu.reveal_in_file_manager")
         };
         let has_selections = editor
             .selections
@@ -156,30 +210,79 @@ pub fn deploy_context_menu(
             .into_iter()
             .any(|s| !s.is_empty());
 
-        ui::ContextMenu::build(cx, |menu, _cx| {
+        let items = editor.context_menu_items.clone();
+        let hidden_actions = EditorSettings::get_global(cx)
+            .hidden_context_menu_actions
+            .clone();
+
+        ui::ContextMenu::build(cx, |menu, cx| {
             let builder = menu
                 .on_blur_subscription(Subscription::new(|| {}))
-                .action("转到定义", Box::new(GoToDefinition))
-                .action("转到声明", Box::new(GoToDeclaration))
-                .action("转到类型定义", Box::new(GoToTypeDefinition))
-                .action("转到实现", Box::new(GoToImplementation))
-                .action("查找所有引用", Box::new(FindAllReferences))
+                .rtl(i18n::is_rtl())
+                .map(|menu| {
+                    add_context_menu_items(menu, &items, ContextMenuPlacement::Start, point, cx)
+                })
+                .action(
+                    t!("context_menu.go_to_definition"),
+                    Box::new(GoToDefinition),
+                )
+                .action(t!("context_menu.peek_definition"), Box::new(PeekDefinition))
+                .action(
+                    t!("context_menu.go_to_declaration"),
+                    Box::new(GoToDeclaration),
+                )
+                .action(
+                    t!("context_menu.go_to_type_definition"),
+                    Box::new(GoToTypeDefinition),
+                )
+                .action(
+                    t!("context_menu.go_to_implementation"),
+                    Box::new(GoToImplementation),
+                )
+                .action(
+                    t!("context_menu.find_all_references"),
+                    Box::new(FindAllReferences),
+                )
                 .separator()
-                .action("重命名字符", Box::new(Rename))
-                .action("格式化", Box::new(Format))
+                .action(t!("context_menu.rename_symbol"), Box::new(Rename))
+                .action(t!("context_menu.format"), Box::new(Format))
                 .when(has_selections, |cx| {
-                    cx.action("格式化所选内容", Box::new(FormatSelections))
+                    cx.action(
+                        t!("context_menu.format_selections"),
+                        Box::new(FormatSelections),
+                    )
                 })
                 .action(
-                    "代码操作",
+                    t!("context_menu.code_actions"),
                     Box::new(ToggleCodeActions {
                         deployed_from_indicator: None,
                     }),
                 )
+                .map(|builder| {
+                    let label = t!("context_menu.quick_fix");
+                    if has_diagnostic {
+                        builder.action(label, Box::new(QuickFix))
+                    } else {
+                        builder.disabled_action(label, Box::new(QuickFix))
+                    }
+                })
+                .map(|builder| {
+                    let next_label = t!("context_menu.go_to_next_diagnostic");
+                    let previous_label = t!("context_menu.go_to_previous_diagnostic");
+                    if has_diagnostics {
+                        builder
+                            .action(next_label, Box::new(GoToDiagnostic))
+                            .action(previous_label, Box::new(GoToPrevDiagnostic))
+                    } else {
+                        builder
+                            .disabled_action(next_label, Box::new(GoToDiagnostic))
+                            .disabled_action(previous_label, Box::new(GoToPrevDiagnostic))
+                    }
+                })
                 .separator()
-                .action("剪切", Box::new(Cut))
-                .action("复制", Box::new(Copy))
-                .action("粘贴", Box::new(Paste))
+                .action(t!("context_menu.cut"), Box::new(Cut))
+                .action(t!("context_menu.copy"), Box::new(Copy))
+                .action(t!("context_menu.paste"), Box::new(Paste))
                 .separator()
                 .map(|builder| {
                     if has_reveal_target {
@@ -189,8 +292,31 @@ pub fn deploy_context_menu(
                             .disabled_action(reveal_in_finder_label, Box::new(RevealInFileManager))
                     }
                 })
-                .action("在终端中打开", Box::new(OpenInTerminal))
-                .action("复制永久链接", Box::new(CopyPermalinkToLine));
+                .action(
+                    t!("context_menu.open_in_terminal"),
+                    Box::new(OpenInTerminal),
+                )
+                .map(|builder| {
+                    let label = t!("context_menu.run_selection_in_terminal");
+                    if has_selections {
+                        builder.action(label, Box::new(RunSelectionInTerminal))
+                    } else {
+                        builder.disabled_action(label, Box::new(RunSelectionInTerminal))
+                    }
+                })
+                .action(
+                    t!("context_menu.copy_permalink"),
+                    Box::new(CopyPermalinkToLine),
+                )
+                .when(has_selections, |builder| {
+                    builder.action(
+                        t!("context_menu.copy_permalink_to_selection"),
+                        Box::new(CopyPermalinkToSelection),
+                    )
+                });
+            let builder =
+                add_context_menu_items(builder, &items, ContextMenuPlacement::End, point, cx)
+                    .hide_actions(&hidden_actions);
             match focus {
                 Some(focus) => builder.context(focus),
                 None => builder,
@@ -218,6 +344,7 @@ mod tests {
     use super::*;
     use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
     use indoc::indoc;
+    use settings::SettingsStore;
 
     #[gpui::test]
     async fn test_mouse_context_menu(cx: &mut gpui::TestAppContext) {
@@ -254,4 +381,150 @@ mod tests {
         "});
         cx.editor(|editor, _app| assert!(editor.mouse_context_menu.is_some()));
     }
+
+    #[gpui::test]
+    async fn test_mouse_context_menu_items(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(lsp::ServerCapabilities::default(), cx).await;
+
+        cx.set_state(indoc! {"
+            fn teˇst() {
+                do_work();
+            }
+        "});
+        cx.deploy_context_menu_at(indoc! {"
+            fn test() {
+                do_wˇork();
+            }
+        "});
+        cx.assert_editor_state(indoc! {"
+            fn test() {
+                do_wˇork();
+            }
+        "});
+        cx.assert_context_menu_items(&[
+            "转到定义",
+            "预览定义",
+            "转到声明",
+            "转到类型定义",
+            "转到实现",
+            "查找所有引用",
+            "重命名字符",
+            "格式化",
+            "代码操作",
+            "快速修复",
+            "转到下一个错误",
+            "转到上一个错误",
+            "剪切",
+            "复制",
+            "粘贴",
+            "文件管理器打开",
+            "在终端中打开",
+            "在终端中运行选择",
+            "复制永久链接",
+        ]);
+
+        cx.set_state(indoc! {"
+            fn «testˇ»() {
+                do_work();
+            }
+        "});
+        cx.deploy_context_menu_at(indoc! {"
+            fn teˇst() {
+                do_work();
+            }
+        "});
+        cx.assert_context_menu_items(&[
+            "转到定义",
+            "预览定义",
+            "转到声明",
+            "转到类型定义",
+            "转到实现",
+            "查找所有引用",
+            "重命名字符",
+            "格式化",
+            "格式化所选内容",
+            "代码操作",
+            "快速修复",
+            "转到下一个错误",
+            "转到上一个错误",
+            "剪切",
+            "复制",
+            "粘贴",
+            "文件管理器打开",
+            "在终端中打开",
+            "在终端中运行选择",
+            "复制永久链接",
+            "复制选区永久链接",
+        ]);
+    }
+
+    #[gpui::test]
+    async fn test_hidden_context_menu_actions(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.hidden_context_menu_actions = Some(vec![
+                        "editor::Cut".into(),
+                        "editor::Copy".into(),
+                        "editor::Paste".into(),
+                        "workspace::OpenInTerminal".into(),
+                        "editor::CopyPermalinkToLine".into(),
+                    ]);
+                });
+            });
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(lsp::ServerCapabilities::default(), cx).await;
+        cx.set_state("fn teˇst() {}");
+        cx.deploy_context_menu_at("fn teˇst() {}");
+        cx.assert_context_menu_items(&[
+            "转到定义",
+            "预览定义",
+            "转到声明",
+            "转到类型定义",
+            "转到实现",
+            "查找所有引用",
+            "重命名字符",
+            "格式化",
+            "代码操作",
+            "快速修复",
+            "转到下一个错误",
+            "转到上一个错误",
+            "文件管理器打开",
+            "在终端中运行选择",
+        ]);
+    }
+
+    #[gpui::test]
+    async fn test_added_context_menu_items(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(lsp::ServerCapabilities::default(), cx).await;
+        cx.update_editor(|editor, _| {
+            editor.add_context_menu_items(ContextMenuPlacement::Start, |menu, _, _| {
+                menu.entry("First", None, |_| {})
+            });
+            editor.add_context_menu_items(ContextMenuPlacement::End, |menu, _, _| {
+                menu.entry("Last", None, |_| {})
+            });
+        });
+
+        cx.set_state("fn teˇst() {}");
+        cx.deploy_context_menu_at("fn teˇst() {}");
+        let labels = cx.editor(|editor, cx| {
+            editor
+                .mouse_context_menu
+                .as_ref()
+                .unwrap()
+                .context_menu
+                .read(cx)
+                .entry_labels()
+        });
+        assert_eq!(labels.first().map(|label| label.as_ref()), Some("First"));
+        assert_eq!(labels.get(1).map(|label| label.as_ref()), Some("转到定义"));
+        assert_eq!(labels.last().map(|label| label.as_ref()), Some("Last"));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
-    display_map::ToDisplayPoint, AnchorRangeExt, Autoscroll, DiffRowHighlight, DisplayPoint,
-    Editor, MultiBuffer, RowExt,
+    display_map::{Crease, ToDisplayPoint},
+    mouse_context_menu, AnchorRangeExt, Autoscroll, Copy, DiffRowHighlight, DisplayPoint, Editor,
+    MultiBuffer, Paste, RowExt,
 };
 use collections::BTreeMap;
 use futures::Future;
@@ -11,13 +12,13 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{Buffer, BufferSnapshot, LanguageRegistry};
-use multi_buffer::{ExcerptRange, ToPoint};
+use multi_buffer::{ExcerptRange, ToOffset, ToPoint};
 use parking_lot::RwLock;
-use project::{FakeFs, Project};
+use project::{buffer_store::BufferChangeSet, FakeFs, Project};
 use std::{
     any::TypeId,
     ops::{Deref, DerefMut, Range},
-    path::Path,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -27,7 +28,7 @@ use std::{
 use ui::Context;
 use util::{
     assert_set_eq,
-    test::{generate_marked_text, marked_text_ranges},
+    test::{generate_marked_text, marked_text_ranges, marked_text_ranges_by, TextRangeMarker},
 };
 
 use super::{build_editor, build_editor_with_project};
@@ -228,6 +229,23 @@ impl EditorTestContext {
         self.cx.dispatch_keystroke(self.window, keystroke);
     }
 
+    /// Copies the current selections by dispatching the `Copy` action, and returns
+    /// the text that was written to the clipboard.
+    pub fn simulate_copy(&mut self) -> String {
+        self.cx.dispatch_action(Copy);
+        self.cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .expect("nothing was copied to the clipboard")
+    }
+
+    /// Pastes the clipboard's contents into the editor by dispatching the `Paste`
+    /// action.
+    pub fn simulate_paste(&mut self) {
+        self.cx.dispatch_action(Paste);
+        self.cx.run_until_parked();
+    }
+
     pub fn run_until_parked(&mut self) {
         self.cx.background_executor.run_until_parked();
     }
@@ -271,6 +289,72 @@ impl EditorTestContext {
         })
     }
 
+    /// Opens the editor's right-click context menu at the position marked with `ˇ`
+    /// and returns the menu that was built.
+    pub fn deploy_context_menu_at(&mut self, marked_text: &str) -> View<ui::ContextMenu> {
+        let point = self.display_point(marked_text);
+        self.update_editor(|editor, cx| {
+            mouse_context_menu::deploy_context_menu(editor, None, point, cx);
+            editor
+                .mouse_context_menu
+                .as_ref()
+                .map(|menu| menu.context_menu.clone())
+                .expect("context menu was not deployed")
+        })
+    }
+
+    /// Asserts that the editor's open context menu contains exactly the given entries.
+    #[track_caller]
+    pub fn assert_context_menu_items(&mut self, expected: &[&str]) {
+        let actual = self.editor(|editor, cx| {
+            editor
+                .mouse_context_menu
+                .as_ref()
+                .map(|menu| menu.context_menu.read(cx).entry_labels())
+                .expect("no context menu is open")
+                .into_iter()
+                .map(|label| label.to_string())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(actual, expected, "{}", self.assertion_context());
+    }
+
+    /// Folds the ranges of the buffer marked with `«` and `»`.
+    pub fn fold_at(&mut self, marked_text: &str) {
+        let ranges = self.ranges(marked_text);
+        self.update_editor(|editor, cx| {
+            let placeholder = editor.display_map.read(cx).fold_placeholder.clone();
+            let creases = ranges
+                .into_iter()
+                .map(|range| Crease::simple(range, placeholder.clone()))
+                .collect();
+            editor.fold_creases(creases, false, cx);
+        });
+    }
+
+    /// Make an assertion about the editor's folds using a string containing the
+    /// buffer text, with each folded range surrounded by `«` and `»`.
+    #[track_caller]
+    pub fn assert_folds(&mut self, marked_text: &str) {
+        let expected_ranges = self.ranges(marked_text);
+        let actual_ranges = self.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            snapshot
+                .folds_in_range(0..snapshot.buffer_snapshot.len())
+                .map(|fold| fold.range.to_offset(&snapshot.buffer_snapshot))
+                .collect::<Vec<_>>()
+        });
+        if actual_ranges != expected_ranges {
+            let buffer_text = self.buffer_text();
+            pretty_assertions::assert_eq!(
+                generate_marked_text(&buffer_text, &actual_ranges, false),
+                generate_marked_text(&buffer_text, &expected_ranges, false),
+                "{}Editor has unexpected folds",
+                self.assertion_context(),
+            );
+        }
+    }
+
     // Returns anchors for the current buffer using `«` and `»`
     pub fn text_anchor_range(&mut self, marked_text: &str) -> Range<language::Anchor> {
         let ranges = self.ranges(marked_text);
@@ -290,6 +374,32 @@ impl EditorTestContext {
         self.cx.run_until_parked();
     }
 
+    /// Sets the diff base of the buffer backing the excerpt at `excerpt_index`, for
+    /// editors whose buffers don't belong to a repository, such as the ones built by
+    /// [`Self::new_multibuffer`].
+    pub fn set_diff_base_for_excerpt(&mut self, excerpt_index: usize, diff_base: &str) {
+        self.update_editor(|editor, cx| {
+            let buffer = editor.buffer().read(cx);
+            let buffer_id = buffer.excerpt_buffer_ids()[excerpt_index];
+            let buffer = buffer
+                .buffer(buffer_id)
+                .expect("no buffer for excerpt")
+                .read(cx)
+                .text_snapshot();
+            if let Some(diff_base_state) = editor.diff_map.diff_bases.get(&buffer_id) {
+                diff_base_state.change_set.update(cx, |change_set, cx| {
+                    let _ = change_set.set_base_text(diff_base.to_string(), buffer, cx);
+                });
+            } else {
+                let change_set = cx.new_model(|cx| {
+                    BufferChangeSet::new_with_base_text(diff_base.to_string(), buffer, cx)
+                });
+                editor.diff_map.add_change_set(change_set, cx);
+            }
+        });
+        self.cx.run_until_parked();
+    }
+
     /// Change the editor's text and selections using a string containing
     /// embedded range markers that represent the ranges and directions of
     /// each selection.
@@ -430,6 +540,67 @@ impl EditorTestContext {
         generate_marked_text(self.buffer_text().as_str(), &self.editor_selections(), true)
     }
 
+    /// Compares the editor's text, selections, folds, and background highlights
+    /// against the golden file `test_data/golden/{name}.txt`. Run the test with
+    /// `BLESS=true` to write the current state to the golden file instead.
+    #[track_caller]
+    pub fn assert_editor_snapshot(&mut self, name: &str) {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("test_data");
+        path.push("golden");
+        path.push(format!("{name}.txt"));
+
+        let actual_snapshot = self.editor_snapshot();
+        let bless = std::env::var("BLESS")
+            .map(|val| val.to_ascii_lowercase() == "true")
+            .unwrap_or(false);
+        if bless {
+            std::fs::create_dir_all(path.parent().unwrap())
+                .expect("could not create golden test data directory");
+            std::fs::write(&path, actual_snapshot).expect("could not write golden data");
+        } else {
+            let expected_snapshot = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+                panic!(
+                    "could not read golden file at {path:?}. Did you run the test with BLESS=true?"
+                )
+            });
+            pretty_assertions::assert_eq!(
+                actual_snapshot,
+                expected_snapshot,
+                "{}Editor state doesn't match golden file {path:?}",
+                self.assertion_context(),
+            );
+        }
+    }
+
+    fn editor_snapshot(&mut self) -> String {
+        let buffer_text = self.buffer_text();
+        let editor_state = self.editor_state();
+        let (folds, highlights) = self.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            let folds = snapshot
+                .folds_in_range(0..snapshot.buffer_snapshot.len())
+                .map(|fold| fold.range.to_offset(&snapshot.buffer_snapshot))
+                .collect::<Vec<_>>();
+            let mut highlights = editor
+                .background_highlights
+                .values()
+                .flat_map(|(_, ranges)| ranges.iter())
+                .map(|range| range.to_offset(&snapshot.buffer_snapshot))
+                .collect::<Vec<_>>();
+            highlights.sort_by_key(|range| (range.start, range.end));
+            highlights.dedup();
+            (folds, highlights)
+        });
+
+        format!(
+            "# text and selections\n{}\n# folds\n{}\n# background highlights\n{}\n",
+            editor_state,
+            generate_marked_text(&buffer_text, &folds, false),
+            generate_marked_text(&buffer_text, &highlights, false),
+        )
+    }
+
     #[track_caller]
     pub fn assert_editor_background_highlights<Tag: 'static>(&mut self, marked_text: &str) {
         let expected_ranges = self.ranges(marked_text);
@@ -461,6 +632,38 @@ impl EditorTestContext {
         assert_set_eq!(actual_ranges, expected_ranges);
     }
 
+    /// Make an assertion about the editor's visible inlay hints using the buffer
+    /// text with each hint's label inserted at its position, surrounded by `«`
+    /// and `»`.
+    ///
+    /// ```text
+    /// let x«: i32» = 1;
+    /// ```
+    #[track_caller]
+    pub fn assert_inlay_hints(&mut self, marked_text: &str) {
+        let actual_marked_text = self.update_editor(|editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut hints = editor
+                .visible_inlay_hints(cx)
+                .into_iter()
+                .map(|hint| (hint.position.to_offset(&snapshot), hint.text.to_string()))
+                .collect::<Vec<_>>();
+            hints.sort_by_key(|(offset, _)| *offset);
+
+            let mut text = snapshot.text();
+            for (offset, label) in hints.into_iter().rev() {
+                text.insert_str(offset, &format!("«{label}»"));
+            }
+            text
+        });
+        pretty_assertions::assert_eq!(
+            actual_marked_text,
+            marked_text,
+            "{}Editor has unexpected inlay hints",
+            self.assertion_context(),
+        );
+    }
+
     #[track_caller]
     pub fn assert_editor_selections(&mut self, expected_selections: Vec<Range<usize>>) {
         let expected_marked_text =
@@ -468,6 +671,35 @@ impl EditorTestContext {
         self.assert_selections(expected_selections, expected_marked_text)
     }
 
+    /// Make an assertion about the editor's selections, including their directions,
+    /// using distinct markers for each direction: forward selections are surrounded
+    /// by `«` and `»`, reversed selections by `‹` and `›`, and empty selections are
+    /// marked with `ˇ`.
+    #[track_caller]
+    pub fn assert_editor_selection_directions(&mut self, marked_text: &str) {
+        let (expected_text, ranges) = marked_text_ranges_by(
+            marked_text,
+            vec![
+                'ˇ'.into(),
+                ('«', '»').into(),
+                TextRangeMarker::ReverseRange('‹', '›'),
+            ],
+        );
+        pretty_assertions::assert_eq!(self.buffer_text(), expected_text, "unexpected buffer text");
+
+        let mut expected_selections = ranges.into_values().flatten().collect::<Vec<_>>();
+        expected_selections.sort_by_key(|range| range.start.min(range.end));
+        let actual_selections = self.editor_selections();
+        if expected_selections != actual_selections {
+            pretty_assertions::assert_eq!(
+                generate_directional_marked_text(&expected_text, &actual_selections),
+                generate_directional_marked_text(&expected_text, &expected_selections),
+                "{}Editor has unexpected selections",
+                self.assertion_context(),
+            );
+        }
+    }
+
     #[track_caller]
     fn editor_selections(&mut self) -> Vec<Range<usize>> {
         self.editor
@@ -505,6 +737,22 @@ impl EditorTestContext {
     }
 }
 
+fn generate_directional_marked_text(unmarked_text: &str, ranges: &[Range<usize>]) -> String {
+    let mut marked_text = unmarked_text.to_string();
+    for range in ranges.iter().rev() {
+        if range.start < range.end {
+            marked_text.insert(range.end, '»');
+            marked_text.insert(range.start, '«');
+        } else if range.start > range.end {
+            marked_text.insert(range.start, '›');
+            marked_text.insert(range.end, '‹');
+        } else {
+            marked_text.insert(range.start, 'ˇ');
+        }
+    }
+    marked_text
+}
+
 fn format_diff(
     text: String,
     actual_deletions: Vec<(u32, String)>,
@@ -0,0 +1,407 @@
+//! Localization of Zed's user interface.
+//!
+//! UI strings are referred to by key, such as `t!("menu.file.new")`, and looked up
+//! in the string table of the active locale. String tables are bundled with Zed in
+//! `assets/locales`, one JSON file per locale, and the active locale is chosen with
+//! the `locale` setting. When that setting is unset, the bundled locale closest to
+//! the operating system's locale is used instead.
+//!
+//! Users can override or add translations without recompiling by placing a file
+//! of the same shape in the `locales` directory of their config directory, such
+//! as `~/.config/zed/locales/zh-CN.json`. Keys missing from that file keep their
+//! bundled translation, and the file is reloaded whenever it changes.
+//!
+//! Keys that the active locale doesn't translate fall back to English, and then
+//! to the key itself. Each such key is logged once, so translators can find the
+//! gaps in a locale.
+//!
+//! Strings that include a count, such as "3 references", are translated with
+//! `t_plural!`. Their keys are suffixed with the plural form the count takes in
+//! the locale, `one` or `other`, and `{count}` in the translation is replaced
+//! with the count: `"references.one": "{count} reference"`.
+
+use std::sync::{Arc, LazyLock};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{AppContext, Global, SharedString, Task};
+use parking_lot::{Mutex, RwLock};
+use rust_embed::RustEmbed;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{watch_config_file, Settings, SettingsSources, SettingsStore};
+use util::{asset_str, ResultExt};
+
+#[derive(RustEmbed)]
+#[folder = "../../assets"]
+#[include = "locales/*"]
+#[exclude = "*.DS_Store"]
+struct LocaleAssets;
+
+/// The locale whose strings are shown until [`init`] picks the active locale.
+pub const DEFAULT_LOCALE: &str = "zh-CN";
+
+/// The locale whose strings are shown when the active locale doesn't translate
+/// a key.
+pub const FALLBACK_LOCALE: &str = "en";
+
+static STRINGS: LazyLock<RwLock<Translations>> = LazyLock::new(|| {
+    RwLock::new(Translations {
+        locale: DEFAULT_LOCALE.to_string(),
+        user: StringTable::default(),
+        bundled: StringTable::bundled(DEFAULT_LOCALE).unwrap_or_default(),
+        fallback: StringTable::bundled(FALLBACK_LOCALE).unwrap_or_default(),
+    })
+});
+
+/// The keys that were looked up without a translation in the active locale.
+static MISSING_KEYS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// Returns the translation of a UI string key in the active locale.
+///
+/// ```ignore
+/// MenuItem::action(t!("menu.file.new"), workspace::NewFile)
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::translate($key)
+    };
+}
+
+/// Returns the translation of a UI string key for `count` items in the active
+/// locale, with `{count}` replaced by the count.
+///
+/// ```ignore
+/// Label::new(t_plural!("editor.references", references.len()))
+/// ```
+#[macro_export]
+macro_rules! t_plural {
+    ($key:expr, $count:expr) => {
+        $crate::translate_plural($key, $count)
+    };
+}
+
+/// Registers the locale setting and keeps the active locale, and the user's
+/// translations for it, in sync with it.
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    LocaleSettings::register(cx);
+
+    let locale = LocaleSettings::get_global(cx).resolve();
+    set_locale(&locale);
+    watch_user_locale_file(fs.clone(), &locale, cx);
+    cx.set_global(ActiveLocale(locale));
+
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let locale = LocaleSettings::get_global(cx).resolve();
+        if locale != cx.global::<ActiveLocale>().0 {
+            set_locale(&locale);
+            watch_user_locale_file(fs.clone(), &locale, cx);
+            cx.set_global(ActiveLocale(locale));
+            cx.refresh();
+        }
+    })
+    .detach();
+}
+
+/// The locale whose strings are currently shown.
+///
+/// Observe this global to rebuild UI that isn't re-rendered automatically when
+/// the locale or its translations change, such as the application menus.
+pub struct ActiveLocale(pub String);
+
+impl Global for ActiveLocale {}
+
+/// Returns the translation of `key` in the active locale, falling back to its
+/// English translation and then to the key itself.
+pub fn translate(key: &str) -> SharedString {
+    let strings = STRINGS.read();
+    if let Some(translation) = strings.get(key) {
+        return translation;
+    }
+    if MISSING_KEYS.lock().insert(key.to_string()) {
+        log::warn!("missing translation for {key:?}");
+    }
+    strings
+        .fallback
+        .get(key)
+        .unwrap_or_else(|| SharedString::from(key.to_string()))
+}
+
+/// Returns the translation of `key` for `count` items, using the plural rules
+/// of whichever locale the translation is taken from.
+pub fn translate_plural(key: &str, count: usize) -> SharedString {
+    let strings = STRINGS.read();
+    let translation = strings
+        .get(&plural_key(key, &strings.locale, count))
+        .or_else(|| strings.get(&format!("{key}.other")));
+    let translation = match translation {
+        Some(translation) => translation,
+        None => {
+            drop(strings);
+            translate(&plural_key(key, FALLBACK_LOCALE, count))
+        }
+    };
+    translation.replace("{count}", &count.to_string()).into()
+}
+
+/// Returns whether the active locale is written right to left, in which case
+/// horizontal layouts, such as menus, should be mirrored.
+pub fn is_rtl() -> bool {
+    is_rtl_locale(&STRINGS.read().locale)
+}
+
+fn is_rtl_locale(locale: &str) -> bool {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    matches!(language, "ar" | "fa" | "he" | "ps" | "ur" | "yi")
+}
+
+/// Returns the key of the plural form that `count` takes in `locale`.
+fn plural_key(key: &str, locale: &str, count: usize) -> String {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let form = match language {
+        // Languages that don't inflect nouns for number.
+        "ja" | "ko" | "th" | "vi" | "zh" => "other",
+        "fr" if count <= 1 => "one",
+        _ if count == 1 => "one",
+        _ => "other",
+    };
+    format!("{key}.{form}")
+}
+
+fn set_locale(locale: &str) {
+    let mut strings = STRINGS.write();
+    strings.user = StringTable::default();
+    MISSING_KEYS.lock().clear();
+    match StringTable::bundled(locale) {
+        Some(bundled) => {
+            strings.locale = locale.to_string();
+            strings.bundled = bundled;
+        }
+        None => log::error!("no translations available for locale {locale:?}"),
+    }
+}
+
+/// Returns the names of the locales bundled with Zed, such as "zh-CN".
+pub fn bundled_locales() -> Vec<String> {
+    LocaleAssets::iter()
+        .filter_map(|path| {
+            path.strip_prefix("locales/")?
+                .strip_suffix(".json")
+                .map(ToString::to_string)
+        })
+        .collect()
+}
+
+/// Returns the translations bundled with Zed for `locale`, by key.
+pub fn bundled_translations(locale: &str) -> Option<HashMap<String, SharedString>> {
+    StringTable::bundled(locale).map(|strings| strings.0)
+}
+
+/// Returns the bundled locale that best matches `locale`, a locale identifier
+/// such as "zh-Hans-CN" or "en_US.UTF-8": the bundled locale with the same
+/// name, or failing that, the first one for the same language.
+fn closest_locale(locale: &str, bundled_locales: &[String]) -> Option<String> {
+    let normalize = |locale: &str| {
+        locale
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase()
+    };
+    let language = |locale: &str| locale.split('-').next().unwrap_or_default().to_string();
+
+    let locale = normalize(locale);
+    bundled_locales
+        .iter()
+        .find(|bundled| normalize(bundled) == locale)
+        .or_else(|| {
+            bundled_locales
+                .iter()
+                .find(|bundled| language(&normalize(bundled)) == language(&locale))
+        })
+        .cloned()
+}
+
+/// Returns the bundled locale closest to the operating system's locale, or
+/// [`FALLBACK_LOCALE`] if none is close.
+fn system_locale() -> &'static str {
+    static SYSTEM_LOCALE: LazyLock<String> = LazyLock::new(|| {
+        sys_locale::get_locale()
+            .and_then(|locale| closest_locale(&locale, &bundled_locales()))
+            .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+    });
+    &SYSTEM_LOCALE
+}
+
+/// Keeps the user's translations for `locale` loaded from the config
+/// directory, replacing the watcher of the previously active locale.
+fn watch_user_locale_file(fs: Arc<dyn Fs>, locale: &str, cx: &mut AppContext) {
+    let path = paths::locales_dir().join(format!("{locale}.json"));
+    let mut user_locale_file_rx = watch_config_file(cx.background_executor(), fs, path);
+    let task = cx.spawn(|cx| async move {
+        while let Some(content) = user_locale_file_rx.next().await {
+            let user = if content.trim().is_empty() {
+                StringTable::default()
+            } else {
+                match StringTable::parse(&content) {
+                    Ok(user) => user,
+                    Err(error) => {
+                        log::error!("failed to parse user translations: {error}");
+                        continue;
+                    }
+                }
+            };
+            STRINGS.write().user = user;
+            cx.update_global::<ActiveLocale, _>(|_, cx| cx.refresh())
+                .ok();
+        }
+    });
+    cx.set_global(UserLocaleFileWatcher(task));
+}
+
+struct UserLocaleFileWatcher(Task<()>);
+
+impl Global for UserLocaleFileWatcher {}
+
+/// The string tables of the active locale.
+struct Translations {
+    locale: String,
+    /// Translations from the user's config directory, which take precedence
+    /// over the bundled ones.
+    user: StringTable,
+    /// Translations bundled with Zed.
+    bundled: StringTable,
+    /// The bundled translations of [`FALLBACK_LOCALE`].
+    fallback: StringTable,
+}
+
+impl Translations {
+    /// Returns the translation of `key` in the active locale, without falling
+    /// back to another locale.
+    fn get(&self, key: &str) -> Option<SharedString> {
+        self.user.get(key).or_else(|| self.bundled.get(key))
+    }
+}
+
+/// The translated strings of a single locale, by key.
+#[derive(Default)]
+struct StringTable(HashMap<String, SharedString>);
+
+impl StringTable {
+    fn bundled(locale: &str) -> Option<Self> {
+        let path = format!("locales/{locale}.json");
+        LocaleAssets::get(&path)?;
+        Self::parse(&asset_str::<LocaleAssets>(&path)).log_err()
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let strings: HashMap<String, String> = serde_json::from_str(content)?;
+        Ok(Self(
+            strings
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+        ))
+    }
+
+    fn get(&self, key: &str) -> Option<SharedString> {
+        self.0.get(key).cloned()
+    }
+}
+
+/// The locale of Zed's user interface.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LocaleSettings {
+    pub locale: Option<String>,
+}
+
+impl LocaleSettings {
+    /// Returns the locale to show the user interface in: the configured one,
+    /// or the one closest to the operating system's locale.
+    pub fn resolve(&self) -> String {
+        match &self.locale {
+            Some(locale) => locale.clone(),
+            None => system_locale().to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocaleSettingsContent {
+    /// The locale of the user interface, such as "zh-CN" or "en". When unset,
+    /// the locale is detected from the operating system.
+    ///
+    /// Default: null
+    pub locale: Option<String>,
+}
+
+impl Settings for LocaleSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = LocaleSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_fallback() {
+        let translations = Translations {
+            locale: "zh-CN".into(),
+            user: StringTable::parse(r#"{ "menu.file.save": "存储" }"#).unwrap(),
+            bundled: StringTable::parse(r#"{ "menu.file.save": "保存", "menu.file.new": "新建" }"#)
+                .unwrap(),
+            fallback: StringTable::parse(r#"{ "menu.file.new": "New", "menu.file.open": "Open" }"#)
+                .unwrap(),
+        };
+
+        assert_eq!(translations.get("menu.file.save"), Some("存储".into()));
+        assert_eq!(translations.get("menu.file.new"), Some("新建".into()));
+        assert_eq!(translations.get("menu.file.open"), None);
+        assert_eq!(
+            translations.fallback.get("menu.file.open"),
+            Some("Open".into())
+        );
+    }
+
+    #[test]
+    fn test_plural_key() {
+        assert_eq!(plural_key("references", "en", 0), "references.other");
+        assert_eq!(plural_key("references", "en", 1), "references.one");
+        assert_eq!(plural_key("references", "en-US", 2), "references.other");
+        assert_eq!(plural_key("references", "fr", 0), "references.one");
+        assert_eq!(plural_key("references", "zh-CN", 1), "references.other");
+    }
+
+    #[test]
+    fn test_is_rtl_locale() {
+        assert!(is_rtl_locale("ar"));
+        assert!(is_rtl_locale("he-IL"));
+        assert!(is_rtl_locale("fa_IR"));
+        assert!(!is_rtl_locale("en"));
+        assert!(!is_rtl_locale("zh-CN"));
+    }
+
+    #[test]
+    fn test_closest_locale() {
+        let bundled = vec!["en".to_string(), "zh-CN".to_string()];
+
+        assert_eq!(closest_locale("zh-CN", &bundled), Some("zh-CN".into()));
+        assert_eq!(
+            closest_locale("zh_CN.UTF-8", &bundled),
+            Some("zh-CN".into())
+        );
+        assert_eq!(closest_locale("zh-Hans-CN", &bundled), Some("zh-CN".into()));
+        assert_eq!(closest_locale("en-US", &bundled), Some("en".into()));
+        assert_eq!(closest_locale("fr-FR", &bundled), None);
+    }
+}
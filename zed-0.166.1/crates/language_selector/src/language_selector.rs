@@ -1,23 +1,24 @@
 mod active_buffer_language;
 
 pub use active_buffer_language::ActiveBufferLanguage;
-use anyhow::anyhow;
+use anyhow::{anyhow, Result};
 use editor::Editor;
 use file_finder::file_finder_settings::FileFinderSettings;
 use file_icons::FileIcons;
 use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
 use gpui::{
-    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
-    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+    actions, AppContext, AsyncWindowContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Model, ParentElement, Render, Styled, View, ViewContext, VisualContext,
+    WeakView,
 };
-use language::{Buffer, LanguageMatcher, LanguageName, LanguageRegistry};
+use language::{Buffer, Language, LanguageMatcher, LanguageName, LanguageRegistry};
 use picker::{Picker, PickerDelegate};
 use project::Project;
 use settings::Settings;
-use std::{ops::Not as _, path::Path, sync::Arc};
+use std::{future::Future, ops::Not as _, path::Path, sync::Arc};
 use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
 use util::ResultExt;
-use workspace::{ModalView, Workspace};
+use workspace::{ModalView, NewFileWithLanguage, Workspace};
 
 actions!(language_selector, [Toggle]);
 
@@ -34,6 +35,27 @@ impl LanguageSelector {
         workspace.register_action(move |workspace, _: &Toggle, cx| {
             Self::toggle(workspace, cx);
         });
+        workspace.register_action(Self::new_file);
+    }
+
+    fn new_file(
+        workspace: &mut Workspace,
+        action: &NewFileWithLanguage,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let registry = workspace.app_state().languages.clone();
+        if let Some(language_name) = &action.language {
+            let language = registry.language_for_name(language_name);
+            cx.spawn(|workspace, cx| open_new_file(workspace, language, cx))
+                .detach_and_log_err(cx);
+            return;
+        }
+
+        let target = LanguageTarget::NewFile(cx.view().downgrade());
+        let project = workspace.project().clone();
+        workspace.toggle_modal(cx, move |cx| {
+            LanguageSelector::new(target, project, registry, cx)
+        });
     }
 
     fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
@@ -46,20 +68,20 @@ impl LanguageSelector {
         let project = workspace.project().clone();
 
         workspace.toggle_modal(cx, move |cx| {
-            LanguageSelector::new(buffer, project, registry, cx)
+            LanguageSelector::new(LanguageTarget::Buffer(buffer), project, registry, cx)
         });
         Some(())
     }
 
     fn new(
-        buffer: Model<Buffer>,
+        target: LanguageTarget,
         project: Model<Project>,
         language_registry: Arc<LanguageRegistry>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
         let delegate = LanguageSelectorDelegate::new(
             cx.view().downgrade(),
-            buffer,
+            target,
             project,
             language_registry,
         );
@@ -84,9 +106,43 @@ impl FocusableView for LanguageSelector {
 impl EventEmitter<DismissEvent> for LanguageSelector {}
 impl ModalView for LanguageSelector {}
 
+/// What the picked language is used for.
+enum LanguageTarget {
+    /// Setting the language of an open buffer.
+    Buffer(Model<Buffer>),
+    /// Opening a new untitled buffer in the language.
+    NewFile(WeakView<Workspace>),
+}
+
+/// Opens a new untitled buffer in `workspace` once `language` has loaded.
+async fn open_new_file(
+    workspace: WeakView<Workspace>,
+    language: impl Future<Output = Result<Arc<Language>>>,
+    mut cx: AsyncWindowContext,
+) -> Result<()> {
+    let language = language.await?;
+    let editor = workspace
+        .update(&mut cx, |workspace, cx| {
+            Editor::new_in_workspace(workspace, cx)
+        })?
+        .await?;
+    workspace.update(&mut cx, |workspace, cx| {
+        let buffer = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .ok_or_else(|| anyhow!("new file has no buffer"))?;
+        workspace.project().update(cx, |project, cx| {
+            project.set_language_for_buffer(&buffer, language, cx);
+        });
+        Ok(())
+    })?
+}
+
 pub struct LanguageSelectorDelegate {
     language_selector: WeakView<LanguageSelector>,
-    buffer: Model<Buffer>,
+    target: LanguageTarget,
     project: Model<Project>,
     language_registry: Arc<LanguageRegistry>,
     candidates: Vec<StringMatchCandidate>,
@@ -97,7 +153,7 @@ pub struct LanguageSelectorDelegate {
 impl LanguageSelectorDelegate {
     fn new(
         language_selector: WeakView<LanguageSelector>,
-        buffer: Model<Buffer>,
+        target: LanguageTarget,
         project: Model<Project>,
         language_registry: Arc<LanguageRegistry>,
     ) -> Self {
@@ -117,7 +173,7 @@ impl LanguageSelectorDelegate {
 
         Self {
             language_selector,
-            buffer,
+            target,
             project,
             language_registry,
             candidates,
@@ -132,7 +188,10 @@ impl LanguageSelectorDelegate {
         cx: &AppContext,
     ) -> (String, Option<Icon>) {
         let mut label = mat.string.clone();
-        let buffer_language = self.buffer.read(cx).language();
+        let buffer_language = match &self.target {
+            LanguageTarget::Buffer(buffer) => buffer.read(cx).language(),
+            LanguageTarget::NewFile(_) => None,
+        };
         let need_icon = FileFinderSettings::get_global(cx).file_icons;
         if let Some(buffer_language) = buffer_language {
             let buffer_language_name = buffer_language.name();
@@ -193,21 +252,30 @@ impl PickerDelegate for LanguageSelectorDelegate {
         if let Some(mat) = self.matches.get(self.selected_index) {
             let language_name = &self.candidates[mat.candidate_id].string;
             let language = self.language_registry.language_for_name(language_name);
-            let project = self.project.downgrade();
-            let buffer = self.buffer.downgrade();
-            cx.spawn(|_, mut cx| async move {
-                let language = language.await?;
-                let project = project
-                    .upgrade()
-                    .ok_or_else(|| anyhow!("project was dropped"))?;
-                let buffer = buffer
-                    .upgrade()
-                    .ok_or_else(|| anyhow!("buffer was dropped"))?;
-                project.update(&mut cx, |project, cx| {
-                    project.set_language_for_buffer(&buffer, language, cx);
-                })
-            })
-            .detach_and_log_err(cx);
+            match &self.target {
+                LanguageTarget::Buffer(buffer) => {
+                    let project = self.project.downgrade();
+                    let buffer = buffer.downgrade();
+                    cx.spawn(|_, mut cx| async move {
+                        let language = language.await?;
+                        let project = project
+                            .upgrade()
+                            .ok_or_else(|| anyhow!("project was dropped"))?;
+                        let buffer = buffer
+                            .upgrade()
+                            .ok_or_else(|| anyhow!("buffer was dropped"))?;
+                        project.update(&mut cx, |project, cx| {
+                            project.set_language_for_buffer(&buffer, language, cx);
+                        })
+                    })
+                    .detach_and_log_err(cx);
+                }
+                LanguageTarget::NewFile(workspace) => {
+                    let workspace = workspace.clone();
+                    cx.spawn(|_, cx| open_new_file(workspace, language, cx))
+                        .detach_and_log_err(cx);
+                }
+            }
         }
         self.dismissed(cx);
     }
@@ -189,6 +189,14 @@ pub fn themes_dir() -> &'static PathBuf {
     THEMES_DIR.get_or_init(|| config_dir().join("themes"))
 }
 
+/// Returns the path to the locales directory.
+///
+/// This is where user-provided translations of the UI are stored.
+pub fn locales_dir() -> &'static PathBuf {
+    static LOCALES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCALES_DIR.get_or_init(|| config_dir().join("locales"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the saved contexts from the Assistant are stored.
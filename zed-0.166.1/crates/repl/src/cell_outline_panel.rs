@@ -0,0 +1,326 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor, EditorEvent};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    Subscription, Task, View, WeakView,
+};
+use i18n::t;
+use multi_buffer::MultiBufferSnapshot;
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
+use ui::{prelude::*, Indicator, ListItem};
+use util::ResultExt as _;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::outputs::ExecutionStatus;
+use crate::repl_settings::CellOutlinePanelSettings;
+use crate::repl_store::ReplStore;
+use crate::{CellInfo, JupyterSettings, Session, SessionEvent};
+
+const CELL_OUTLINE_PANEL_KEY: &str = "CellOutlinePanel";
+
+actions!(cell_outline_panel, [ToggleFocus]);
+
+/// A panel listing the cells of the active editor's REPL session, with the
+/// status of their latest execution, for jumping between them.
+pub struct CellOutlinePanel {
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    editor: Option<View<Editor>>,
+    session: Option<View<Session>>,
+    pending_serialization: Task<Option<()>>,
+    _editor_subscription: Option<Subscription>,
+    _session_subscriptions: Vec<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedCellOutlinePanel {
+    width: Option<Pixels>,
+}
+
+impl CellOutlinePanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let active_editor = workspace.active_item_as::<Editor>(cx);
+        let workspace_handle = cx.view().clone();
+
+        cx.new_view(|cx| {
+            let store = ReplStore::global(cx);
+            let subscriptions = vec![
+                cx.subscribe(&workspace_handle, |this, workspace, event, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        let editor = workspace.read(cx).active_item_as::<Editor>(cx);
+                        this.set_editor(editor, cx);
+                    }
+                }),
+                cx.observe(&store, |this, _, cx| this.update_session(cx)),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                width: None,
+                editor: None,
+                session: None,
+                pending_serialization: Task::ready(None),
+                _editor_subscription: None,
+                _session_subscriptions: Vec::new(),
+                _subscriptions: subscriptions,
+            };
+            this.set_editor(active_editor, cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(CELL_OUTLINE_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedCellOutlinePanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        CELL_OUTLINE_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedCellOutlinePanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn set_editor(&mut self, editor: Option<View<Editor>>, cx: &mut ViewContext<Self>) {
+        if self.editor != editor {
+            // Cells move around as the code is edited.
+            self._editor_subscription = editor.as_ref().map(|editor| {
+                cx.subscribe(editor, |_, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        cx.notify();
+                    }
+                })
+            });
+            self.editor = editor;
+        }
+        self.update_session(cx);
+    }
+
+    fn update_session(&mut self, cx: &mut ViewContext<Self>) {
+        let session = self.editor.as_ref().and_then(|editor| {
+            ReplStore::global(cx)
+                .read(cx)
+                .get_session(editor.entity_id())
+                .cloned()
+        });
+        if self.session == session {
+            return;
+        }
+
+        self._session_subscriptions = session
+            .iter()
+            .flat_map(|session| {
+                [
+                    cx.observe(session, |_, _, cx| cx.notify()),
+                    cx.subscribe(session, |_, _, event: &SessionEvent, cx| {
+                        if let SessionEvent::ExecutionFinished { .. } = event {
+                            cx.notify();
+                        }
+                    }),
+                ]
+            })
+            .collect();
+        self.session = session;
+        cx.notify();
+    }
+
+    fn render_cell(
+        ix: usize,
+        cell: CellInfo,
+        snapshot: &MultiBufferSnapshot,
+        editor: &View<Editor>,
+    ) -> ListItem {
+        let code = snapshot
+            .text_for_range(cell.range.clone())
+            .collect::<String>();
+        let title = code
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string();
+        let execution_count = match cell.execution_count {
+            Some(execution_count) => format!("[{execution_count}]"),
+            None => "[ ]".to_string(),
+        };
+
+        let editor = editor.downgrade();
+        let position = cell.range.start;
+        ListItem::new(ix)
+            .start_slot(Indicator::dot().color(status_color(cell.status.as_ref())))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(execution_count)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(title).size(LabelSize::Small)),
+            )
+            .on_click(move |_, cx| {
+                let Some(editor) = editor.upgrade() else {
+                    return;
+                };
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([position..position]);
+                    });
+                });
+                cx.focus_view(&editor);
+            })
+    }
+}
+
+/// The color of the badge shown next to a cell with the given execution status.
+fn status_color(status: Option<&ExecutionStatus>) -> Color {
+    match status {
+        None => Color::Disabled,
+        Some(ExecutionStatus::Finished) => Color::Success,
+        Some(ExecutionStatus::KernelErrored(_)) => Color::Error,
+        Some(ExecutionStatus::Reconnecting) => Color::Warning,
+        Some(
+            ExecutionStatus::ConnectingToKernel
+            | ExecutionStatus::Queued
+            | ExecutionStatus::Executing
+            | ExecutionStatus::Restarting
+            | ExecutionStatus::ShuttingDown,
+        ) => Color::Modified,
+        Some(ExecutionStatus::Unknown | ExecutionStatus::Shutdown) => Color::Muted,
+    }
+}
+
+impl Render for CellOutlinePanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match (&self.editor, &self.session) {
+            (Some(editor), Some(session)) => {
+                let cells = session.read(cx).cells(cx);
+                if cells.is_empty() {
+                    Label::new(t!("cell_outline_panel.no_cells"))
+                        .color(Color::Muted)
+                        .into_any_element()
+                } else {
+                    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                    v_flex()
+                        .children(
+                            cells
+                                .into_iter()
+                                .enumerate()
+                                .map(|(ix, cell)| Self::render_cell(ix, cell, &snapshot, editor)),
+                        )
+                        .into_any_element()
+                }
+            }
+            _ => Label::new(t!("cell_outline_panel.no_session"))
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("cell-outline-panel")
+            .key_context("CellOutlinePanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .child(content)
+    }
+}
+
+impl FocusableView for CellOutlinePanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for CellOutlinePanel {}
+
+impl Panel for CellOutlinePanel {
+    fn persistent_name() -> &'static str {
+        "CellOutlinePanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        CellOutlinePanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        let fs = ReplStore::global(cx).read(cx).fs().clone();
+        settings::update_settings_file::<CellOutlinePanelSettings>(fs, cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| CellOutlinePanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        // Only offer the panel where there are cells to list.
+        (CellOutlinePanelSettings::get_global(cx).button
+            && JupyterSettings::enabled(cx)
+            && self.session.is_some())
+        .then_some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Cell Outline Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -66,6 +66,21 @@ impl<T: PopoverTrigger> KernelSelector<T> {
     }
 }
 
+impl KernelPickerDelegate {
+    pub fn new(
+        kernels: Vec<KernelSpecification>,
+        selected_kernelspec: Option<KernelSpecification>,
+        on_select: OnSelect,
+    ) -> Self {
+        Self {
+            all_kernels: kernels.clone(),
+            filtered_kernels: kernels,
+            selected_kernelspec,
+            on_select,
+        }
+    }
+}
+
 impl PickerDelegate for KernelPickerDelegate {
     type ListItem = ListItem;
 
@@ -236,12 +251,7 @@ impl<T: PopoverTrigger> RenderOnce for KernelSelector<T> {
 
         let selected_kernelspec = store.active_kernelspec(self.worktree_id, None, cx);
 
-        let delegate = KernelPickerDelegate {
-            on_select: self.on_select,
-            all_kernels: all_kernels.clone(),
-            filtered_kernels: all_kernels,
-            selected_kernelspec,
-        };
+        let delegate = KernelPickerDelegate::new(all_kernels, selected_kernelspec, self.on_select);
 
         let picker_view = cx.new_view(|cx| {
             let picker = Picker::uniform_list(delegate, cx)
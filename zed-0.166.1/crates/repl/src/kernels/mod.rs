@@ -15,7 +15,7 @@ use project::{Project, WorktreeId};
 pub use remote_kernels::*;
 
 use anyhow::Result;
-use jupyter_protocol::JupyterKernelspec;
+use jupyter_protocol::{connection_info::ConnectionInfo, JupyterKernelspec};
 use runtimelib::{ExecutionState, JupyterMessage, KernelInfoReply};
 use ui::{Icon, IconName, SharedString};
 
@@ -148,11 +148,23 @@ pub trait RunningKernel: Send + Debug {
     fn set_execution_state(&mut self, state: ExecutionState);
     fn kernel_info(&self) -> Option<&KernelInfoReply>;
     fn set_kernel_info(&mut self, info: KernelInfoReply);
+    /// Whether the kernel is re-establishing a dropped connection. Only remote
+    /// kernels can lose their connection.
+    fn is_reconnecting(&self) -> bool {
+        false
+    }
+    fn set_reconnecting(&mut self, _reconnecting: bool) {}
+    /// The ports, key and transport of a kernel that Zed launched, for other
+    /// Jupyter clients to connect to it.
+    fn connection_info(&self) -> Option<&ConnectionInfo> {
+        None
+    }
     fn force_shutdown(&mut self, cx: &mut WindowContext) -> Task<anyhow::Result<()>>;
 }
 
 #[derive(Debug, Clone)]
 pub enum KernelStatus {
+    NotStarted,
     Idle,
     Busy,
     Starting,
@@ -160,6 +172,7 @@ pub enum KernelStatus {
     ShuttingDown,
     Shutdown,
     Restarting,
+    Reconnecting,
 }
 
 impl KernelStatus {
@@ -174,6 +187,7 @@ impl KernelStatus {
 impl ToString for KernelStatus {
     fn to_string(&self) -> String {
         match self {
+            KernelStatus::NotStarted => "Not Started".to_string(),
             KernelStatus::Idle => "Idle".to_string(),
             KernelStatus::Busy => "Busy".to_string(),
             KernelStatus::Starting => "Starting".to_string(),
@@ -181,12 +195,15 @@ impl ToString for KernelStatus {
             KernelStatus::ShuttingDown => "Shutting Down".to_string(),
             KernelStatus::Shutdown => "Shutdown".to_string(),
             KernelStatus::Restarting => "Restarting".to_string(),
+            KernelStatus::Reconnecting => "Reconnecting".to_string(),
         }
     }
 }
 
 #[derive(Debug)]
 pub enum Kernel {
+    /// The kernel of a session with `repl.lazy_start` on, before any code ran.
+    NotStarted,
     RunningKernel(Box<dyn RunningKernel>),
     StartingKernel(Shared<Task<()>>),
     ErroredLaunch(String),
@@ -198,10 +215,12 @@ pub enum Kernel {
 impl From<&Kernel> for KernelStatus {
     fn from(kernel: &Kernel) -> Self {
         match kernel {
+            Kernel::RunningKernel(kernel) if kernel.is_reconnecting() => KernelStatus::Reconnecting,
             Kernel::RunningKernel(kernel) => match kernel.execution_state() {
                 ExecutionState::Idle => KernelStatus::Idle,
                 ExecutionState::Busy => KernelStatus::Busy,
             },
+            Kernel::NotStarted => KernelStatus::NotStarted,
             Kernel::StartingKernel(_) => KernelStatus::Starting,
             Kernel::ErroredLaunch(_) => KernelStatus::Error,
             Kernel::ShuttingDown => KernelStatus::ShuttingDown,
@@ -228,10 +247,24 @@ impl Kernel {
         }
     }
 
+    pub fn set_reconnecting(&mut self, reconnecting: bool) {
+        if let Kernel::RunningKernel(running_kernel) = self {
+            running_kernel.set_reconnecting(reconnecting);
+        }
+    }
+
+    pub fn is_reconnecting(&self) -> bool {
+        match self {
+            Kernel::RunningKernel(running_kernel) => running_kernel.is_reconnecting(),
+            _ => false,
+        }
+    }
+
     pub fn is_shutting_down(&self) -> bool {
         match self {
             Kernel::Restarting | Kernel::ShuttingDown => true,
-            Kernel::RunningKernel(_)
+            Kernel::NotStarted
+            | Kernel::RunningKernel(_)
             | Kernel::StartingKernel(_)
             | Kernel::ErroredLaunch(_)
             | Kernel::Shutdown => false,
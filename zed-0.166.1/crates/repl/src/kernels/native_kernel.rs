@@ -14,6 +14,7 @@ use project::Fs;
 use runtimelib::dirs;
 use smol::{net::TcpListener, process::Command};
 use std::{
+    collections::VecDeque,
     env,
     fmt::Debug,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -70,6 +71,42 @@ impl LocalKernelSpecification {
 
         Ok(cmd)
     }
+
+    /// Starts the kernel process, with its standard streams piped.
+    fn spawn(
+        &self,
+        connection_path: &PathBuf,
+        working_directory: &PathBuf,
+    ) -> Result<smol::process::Child> {
+        self.command(connection_path)?
+            .current_dir(working_directory)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .stdin(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "failed to start the kernel process `{}` for kernelspec {}",
+                    self.kernelspec.argv[0], self.name
+                )
+            })
+    }
+}
+
+/// How many of the last lines a kernel wrote to stderr are shown when it exits
+/// with an error.
+const STDERR_TAIL_LINES: usize = 10;
+
+/// Describes a kernel process that exited with an error, followed by the last
+/// lines it wrote to stderr, which usually say why (e.g. a missing module).
+fn kernel_exit_message(exit_description: String, stderr_tail: &VecDeque<String>) -> String {
+    if stderr_tail.is_empty() {
+        return exit_description;
+    }
+
+    let stderr_tail = stderr_tail.iter().cloned().collect::<Vec<_>>().join("\n");
+    format!("{exit_description}\n{stderr_tail}")
 }
 
 // Find a set of open ports. This creates a listener with port set to 0. The listener will be closed at the end when it goes out of scope.
@@ -92,6 +129,7 @@ pub struct NativeRunningKernel {
     _control_task: Task<Result<()>>,
     _routing_task: Task<Result<()>>,
     connection_path: PathBuf,
+    connection_info: ConnectionInfo,
     _process_status_task: Option<Task<()>>,
     pub working_directory: PathBuf,
     pub request_tx: mpsc::Sender<JupyterMessage>,
@@ -142,16 +180,7 @@ impl NativeRunningKernel {
             let content = serde_json::to_string(&connection_info)?;
             fs.atomic_write(connection_path.clone(), content).await?;
 
-            let mut cmd = kernel_specification.command(&connection_path)?;
-
-            let mut process = cmd
-                .current_dir(&working_directory)
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped())
-                .stdin(std::process::Stdio::piped())
-                .kill_on_drop(true)
-                .spawn()
-                .context("failed to start the kernel process")?;
+            let mut process = kernel_specification.spawn(&connection_path, &working_directory)?;
 
             let session_id = Uuid::new_v4().to_string();
 
@@ -252,17 +281,23 @@ impl NativeRunningKernel {
 
             let stderr = process.stderr.take();
 
-            cx.spawn(|mut _cx| async move {
-                if stderr.is_none() {
-                    return;
-                }
-                let reader = BufReader::new(stderr.unwrap());
+            // Keep the last lines of stderr to explain why the kernel exited, if it does
+            let stderr_task = cx.background_executor().spawn(async move {
+                let mut stderr_tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+                let Some(stderr) = stderr else {
+                    return stderr_tail;
+                };
+                let reader = BufReader::new(stderr);
                 let mut lines = reader.lines();
                 while let Some(Ok(line)) = lines.next().await {
                     log::error!("kernel: {}", line);
+                    if stderr_tail.len() == STDERR_TAIL_LINES {
+                        stderr_tail.pop_front();
+                    }
+                    stderr_tail.push_back(line);
                 }
-            })
-            .detach();
+                stderr_tail
+            });
 
             let stdout = process.stdout.take();
 
@@ -294,6 +329,7 @@ impl NativeRunningKernel {
                         format!("kernel process exited with error: {:?}", err)
                     }
                 };
+                let error_message = kernel_exit_message(error_message, &stderr_task.await);
 
                 log::error!("{}", error_message);
 
@@ -315,6 +351,7 @@ impl NativeRunningKernel {
                 _control_task: control_task,
                 _routing_task: routing_task,
                 connection_path,
+                connection_info,
                 execution_state: ExecutionState::Idle,
                 kernel_info: None,
             }) as Box<dyn RunningKernel>)
@@ -347,6 +384,10 @@ impl RunningKernel for NativeRunningKernel {
         self.kernel_info = Some(info);
     }
 
+    fn connection_info(&self) -> Option<&ConnectionInfo> {
+        Some(&self.connection_info)
+    }
+
     fn force_shutdown(&mut self, _cx: &mut WindowContext) -> Task<anyhow::Result<()>> {
         self._process_status_task.take();
         self.request_tx.close_channel();
@@ -518,4 +559,51 @@ mod test {
             vec!["deno", "python"]
         );
     }
+
+    #[test]
+    fn test_spawn_missing_kernel_binary() {
+        let kernel_specification = LocalKernelSpecification {
+            name: "missing".to_string(),
+            path: PathBuf::from("/jupyter/kernels/missing"),
+            kernelspec: serde_json::from_value(json!({
+                "display_name": "Missing",
+                "language": "python",
+                "argv": ["zed-test-missing-kernel", "-f", "{connection_file}"],
+                "env": {}
+            }))
+            .unwrap(),
+        };
+
+        let error = kernel_specification
+            .spawn(
+                &PathBuf::from("/tmp/kernel-zed-test.json"),
+                &env::temp_dir(),
+            )
+            .unwrap_err();
+        let message = format!("{error:#}");
+        assert!(
+            message.starts_with(
+                "failed to start the kernel process `zed-test-missing-kernel` for kernelspec missing:"
+            ),
+            "unexpected error message: {message}"
+        );
+    }
+
+    #[test]
+    fn test_kernel_exit_message() {
+        assert_eq!(
+            kernel_exit_message("kernel process exited".into(), &VecDeque::new()),
+            "kernel process exited"
+        );
+        assert_eq!(
+            kernel_exit_message(
+                "kernel process exited".into(),
+                &VecDeque::from([
+                    "Traceback (most recent call last):".to_string(),
+                    "ModuleNotFoundError: No module named 'ipykernel'".to_string(),
+                ])
+            ),
+            "kernel process exited\nTraceback (most recent call last):\nModuleNotFoundError: No module named 'ipykernel'"
+        );
+    }
 }
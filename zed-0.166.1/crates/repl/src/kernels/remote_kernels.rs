@@ -1,5 +1,9 @@
-use futures::{channel::mpsc, SinkExt as _};
-use gpui::{Task, View, WindowContext};
+use futures::{
+    channel::mpsc,
+    future::{self, Either},
+    SinkExt as _,
+};
+use gpui::{AsyncWindowContext, Task, View, WindowContext};
 use http_client::{AsyncBody, HttpClient, Request};
 use jupyter_protocol::{ExecutionState, JupyterKernelspec, JupyterMessage, KernelInfoReply};
 
@@ -11,10 +15,14 @@ use crate::Session;
 use super::RunningKernel;
 use anyhow::Result;
 use jupyter_websocket_client::{
-    JupyterWebSocketReader, JupyterWebSocketWriter, KernelLaunchRequest, KernelSpecsResponse,
-    RemoteServer,
+    JupyterWebSocket, JupyterWebSocketReader, JupyterWebSocketWriter, KernelLaunchRequest,
+    KernelSpecsResponse, RemoteServer,
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+const MAX_RECONNECT_ATTEMPTS: usize = 10;
 
 #[derive(Debug, Clone)]
 pub struct RemoteKernelSpecification {
@@ -116,15 +124,19 @@ impl PartialEq for RemoteKernelSpecification {
 
 impl Eq for RemoteKernelSpecification {}
 
+/// A kernel run by a Jupyter server, reached through the server's websocket
+/// API. The server signs and verifies the kernel's messages with the key and
+/// signature scheme from the kernel's connection file, so messages on the
+/// websocket are never signed by us.
 pub struct RemoteRunningKernel {
     remote_server: RemoteServer,
-    _receiving_task: Task<Result<()>>,
-    _routing_task: Task<Result<()>>,
+    _connection_task: Task<Result<()>>,
     http_client: Arc<dyn HttpClient>,
     pub working_directory: std::path::PathBuf,
     pub request_tx: mpsc::Sender<JupyterMessage>,
     pub execution_state: ExecutionState,
     pub kernel_info: Option<KernelInfoReply>,
+    pub reconnecting: bool,
     pub kernel_id: String,
 }
 
@@ -153,52 +165,36 @@ impl RemoteRunningKernel {
 
             let (kernel_socket, _response) = remote_server.connect_to_kernel(&kernel_id).await?;
 
-            let (mut w, mut r): (JupyterWebSocketWriter, JupyterWebSocketReader) =
-                kernel_socket.split();
-
-            let (request_tx, mut request_rx) =
-                futures::channel::mpsc::channel::<JupyterMessage>(100);
-
-            let routing_task = cx.background_executor().spawn({
-                async move {
-                    while let Some(message) = request_rx.next().await {
-                        w.send(message).await.ok();
-                    }
-                    Ok(())
-                }
-            });
-
-            let receiving_task = cx.spawn({
-                let session = session.clone();
-
-                |mut cx| async move {
-                    while let Some(message) = r.next().await {
-                        match message {
-                            Ok(message) => {
-                                session
-                                    .update(&mut cx, |session, cx| {
-                                        session.route(&message, cx);
-                                    })
-                                    .ok();
-                            }
-                            Err(e) => {
-                                log::error!("Error receiving message: {:?}", e);
-                            }
-                        }
-                    }
-                    Ok(())
+            let (request_tx, request_rx) = futures::channel::mpsc::channel::<JupyterMessage>(100);
+
+            let connection_task = cx.spawn({
+                let remote_server = RemoteServer {
+                    base_url: remote_server.base_url.clone(),
+                    token: remote_server.token.clone(),
+                };
+                let kernel_id = kernel_id.clone();
+
+                |cx| {
+                    maintain_connection(
+                        remote_server,
+                        kernel_id,
+                        kernel_socket,
+                        request_rx,
+                        session,
+                        cx,
+                    )
                 }
             });
 
             anyhow::Ok(Box::new(Self {
-                _routing_task: routing_task,
-                _receiving_task: receiving_task,
+                _connection_task: connection_task,
                 remote_server,
                 working_directory,
                 request_tx,
                 // todo(kyle): pull this from the kernel API to start with
                 execution_state: ExecutionState::Idle,
                 kernel_info: None,
+                reconnecting: false,
                 kernel_id,
                 http_client: http_client.clone(),
             }) as Box<dyn RunningKernel>)
@@ -206,6 +202,107 @@ impl RemoteRunningKernel {
     }
 }
 
+/// Relays messages between the session and the kernel's websocket until the
+/// kernel is dropped, reconnecting whenever the connection drops.
+async fn maintain_connection(
+    remote_server: RemoteServer,
+    kernel_id: String,
+    mut kernel_socket: JupyterWebSocket,
+    mut request_rx: mpsc::Receiver<JupyterMessage>,
+    session: View<Session>,
+    mut cx: AsyncWindowContext,
+) -> Result<()> {
+    loop {
+        {
+            let (mut w, mut r): (JupyterWebSocketWriter, JupyterWebSocketReader) =
+                kernel_socket.split();
+
+            let routing = async {
+                while let Some(message) = request_rx.next().await {
+                    w.send(message).await?;
+                }
+                anyhow::Ok(())
+            };
+
+            let receiving = async {
+                while let Some(message) = r.next().await {
+                    match message {
+                        Ok(message) => {
+                            session
+                                .update(&mut cx, |session, cx| {
+                                    session.route(&message, cx);
+                                })
+                                .ok();
+                        }
+                        Err(e) => {
+                            log::error!("Error receiving message: {:?}", e);
+                        }
+                    }
+                }
+            };
+
+            futures::pin_mut!(routing, receiving);
+            match future::select(routing, receiving).await {
+                // The kernel was dropped, so there is nothing left to relay.
+                Either::Left((Ok(()), _)) => return Ok(()),
+                Either::Left((Err(error), _)) => {
+                    log::warn!("lost connection to remote kernel: {error:?}");
+                }
+                Either::Right(_) => log::warn!("lost connection to remote kernel"),
+            }
+        }
+
+        session.update(&mut cx, |session, cx| {
+            session.kernel_reconnecting(true, cx);
+        })?;
+
+        kernel_socket = match reconnect(&remote_server, &kernel_id, &cx).await {
+            Ok(kernel_socket) => kernel_socket,
+            Err(error) => {
+                session
+                    .update(&mut cx, |session, cx| {
+                        session.kernel_errored(format!("{error:#}"), cx);
+                    })
+                    .ok();
+                return Err(error);
+            }
+        };
+
+        session.update(&mut cx, |session, cx| {
+            session.kernel_reconnecting(false, cx);
+        })?;
+    }
+}
+
+/// Reconnects to a running remote kernel, backing off exponentially between
+/// attempts.
+async fn reconnect(
+    remote_server: &RemoteServer,
+    kernel_id: &str,
+    cx: &AsyncWindowContext,
+) -> Result<JupyterWebSocket> {
+    let mut delay = RECONNECT_INITIAL_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        cx.background_executor().timer(delay).await;
+
+        match remote_server.connect_to_kernel(kernel_id).await {
+            Ok((kernel_socket, _response)) => return Ok(kernel_socket),
+            Err(error) if attempt < MAX_RECONNECT_ATTEMPTS => {
+                log::warn!("failed to reconnect to remote kernel (attempt {attempt}): {error:?}");
+                attempt += 1;
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            }
+            Err(error) => {
+                return Err(error.context(format!(
+                    "Lost connection to the kernel after {attempt} reconnection attempts"
+                )))
+            }
+        }
+    }
+}
+
 impl Debug for RemoteRunningKernel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RemoteRunningKernel")
@@ -215,6 +312,7 @@ impl Debug for RemoteRunningKernel {
             .field("request_tx", &self.request_tx)
             .field("execution_state", &self.execution_state)
             .field("kernel_info", &self.kernel_info)
+            .field("reconnecting", &self.reconnecting)
             .finish()
     }
 }
@@ -244,6 +342,14 @@ impl RunningKernel for RemoteRunningKernel {
         self.kernel_info = Some(info);
     }
 
+    fn is_reconnecting(&self) -> bool {
+        self.reconnecting
+    }
+
+    fn set_reconnecting(&mut self, reconnecting: bool) {
+        self.reconnecting = reconnecting;
+    }
+
     fn force_shutdown(&mut self, cx: &mut WindowContext) -> Task<anyhow::Result<()>> {
         let url = self
             .remote_server
@@ -80,7 +80,11 @@ pub enum Cell {
     Raw(View<RawCell>),
 }
 
-fn convert_outputs(outputs: &Vec<nbformat::v4::Output>, cx: &mut WindowContext) -> Vec<Output> {
+fn convert_outputs(
+    outputs: &Vec<nbformat::v4::Output>,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut WindowContext,
+) -> Vec<Output> {
     outputs
         .into_iter()
         .map(|output| match output {
@@ -88,10 +92,10 @@ fn convert_outputs(outputs: &Vec<nbformat::v4::Output>, cx: &mut WindowContext)
                 content: cx.new_view(|cx| TerminalOutput::from(&text.0, cx)),
             },
             nbformat::v4::Output::DisplayData(display_data) => {
-                Output::new(&display_data.data, None, cx)
+                Output::new(&display_data.data, None, Some(languages.clone()), cx)
             }
             nbformat::v4::Output::ExecuteResult(execute_result) => {
-                Output::new(&execute_result.data, None, cx)
+                Output::new(&execute_result.data, None, Some(languages.clone()), cx)
             }
             nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
                 ename: error.ename.clone(),
@@ -206,7 +210,7 @@ impl Cell {
                     execution_count: *execution_count,
                     source: source.join(""),
                     editor: editor_view,
-                    outputs: convert_outputs(outputs, cx),
+                    outputs: convert_outputs(outputs, languages, cx),
                     selected: false,
                     language_task,
                     cell_position: None,
@@ -626,6 +630,15 @@ impl Render for CodeCell {
                                             Output::Table { content, .. } => {
                                                 Some(content.clone().into_any_element())
                                             }
+                                            Output::Html { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Code { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
+                                            Output::Json { content, .. } => {
+                                                Some(content.clone().into_any_element())
+                                            }
                                             Output::ErrorOutput(error_view) => {
                                                 error_view.render(cx)
                                             }
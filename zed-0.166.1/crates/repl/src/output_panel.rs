@@ -0,0 +1,320 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    Subscription, Task, View, WeakView,
+};
+use i18n::t;
+use multi_buffer::MultiBufferSnapshot;
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
+use ui::{prelude::*, Tooltip};
+use util::ResultExt as _;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::repl_settings::{OutputPlacement, ReplOutputPanelSettings};
+use crate::repl_store::ReplStore;
+use crate::session::CellOutput;
+use crate::{JupyterSettings, ReplSettings, Session};
+
+const REPL_OUTPUT_PANEL_KEY: &str = "ReplOutputPanel";
+
+actions!(repl_output_panel, [ToggleFocus]);
+
+/// A panel showing the outputs of the active editor's REPL session, one
+/// section per execution, for when the `output_placement` setting keeps them
+/// out of the editor.
+pub struct ReplOutputPanel {
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    editor: Option<View<Editor>>,
+    session: Option<View<Session>>,
+    pending_serialization: Task<Option<()>>,
+    _session_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedReplOutputPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+impl ReplOutputPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let active_editor = workspace.active_item_as::<Editor>(cx);
+        let workspace_handle = cx.view().clone();
+
+        cx.new_view(|cx| {
+            let store = ReplStore::global(cx);
+            let subscriptions = vec![
+                cx.subscribe(&workspace_handle, |this, workspace, event, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        this.editor = workspace.read(cx).active_item_as::<Editor>(cx);
+                        this.update_session(cx);
+                    }
+                }),
+                cx.observe(&store, |this, _, cx| this.update_session(cx)),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                width: None,
+                height: None,
+                editor: active_editor,
+                session: None,
+                pending_serialization: Task::ready(None),
+                _session_subscription: None,
+                _subscriptions: subscriptions,
+            };
+            this.update_session(cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(REPL_OUTPUT_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedReplOutputPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        panel.height = serialized_panel.height.map(|h| h.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        REPL_OUTPUT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedReplOutputPanel { width, height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn update_session(&mut self, cx: &mut ViewContext<Self>) {
+        let session = self.editor.as_ref().and_then(|editor| {
+            ReplStore::global(cx)
+                .read(cx)
+                .get_session(editor.entity_id())
+                .cloned()
+        });
+        if self.session == session {
+            return;
+        }
+
+        self._session_subscription = session
+            .as_ref()
+            .map(|session| cx.observe(session, |_, _, cx| cx.notify()));
+        self.session = session;
+        cx.notify();
+    }
+
+    fn render_output(
+        output: CellOutput,
+        snapshot: &MultiBufferSnapshot,
+        editor: &View<Editor>,
+        session: &View<Session>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let title = snapshot
+            .text_for_range(output.range.clone())
+            .collect::<String>()
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string();
+
+        let position = output.range.start;
+        let editor = editor.downgrade();
+        let session = session.downgrade();
+        let key = output.key;
+
+        v_flex()
+            .w_full()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .id(SharedString::from(format!("output-header-{key}")))
+                    .w_full()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                    .on_click(move |_, cx| {
+                        let Some(editor) = editor.upgrade() else {
+                            return;
+                        };
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(
+                                Some(Autoscroll::center()),
+                                cx,
+                                |selections| {
+                                    selections.select_ranges([position..position]);
+                                },
+                            );
+                        });
+                        cx.focus_view(&editor);
+                    })
+                    .child(
+                        Label::new(title)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .child(
+                        IconButton::new(
+                            SharedString::from(format!("close-output-{key}")),
+                            IconName::Close,
+                        )
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted)
+                        .tooltip(|cx| Tooltip::text(t!("repl.close_output_area"), cx))
+                        .on_click(move |_, cx| {
+                            session
+                                .update(cx, |session, cx| session.close_output(&key, cx))
+                                .ok();
+                        }),
+                    ),
+            )
+            .child(div().px_2().pb_2().child(output.execution_view))
+    }
+}
+
+impl Render for ReplOutputPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = match (self.editor.clone(), self.session.clone()) {
+            (Some(editor), Some(session)) => {
+                let outputs = session.read(cx).outputs(cx);
+                if outputs.is_empty() {
+                    Label::new(t!("repl_output_panel.no_outputs"))
+                        .color(Color::Muted)
+                        .into_any_element()
+                } else {
+                    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                    v_flex()
+                        .w_full()
+                        .children(outputs.into_iter().map(|output| {
+                            Self::render_output(output, &snapshot, &editor, &session, cx)
+                        }))
+                        .into_any_element()
+                }
+            }
+            _ => Label::new(t!("repl_output_panel.no_session"))
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("repl-output-panel")
+            .key_context("ReplOutputPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background)
+            .child(content)
+    }
+}
+
+impl FocusableView for ReplOutputPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReplOutputPanel {}
+
+impl Panel for ReplOutputPanel {
+    fn persistent_name() -> &'static str {
+        "ReplOutputPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        ReplOutputPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        let fs = ReplStore::global(cx).read(cx).fs().clone();
+        settings::update_settings_file::<ReplOutputPanelSettings>(fs, cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        let settings = ReplOutputPanelSettings::get_global(cx);
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        match self.position(cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        // Outputs only end up here when they're kept out of the editor.
+        (ReplOutputPanelSettings::get_global(cx).button
+            && JupyterSettings::enabled(cx)
+            && ReplSettings::get_global(cx).output_placement == OutputPlacement::Panel
+            && self.session.is_some())
+        .then_some(IconName::ReplNeutral)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("REPL Output Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -16,6 +16,8 @@
 //! - Markdown
 //! - Images (PNG and JPEG)
 //! - Tables
+//! - JSON, as a collapsible tree
+//! - HTML tables (such as pandas DataFrames)
 //! - Error messages
 //!
 //! ## Clipboard Support
@@ -33,20 +35,34 @@
 //! This module is designed to work with Jupyter message protocols,
 //! interpreting and displaying various types of Jupyter output.
 
+use std::sync::Arc;
 use std::time::Duration;
 
-use editor::{Editor, MultiBuffer};
+use editor::{Editor, EditorEvent, MultiBuffer};
 use gpui::{
-    percentage, Animation, AnimationExt, AnyElement, ClipboardItem, Model, Render, Transformation,
-    View, WeakView,
+    percentage, Animation, AnimationExt, AnyElement, AppContext, ClipboardItem, Model, Render,
+    Subscription, Transformation, View, WeakView,
+};
+use i18n::{t, t_plural};
+use language::{
+    language_settings::{language_settings, SoftWrap},
+    Buffer, LanguageRegistry,
 };
-use language::Buffer;
 use runtimelib::{ExecutionState, JupyterMessageContent, MimeBundle, MimeType};
 use ui::{div, prelude::*, v_flex, IntoElement, Styled, Tooltip, ViewContext};
 
+mod code;
+use code::{code_content, code_language, CodeView};
+
+mod html;
+use html::HtmlView;
+
 mod image;
 use image::ImageView;
 
+mod json;
+use json::{json_tree, JsonView};
+
 mod markdown;
 use markdown::MarkdownView;
 
@@ -63,16 +79,32 @@ use workspace::Workspace;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
-        MimeType::Plain(_) => 1,
+        MimeType::DataTable(_) => 9,
+        MimeType::Html(_) => 8,
+        MimeType::Png(_) => 7,
+        MimeType::Jpeg(_) => 6,
+        MimeType::Markdown(_) => 5,
+        MimeType::Json(_) => 4,
+        // JavaScript is only shown when there's no plain text, as kernels send
+        // it alongside for browsers to run, not for people to read.
+        MimeType::Javascript(_) => 1,
+        // Code, like text/x-python, is shown highlighted rather than as the
+        // plain text sent with it.
+        mimetype if code_language(mimetype).is_some() => 3,
+        MimeType::Plain(_) => 2,
         // All other media types are not supported in Zed at this time
         _ => 0,
     }
 }
 
+/// Ranks media types like [`rank_mime_type`], for outputs whose HTML can't be rendered.
+fn rank_mime_type_without_html(mimetype: &MimeType) -> usize {
+    match mimetype {
+        MimeType::Html(_) => 0,
+        mimetype => rank_mime_type(mimetype),
+    }
+}
+
 pub(crate) trait OutputContent {
     fn clipboard_content(&self, cx: &WindowContext) -> Option<ClipboardItem>;
     fn has_clipboard_content(&self, _cx: &WindowContext) -> bool {
@@ -122,10 +154,22 @@ pub enum Output {
         content: View<TableView>,
         display_id: Option<String>,
     },
+    Html {
+        content: View<HtmlView>,
+        display_id: Option<String>,
+    },
     Markdown {
         content: View<MarkdownView>,
         display_id: Option<String>,
     },
+    Code {
+        content: View<CodeView>,
+        display_id: Option<String>,
+    },
+    Json {
+        content: View<JsonView>,
+        display_id: Option<String>,
+    },
     ClearOutputWaitMarker,
 }
 
@@ -133,15 +177,43 @@ impl Output {
     fn render_output_controls<V: OutputContent + 'static>(
         v: View<V>,
         workspace: WeakView<Workspace>,
+        soft_wrap: Option<bool>,
         cx: &mut ViewContext<ExecutionView>,
     ) -> Option<AnyElement> {
-        if !v.has_clipboard_content(cx) && !v.has_buffer_content(cx) {
+        if !v.has_clipboard_content(cx) && !v.has_buffer_content(cx) && soft_wrap.is_none() {
             return None;
         }
 
         Some(
             h_flex()
                 .pl_1()
+                .when_some(soft_wrap, |el, soft_wrap| {
+                    el.child(
+                        IconButton::new(
+                            ElementId::Name("toggle-soft-wrap".into()),
+                            IconName::Return,
+                        )
+                        .style(ButtonStyle::Transparent)
+                        .selected(soft_wrap)
+                        .tooltip(move |cx| Tooltip::text(t!("repl.toggle_soft_wrap"), cx))
+                        .on_click(cx.listener(|execution_view, _, cx| {
+                            execution_view.toggle_soft_wrap(cx);
+                        })),
+                    )
+                })
+                .when(soft_wrap.is_some(), |el| {
+                    el.child(
+                        IconButton::new(
+                            ElementId::Name("search-output".into()),
+                            IconName::MagnifyingGlass,
+                        )
+                        .style(ButtonStyle::Transparent)
+                        .tooltip(move |cx| Tooltip::text(t!("repl.search_output"), cx))
+                        .on_click(cx.listener(|execution_view, _, cx| {
+                            execution_view.toggle_search(cx);
+                        })),
+                    )
+                })
                 .when(v.has_clipboard_content(cx), |el| {
                     let v = v.clone();
                     el.child(
@@ -205,15 +277,19 @@ impl Output {
         &self,
 
         workspace: WeakView<Workspace>,
+        soft_wrap: bool,
         cx: &mut ViewContext<ExecutionView>,
     ) -> impl IntoElement {
         let content = match self {
             Self::Plain { content, .. } => Some(content.clone().into_any_element()),
             Self::Markdown { content, .. } => Some(content.clone().into_any_element()),
+            Self::Code { content, .. } => Some(content.clone().into_any_element()),
+            Self::Json { content, .. } => Some(content.clone().into_any_element()),
             Self::Stream { content, .. } => Some(content.clone().into_any_element()),
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
+            Self::Html { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(cx),
             Self::ClearOutputWaitMarker => None,
         };
@@ -223,29 +299,95 @@ impl Output {
             .items_start()
             .child(div().flex_1().children(content))
             .children(match self {
-                Self::Plain { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), cx)
-                }
+                Self::Plain { content, .. } => Self::render_output_controls(
+                    content.clone(),
+                    workspace.clone(),
+                    Some(soft_wrap),
+                    cx,
+                ),
                 Self::Markdown { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), cx)
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
                 }
-                Self::Stream { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), cx)
+                Self::Code { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
                 }
-                Self::Image { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), cx)
+                Self::Json { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
                 }
-                Self::ErrorOutput(err) => {
-                    Self::render_output_controls(err.traceback.clone(), workspace.clone(), cx)
+                Self::Stream { content, .. } => Self::render_output_controls(
+                    content.clone(),
+                    workspace.clone(),
+                    Some(soft_wrap),
+                    cx,
+                ),
+                Self::Image { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
                 }
+                Self::ErrorOutput(err) => Self::render_output_controls(
+                    err.traceback.clone(),
+                    workspace.clone(),
+                    Some(soft_wrap),
+                    cx,
+                ),
                 Self::Message(_) => None,
                 Self::Table { content, .. } => {
-                    Self::render_output_controls(content.clone(), workspace.clone(), cx)
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
+                }
+                Self::Html { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace.clone(), None, cx)
                 }
                 Self::ClearOutputWaitMarker => None,
             })
     }
 
+    /// Returns the terminal that renders this output as plain text, if any.
+    fn terminal(&self) -> Option<&View<TerminalOutput>> {
+        match self {
+            Self::Plain { content, .. } | Self::Stream { content } => Some(content),
+            Self::ErrorOutput(error_view) => Some(&error_view.traceback),
+            _ => None,
+        }
+    }
+
+    /// Returns the plain text of this output, as it would be copied to the clipboard.
+    pub fn text_content(&self, cx: &WindowContext) -> Option<String> {
+        let clipboard_content = match self {
+            Self::Plain { content, .. } | Self::Stream { content } => content.clipboard_content(cx),
+            Self::Markdown { content, .. } => content.clipboard_content(cx),
+            Self::Code { content, .. } => content.clipboard_content(cx),
+            Self::Json { content, .. } => content.clipboard_content(cx),
+            Self::Image { content, .. } => content.clipboard_content(cx),
+            Self::Table { content, .. } => content.clipboard_content(cx),
+            Self::Html { content, .. } => content.clipboard_content(cx),
+            Self::ErrorOutput(error_view) => error_view.traceback.clipboard_content(cx),
+            Self::Message(message) => return Some(message.clone()),
+            Self::ClearOutputWaitMarker => None,
+        };
+        clipboard_content?.text()
+    }
+
+    /// Returns this output as markdown. Text is fenced as code, and images are
+    /// embedded as base64 data.
+    pub fn markdown_content(&self, cx: &WindowContext) -> Option<String> {
+        match self {
+            Self::Image { content, .. } => {
+                Some(format!("![output]({})", content.read(cx).data_url()))
+            }
+            // Tables are copied as markdown tables already.
+            Self::Markdown { .. } | Self::Table { .. } => self.text_content(cx),
+            Self::Code { content, .. } => self
+                .text_content(cx)
+                .map(|code| fenced_code_block(content.read(cx).language(), &code)),
+            Self::Json { .. } => self
+                .text_content(cx)
+                .map(|json| fenced_code_block("json", &json)),
+            Self::ClearOutputWaitMarker => None,
+            _ => self
+                .text_content(cx)
+                .map(|text| fenced_code_block("", &text)),
+        }
+    }
+
     pub fn display_id(&self) -> Option<String> {
         match self {
             Output::Plain { display_id, .. } => display_id.clone(),
@@ -254,13 +396,44 @@ impl Output {
             Output::ErrorOutput(_) => None,
             Output::Message(_) => None,
             Output::Table { display_id, .. } => display_id.clone(),
+            Output::Html { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
+            Output::Code { display_id, .. } => display_id.clone(),
+            Output::Json { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
 
-    pub fn new(data: &MimeBundle, display_id: Option<String>, cx: &mut WindowContext) -> Self {
-        match data.richest(rank_mime_type) {
+    /// Creates the output for the richest media type in `data` that Zed can
+    /// render. Code outputs are highlighted with grammars from `languages`.
+    pub fn new(
+        data: &MimeBundle,
+        display_id: Option<String>,
+        languages: Option<Arc<LanguageRegistry>>,
+        cx: &mut WindowContext,
+    ) -> Self {
+        if let Some(MimeType::Html(html)) = data.richest(rank_mime_type) {
+            if let Some(view) = HtmlView::from(html, cx) {
+                return Output::Html {
+                    content: cx.new_view(|_| view),
+                    display_id,
+                };
+            }
+        }
+
+        // JSON objects and arrays are shown as a tree, and any other JSON as code.
+        if let Some(MimeType::Json(value)) = data.richest(rank_mime_type_without_html) {
+            if let Some(value) = json_tree(value) {
+                return Output::Json {
+                    content: cx.new_view(|_| JsonView::new(value)),
+                    display_id,
+                };
+            }
+        }
+
+        // HTML that can't be rendered falls back to the next richest media type,
+        // which is usually text/plain.
+        match data.richest(rank_mime_type_without_html) {
             Some(MimeType::Plain(text)) => Output::Plain {
                 content: cx.new_view(|cx| TerminalOutput::from(text, cx)),
                 display_id,
@@ -283,8 +456,15 @@ impl Output {
                 content: cx.new_view(|cx| TableView::new(data, cx)),
                 display_id,
             },
-            // Any other media types are not supported
-            _ => Output::Message("Unsupported media type".to_string()),
+            Some(mimetype) => match code_content(mimetype) {
+                Some((language, code)) => Output::Code {
+                    content: cx.new_view(|cx| CodeView::new(language, code, languages, cx)),
+                    display_id,
+                },
+                // Any other media types are not supported
+                None => Output::Message("Unsupported media type".to_string()),
+            },
+            None => Output::Message("Unsupported media type".to_string()),
         }
     }
 }
@@ -301,6 +481,8 @@ pub enum ExecutionStatus {
     Shutdown,
     KernelErrored(String),
     Restarting,
+    /// The connection to a remote kernel dropped and is being re-established.
+    Reconnecting,
 }
 
 /// An ExecutionView shows the outputs of an execution.
@@ -311,32 +493,194 @@ pub struct ExecutionView {
     workspace: WeakView<Workspace>,
     pub outputs: Vec<Output>,
     pub status: ExecutionStatus,
+    /// The execution count the kernel assigned to this execution, once known.
+    pub execution_count: Option<usize>,
+    /// Whether long lines of plain text outputs wrap.
+    soft_wrap: bool,
+    /// The query editor shown while searching within the plain text outputs.
+    search_editor: Option<View<Editor>>,
+    _search_subscription: Option<Subscription>,
+    /// Whether the outputs are shown in full, rather than capped at the
+    /// `max_output_height` setting.
+    expanded: bool,
+    /// Whether the outputs are hidden behind a one-line summary.
+    collapsed: bool,
 }
 
 impl ExecutionView {
     pub fn new(
         status: ExecutionStatus,
         workspace: WeakView<Workspace>,
-        _cx: &mut ViewContext<Self>,
+        cx: &mut ViewContext<Self>,
     ) -> Self {
+        let soft_wrap = !matches!(
+            language_settings(None, None, cx).soft_wrap,
+            SoftWrap::None | SoftWrap::PreferLine
+        );
         Self {
             workspace,
             outputs: Default::default(),
             status,
+            execution_count: None,
+            soft_wrap,
+            search_editor: None,
+            _search_subscription: None,
+            expanded: false,
+            collapsed: false,
+        }
+    }
+
+    /// Shows the outputs in full, rather than capped at the
+    /// `max_output_height` setting.
+    pub fn expand(&mut self, cx: &mut ViewContext<Self>) {
+        self.expanded = true;
+        for output in &self.outputs {
+            Self::apply_capped(output, false, cx);
+        }
+        cx.notify();
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// The languages of the workspace's project, for highlighting code outputs.
+    fn languages(&self, cx: &AppContext) -> Option<Arc<LanguageRegistry>> {
+        let workspace = self.workspace.upgrade()?;
+        let languages = workspace.read(cx).project().read(cx).languages().clone();
+        Some(languages)
+    }
+
+    /// Hides the outputs behind a one-line summary, or shows them again.
+    /// Whether they're shown in full is kept, and applies once they're shown.
+    pub fn toggle_collapsed(&mut self, cx: &mut ViewContext<Self>) {
+        self.collapsed = !self.collapsed;
+        cx.notify();
+    }
+
+    /// Sets whether the outputs are collapsed without notifying, for callers
+    /// that collapse many execution views and notify once.
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    fn apply_capped(output: &Output, capped: bool, cx: &mut ViewContext<Self>) {
+        if let Some(terminal) = output.terminal() {
+            terminal.update(cx, |terminal, cx| terminal.set_capped(capped, cx));
         }
     }
 
+    /// Returns how many lines of the outputs don't fit in `max_lines`.
+    /// Outputs without any text, such as images, aren't counted.
+    pub fn hidden_line_count(&self, max_lines: usize, cx: &WindowContext) -> usize {
+        let line_count = self
+            .outputs
+            .iter()
+            .map(|output| match output.terminal() {
+                Some(terminal) => terminal.read(cx).row_count(),
+                None => output
+                    .text_content(cx)
+                    .map_or(0, |text| text.lines().count()),
+            })
+            .sum::<usize>();
+        line_count.saturating_sub(max_lines)
+    }
+
+    /// Switches the plain text outputs between wrapping long lines and
+    /// scrolling them horizontally.
+    pub fn toggle_soft_wrap(&mut self, cx: &mut ViewContext<Self>) {
+        self.soft_wrap = !self.soft_wrap;
+        for output in &self.outputs {
+            Self::apply_soft_wrap(output, self.soft_wrap, cx);
+        }
+        cx.notify();
+    }
+
+    fn apply_soft_wrap(output: &Output, soft_wrap: bool, cx: &mut ViewContext<Self>) {
+        if let Some(terminal) = output.terminal() {
+            terminal.update(cx, |terminal, cx| terminal.set_soft_wrap(soft_wrap, cx));
+        }
+    }
+
+    /// Shows or hides the field for searching within the plain text outputs.
+    /// While it's shown, those outputs only show the lines matching its query.
+    pub fn toggle_search(&mut self, cx: &mut ViewContext<Self>) {
+        if self.search_editor.take().is_some() {
+            self._search_subscription = None;
+            self.apply_search(cx);
+            return;
+        }
+
+        let search_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(t!("repl.search_output.placeholder"), cx);
+            editor
+        });
+        self._search_subscription = Some(cx.subscribe(
+            &search_editor,
+            |execution_view, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    execution_view.apply_search(cx);
+                }
+            },
+        ));
+        cx.focus_view(&search_editor);
+        self.search_editor = Some(search_editor);
+        cx.notify();
+    }
+
+    fn search_query(&self, cx: &AppContext) -> String {
+        self.search_editor
+            .as_ref()
+            .map(|editor| editor.read(cx).text(cx))
+            .unwrap_or_default()
+    }
+
+    fn apply_search(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.search_query(cx);
+        for output in &self.outputs {
+            Self::apply_filter(output, &query, cx);
+        }
+        cx.notify();
+    }
+
+    fn apply_filter(output: &Output, query: &str, cx: &mut ViewContext<Self>) {
+        if let Some(terminal) = output.terminal() {
+            terminal.update(cx, |terminal, cx| terminal.set_filter(query, cx));
+        }
+    }
+
+    /// Whether the kernel reported an error for this execution.
+    pub fn has_error(&self) -> bool {
+        self.error().is_some()
+    }
+
+    /// The first error the kernel reported for this execution, as
+    /// `name: value`, like `ZeroDivisionError: division by zero`.
+    pub fn error(&self) -> Option<String> {
+        self.outputs.iter().find_map(|output| match output {
+            Output::ErrorOutput(error) => Some(format!("{}: {}", error.ename, error.evalue)),
+            _ => None,
+        })
+    }
+
     /// Accept a Jupyter message belonging to this execution
     pub fn push_message(&mut self, message: &JupyterMessageContent, cx: &mut ViewContext<Self>) {
         let output: Output = match message {
             JupyterMessageContent::ExecuteResult(result) => Output::new(
                 &result.data,
                 result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                self.languages(cx),
                 cx,
             ),
             JupyterMessageContent::DisplayData(result) => Output::new(
                 &result.data,
                 result.transient.as_ref().and_then(|t| t.display_id.clone()),
+                self.languages(cx),
                 cx,
             ),
             JupyterMessageContent::StreamContent(result) => {
@@ -357,10 +701,19 @@ impl ExecutionView {
                     traceback: terminal,
                 })
             }
+            JupyterMessageContent::ExecuteInput(input) => {
+                self.execution_count = Some(input.execution_count.0);
+                cx.notify();
+                return;
+            }
             JupyterMessageContent::ExecuteReply(reply) => {
+                self.execution_count = Some(reply.execution_count.0);
                 for payload in reply.payload.iter() {
                     if let runtimelib::Payload::Page { data, .. } = payload {
-                        let output = Output::new(data, None, cx);
+                        let output = Output::new(data, None, self.languages(cx), cx);
+                        Self::apply_soft_wrap(&output, self.soft_wrap, cx);
+                        Self::apply_capped(&output, !self.expanded, cx);
+                        Self::apply_filter(&output, &self.search_query(cx), cx);
                         self.outputs.push(output);
                     }
                 }
@@ -399,23 +752,47 @@ impl ExecutionView {
             }
         }
 
+        Self::apply_soft_wrap(&output, self.soft_wrap, cx);
+        Self::apply_capped(&output, !self.expanded, cx);
+        Self::apply_filter(&output, &self.search_query(cx), cx);
         self.outputs.push(output);
 
         cx.notify();
     }
 
+    /// Replaces the outputs with the rendered `markdown`, for cells whose
+    /// source is shown as prose rather than sent to a kernel.
+    pub fn set_markdown(&mut self, markdown: String, cx: &mut ViewContext<Self>) {
+        let content = cx.new_view(|cx| MarkdownView::from(markdown, cx));
+        self.outputs = vec![Output::Markdown {
+            content,
+            display_id: None,
+        }];
+        self.status = ExecutionStatus::Finished;
+        cx.notify();
+    }
+
+    /// Replaces the outputs shown under `display_id` with `data`, returning
+    /// whether this execution has any such outputs.
     pub fn update_display_data(
         &mut self,
         data: &MimeBundle,
         display_id: &str,
         cx: &mut ViewContext<Self>,
-    ) {
+    ) -> bool {
         let mut any = false;
+        let soft_wrap = self.soft_wrap;
+        let capped = !self.expanded;
+        let query = self.search_query(cx);
+        let languages = self.languages(cx);
 
         self.outputs.iter_mut().for_each(|output| {
             if let Some(other_display_id) = output.display_id().as_ref() {
                 if other_display_id == display_id {
-                    *output = Output::new(data, Some(display_id.to_owned()), cx);
+                    *output = Output::new(data, Some(display_id.to_owned()), languages.clone(), cx);
+                    Self::apply_soft_wrap(output, soft_wrap, cx);
+                    Self::apply_capped(output, capped, cx);
+                    Self::apply_filter(output, &query, cx);
                     any = true;
                 }
             }
@@ -424,6 +801,48 @@ impl ExecutionView {
         if any {
             cx.notify();
         }
+        any
+    }
+
+    /// Opens the text of all outputs in a new read-only tab, where outputs
+    /// too large to browse inline can be searched or saved.
+    pub fn open_in_new_tab(&self, cx: &mut ViewContext<Self>) {
+        let text = self.text_content(cx);
+        let buffer = cx.new_model(|cx| {
+            let mut buffer =
+                Buffer::local(text, cx).with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        let editor = cx.new_view(|cx| {
+            let multibuffer = cx.new_model(|cx| {
+                MultiBuffer::singleton(buffer, cx).with_title("REPL Output".to_string())
+            });
+            Editor::for_multibuffer(multibuffer, None, false, cx)
+        });
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+            })
+            .ok();
+    }
+
+    /// Returns the text of all outputs with textual content, one output per line.
+    pub fn text_content(&self, cx: &WindowContext) -> String {
+        self.outputs
+            .iter()
+            .filter_map(|output| output.text_content(cx))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns all outputs as markdown, separated by blank lines.
+    pub fn markdown_content(&self, cx: &WindowContext) -> String {
+        self.outputs
+            .iter()
+            .filter_map(|output| output.markdown_content(cx))
+            .collect::<Vec<_>>()
+            .join("\n\n")
     }
 
     fn apply_terminal_text(&mut self, text: &str, cx: &mut ViewContext<Self>) -> Option<Output> {
@@ -451,9 +870,11 @@ impl ExecutionView {
 impl Render for ExecutionView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let status = match &self.status {
-            ExecutionStatus::ConnectingToKernel => Label::new("Connecting to kernel...")
-                .color(Color::Muted)
-                .into_any_element(),
+            ExecutionStatus::ConnectingToKernel => {
+                Label::new(t!("repl.execution.connecting_to_kernel"))
+                    .color(Color::Muted)
+                    .into_any_element()
+            }
             ExecutionStatus::Executing => h_flex()
                 .gap_2()
                 .child(
@@ -466,29 +887,34 @@ impl Render for ExecutionView {
                             |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
                         ),
                 )
-                .child(Label::new("Executing...").color(Color::Muted))
+                .child(Label::new(t!("repl.execution.executing")).color(Color::Muted))
                 .into_any_element(),
             ExecutionStatus::Finished => Icon::new(IconName::Check)
                 .size(IconSize::Small)
                 .into_any_element(),
-            ExecutionStatus::Unknown => Label::new("Unknown status")
+            ExecutionStatus::Unknown => Label::new(t!("repl.execution.unknown"))
                 .color(Color::Muted)
                 .into_any_element(),
-            ExecutionStatus::ShuttingDown => Label::new("Kernel shutting down...")
+            ExecutionStatus::ShuttingDown => Label::new(t!("repl.execution.shutting_down"))
                 .color(Color::Muted)
                 .into_any_element(),
-            ExecutionStatus::Restarting => Label::new("Kernel restarting...")
+            ExecutionStatus::Restarting => Label::new(t!("repl.execution.restarting"))
                 .color(Color::Muted)
                 .into_any_element(),
-            ExecutionStatus::Shutdown => Label::new("Kernel shutdown")
-                .color(Color::Muted)
+            ExecutionStatus::Reconnecting => Label::new(t!("repl.execution.reconnecting"))
+                .color(Color::Warning)
                 .into_any_element(),
-            ExecutionStatus::Queued => Label::new("Queued...")
+            ExecutionStatus::Shutdown => Label::new(t!("repl.execution.shutdown"))
                 .color(Color::Muted)
                 .into_any_element(),
-            ExecutionStatus::KernelErrored(error) => Label::new(format!("Kernel error: {}", error))
-                .color(Color::Error)
+            ExecutionStatus::Queued => Label::new(t!("repl.execution.queued"))
+                .color(Color::Muted)
                 .into_any_element(),
+            ExecutionStatus::KernelErrored(error) => {
+                Label::new(format!("{}: {}", t!("repl.execution.kernel_error"), error))
+                    .color(Color::Error)
+                    .into_any_element()
+            }
         };
 
         if self.outputs.is_empty() {
@@ -499,18 +925,125 @@ impl Render for ExecutionView {
                 .into_any_element();
         }
 
+        if self.collapsed {
+            return h_flex()
+                .min_h(cx.line_height())
+                .gap_2()
+                .child(
+                    Label::new(t_plural!("repl.collapsed_outputs", self.outputs.len()))
+                        .color(Color::Muted),
+                )
+                .children(match self.status {
+                    ExecutionStatus::Executing
+                    | ExecutionStatus::Queued
+                    | ExecutionStatus::Reconnecting => Some(status),
+                    _ => None,
+                })
+                .into_any_element();
+        }
+
+        let search = self.search_editor.clone().map(|search_editor| {
+            let query = self.search_query(cx);
+            let match_count = self
+                .outputs
+                .iter()
+                .filter_map(|output| output.terminal())
+                .map(|terminal| terminal.read(cx).match_count(&query))
+                .sum::<usize>();
+
+            h_flex()
+                .gap_2()
+                .mb_1()
+                .px_2()
+                .py_1()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .rounded_md()
+                .child(div().flex_1().child(search_editor))
+                .when(!query.is_empty(), |el| {
+                    el.child(
+                        Label::new(t_plural!("repl.search_output.matches", match_count))
+                            .color(Color::Muted),
+                    )
+                })
+        });
+
         div()
             .w_full()
+            .children(search)
             .children(
                 self.outputs
                     .iter()
-                    .map(|output| output.render(self.workspace.clone(), cx)),
+                    .map(|output| output.render(self.workspace.clone(), self.soft_wrap, cx)),
             )
             .children(match self.status {
                 ExecutionStatus::Executing => vec![status],
                 ExecutionStatus::Queued => vec![status],
+                ExecutionStatus::Reconnecting => vec![status],
                 _ => vec![],
             })
             .into_any_element()
     }
 }
+
+/// Wraps `code` in a markdown code fence tagged with `language`, using a fence
+/// longer than any run of backticks in the code.
+pub(crate) fn fenced_code_block(language: &str, code: &str) -> String {
+    let longest_backtick_run = code
+        .split(|c: char| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_backtick_run.max(2) + 1);
+    format!(
+        "{fence}{language}\n{}\n{fence}",
+        code.trim_end_matches('\n')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenced_code_block() {
+        assert_eq!(
+            fenced_code_block("python", "print(1)\n"),
+            "```python\nprint(1)\n```"
+        );
+        assert_eq!(fenced_code_block("", "a ``` b"), "````\na ``` b\n````");
+    }
+
+    #[test]
+    fn test_rank_mime_type() {
+        let plain = MimeType::Plain("f()".to_string());
+        let javascript = MimeType::Javascript("f()".to_string());
+        let python = MimeType::Other((
+            "text/x-python".to_string(),
+            serde_json::Value::String("f()".to_string()),
+        ));
+        let json = MimeType::Json(serde_json::json!({ "a": 1 }));
+
+        assert!(rank_mime_type(&plain) > rank_mime_type(&javascript));
+        assert!(rank_mime_type(&javascript) > 0);
+        assert!(rank_mime_type(&python) > rank_mime_type(&plain));
+        assert!(rank_mime_type(&json) > rank_mime_type(&plain));
+
+        // Kernels send plain text with every bundle, so code is only
+        // highlighted if it's preferred to it.
+        let bundle = MimeBundle {
+            content: vec![plain.clone(), python],
+        };
+        assert!(matches!(
+            bundle.richest(rank_mime_type),
+            Some(MimeType::Other((mimetype, _))) if mimetype == "text/x-python"
+        ));
+        let bundle = MimeBundle {
+            content: vec![javascript, plain],
+        };
+        assert!(matches!(
+            bundle.richest(rank_mime_type),
+            Some(MimeType::Plain(_))
+        ));
+    }
+}
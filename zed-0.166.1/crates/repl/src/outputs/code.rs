@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use gpui::{div, prelude::*, ClipboardItem, Model, Task, ViewContext, WindowContext};
+use language::{Buffer, LanguageRegistry};
+use markdown_preview::{
+    markdown_elements::ParsedMarkdown, markdown_parser::parse_markdown,
+    markdown_renderer::render_markdown_block,
+};
+use runtimelib::MimeType;
+use ui::v_flex;
+
+use crate::outputs::{fenced_code_block, OutputContent};
+
+/// Returns the language of outputs that are code, such as `application/json`
+/// or `text/x-python`, so they can be syntax highlighted.
+pub fn code_language(mimetype: &MimeType) -> Option<&str> {
+    match mimetype {
+        MimeType::Json(_) => Some("json"),
+        MimeType::Javascript(_) => Some("javascript"),
+        MimeType::Other((mimetype, serde_json::Value::String(_))) => {
+            mimetype.strip_prefix("text/x-")
+        }
+        _ => None,
+    }
+}
+
+/// Returns the language and source of outputs that are code.
+pub fn code_content(mimetype: &MimeType) -> Option<(String, String)> {
+    let language = code_language(mimetype)?.to_string();
+    let code = match mimetype {
+        // JSON sent as a string that doesn't parse is shown as is.
+        MimeType::Json(serde_json::Value::String(json)) => json.clone(),
+        MimeType::Json(value) => serde_json::to_string_pretty(value).ok()?,
+        MimeType::Javascript(code) => code.clone(),
+        MimeType::Other((_, serde_json::Value::String(code))) => code.clone(),
+        _ => return None,
+    };
+    Some((language, code))
+}
+
+/// An output of code, highlighted with the grammar of its language when Zed
+/// has one.
+pub struct CodeView {
+    language: String,
+    code: String,
+    contents: Option<ParsedMarkdown>,
+    parsing_task: Option<Task<anyhow::Result<()>>>,
+}
+
+impl CodeView {
+    pub fn new(
+        language: String,
+        code: String,
+        languages: Option<Arc<LanguageRegistry>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        // Code blocks in markdown are highlighted just like buffers are.
+        let markdown = fenced_code_block(&language, &code);
+        let task = cx.spawn(|code_view, mut cx| {
+            let parsed = cx
+                .background_executor()
+                .spawn(async move { parse_markdown(&markdown, None, languages).await });
+
+            async move {
+                let contents = parsed.await;
+
+                code_view.update(&mut cx, |code_view, cx| {
+                    code_view.parsing_task.take();
+                    code_view.contents = Some(contents);
+                    cx.notify();
+                })
+            }
+        });
+
+        Self {
+            language,
+            code,
+            contents: None,
+            parsing_task: Some(task),
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+}
+
+impl OutputContent for CodeView {
+    fn clipboard_content(&self, _cx: &WindowContext) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.code.clone()))
+    }
+
+    fn has_clipboard_content(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, cx: &mut WindowContext) -> Option<Model<Buffer>> {
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(self.code.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+impl Render for CodeView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(parsed) = self.contents.as_ref() else {
+            return div().child(self.code.clone()).into_any_element();
+        };
+
+        let mut render_context = markdown_preview::markdown_renderer::RenderContext::new(None, cx);
+
+        v_flex()
+            .children(
+                parsed
+                    .children
+                    .iter()
+                    .map(|child| render_markdown_block(child, &mut render_context)),
+            )
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_code_content() {
+        assert_eq!(
+            code_content(&MimeType::Javascript("f()".to_string())),
+            Some(("javascript".to_string(), "f()".to_string()))
+        );
+        assert_eq!(
+            code_content(&MimeType::Other((
+                "text/x-python".to_string(),
+                json!("print(1)")
+            ))),
+            Some(("python".to_string(), "print(1)".to_string()))
+        );
+        assert_eq!(
+            code_content(&MimeType::Other((
+                "application/vnd.custom".to_string(),
+                json!("data")
+            ))),
+            None
+        );
+        // Code must be sent as a string.
+        assert_eq!(
+            code_content(&MimeType::Other((
+                "text/x-python".to_string(),
+                json!({ "code": "print(1)" })
+            ))),
+            None
+        );
+        assert_eq!(
+            code_content(&MimeType::Json(json!("{\"a\":"))),
+            Some(("json".to_string(), "{\"a\":".to_string()))
+        );
+        assert_eq!(code_content(&MimeType::Plain("text".to_string())), None);
+    }
+}
@@ -0,0 +1,451 @@
+//! # HTML Output for REPL
+//!
+//! This module renders the subset of HTML that kernels commonly use for rich
+//! text output, such as the `text/html` representation of a pandas DataFrame:
+//!
+//! - Tables (`table`, `thead`, `tbody`, `tfoot`, `tr`, `th`, `td`)
+//! - Paragraphs and line breaks (`p`, `div`, `span`, `br`)
+//! - Bold text (`b`, `strong`)
+//! - Links (`a`) to `http` and `https` URLs
+//!
+//! Scripts and styles are never run or applied; their contents are dropped.
+//! HTML using any other element isn't rendered here, and the output falls back
+//! to its `text/plain` representation instead.
+
+use anyhow::{anyhow, bail, Result};
+use gpui::{ClipboardItem, FontWeight, TextRun};
+use html5ever::{driver::ParseOpts, parse_document, tendril::TendrilSink};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{div, prelude::*, v_flex, IntoElement, Styled};
+
+use crate::outputs::OutputContent;
+
+/// HtmlView renders a static HTML document inline in a buffer.
+pub struct HtmlView {
+    blocks: Vec<HtmlBlock>,
+    clipboard_content: ClipboardItem,
+}
+
+enum HtmlBlock {
+    Paragraph(Vec<HtmlSpan>),
+    Table {
+        rows: Vec<Vec<HtmlCell>>,
+        column_widths: Vec<Pixels>,
+    },
+}
+
+struct HtmlCell {
+    header: bool,
+    spans: Vec<HtmlSpan>,
+}
+
+#[derive(Clone)]
+struct HtmlSpan {
+    text: String,
+    bold: bool,
+    link: Option<SharedString>,
+}
+
+fn spans_text(spans: &[HtmlSpan]) -> String {
+    spans
+        .iter()
+        .map(|span| span.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Collapses each run of whitespace in `text` into a single space, as HTML does.
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut after_whitespace = false;
+    for char in text.chars() {
+        if char.is_whitespace() {
+            if !after_whitespace {
+                collapsed.push(' ');
+            }
+            after_whitespace = true;
+        } else {
+            collapsed.push(char);
+            after_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+// Horizontal padding and borders of a table cell, in addition to its text.
+const CELL_X_PADDING: Pixels = px(22.);
+
+impl HtmlView {
+    /// Parses `html` into a view, or returns `None` if it uses HTML that this
+    /// view doesn't support.
+    pub fn from(html: &str, cx: &mut WindowContext) -> Option<Self> {
+        // Most rich outputs use HTML that isn't supported, so this is routine
+        // and they fall back to another media type.
+        let mut blocks = parse_html(html)
+            .map_err(|error| log::debug!("not rendering HTML output: {error}"))
+            .ok()?;
+        if blocks.is_empty() {
+            return None;
+        }
+
+        let text_font = ThemeSettings::get_global(cx).buffer_font.clone();
+        let font_size = ThemeSettings::get_global(cx).buffer_font_size;
+        let color = cx.text_style().color;
+        for block in &mut blocks {
+            let HtmlBlock::Table {
+                rows,
+                column_widths,
+            } = block
+            else {
+                continue;
+            };
+
+            for row in rows.iter() {
+                for (ix, cell) in row.iter().enumerate() {
+                    let text = spans_text(&cell.spans);
+                    let mut font = text_font.clone();
+                    if cell.header {
+                        font.weight = FontWeight::BOLD;
+                    }
+                    let runs = [TextRun {
+                        len: text.len(),
+                        font,
+                        color,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    }];
+                    let width = cx
+                        .text_system()
+                        .layout_line(&text, font_size, &runs)
+                        .map(|layout| layout.width)
+                        .unwrap_or(px(0.));
+                    if column_widths.len() <= ix {
+                        column_widths.resize(ix + 1, px(0.));
+                    }
+                    column_widths[ix] = column_widths[ix].max(width);
+                }
+            }
+        }
+
+        let clipboard_content = ClipboardItem::new_string(Self::plain_text(&blocks));
+        Some(Self {
+            blocks,
+            clipboard_content,
+        })
+    }
+
+    /// Returns the text of the blocks, with table cells separated by tabs.
+    fn plain_text(blocks: &[HtmlBlock]) -> String {
+        blocks
+            .iter()
+            .map(|block| match block {
+                HtmlBlock::Paragraph(spans) => spans_text(spans),
+                HtmlBlock::Table { rows, .. } => rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|cell| spans_text(&cell.spans))
+                            .collect::<Vec<_>>()
+                            .join("\t")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn render_spans(spans: &[HtmlSpan], cx: &WindowContext) -> impl IntoElement {
+        h_flex()
+            .flex_wrap()
+            .children(spans.iter().enumerate().map(|(ix, span)| {
+                let text = div()
+                    .child(span.text.clone())
+                    .when(span.bold, |text| text.font_weight(FontWeight::BOLD));
+                match span.link.clone() {
+                    Some(link) => text
+                        .id(ix)
+                        .text_color(cx.theme().colors().text_accent)
+                        .text_decoration_1()
+                        .cursor_pointer()
+                        .on_click(move |_, cx| cx.open_url(&link))
+                        .into_any_element(),
+                    None => text.into_any_element(),
+                }
+            }))
+    }
+
+    fn render_table(
+        table_ix: usize,
+        rows: &[Vec<HtmlCell>],
+        column_widths: &[Pixels],
+        cx: &WindowContext,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        let line_height = cx.line_height();
+
+        v_flex()
+            .id(("html-table", table_ix))
+            .overflow_x_scroll()
+            .w_full()
+            .children(rows.iter().map(|row| {
+                h_flex().children(row.iter().zip(column_widths).map(|(cell, width)| {
+                    div()
+                        .min_w(*width + CELL_X_PADDING)
+                        .w(*width + CELL_X_PADDING)
+                        .px_2()
+                        .py(line_height / 4.)
+                        .border_1()
+                        .border_color(theme.colors().border)
+                        .when(cell.header, |cell| {
+                            cell.bg(theme.colors().border_focused)
+                                .font_weight(FontWeight::BOLD)
+                        })
+                        .child(Self::render_spans(&cell.spans, cx))
+                }))
+            }))
+    }
+}
+
+impl Render for HtmlView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w_full()
+            .gap_2()
+            .children(
+                self.blocks
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, block)| match block {
+                        HtmlBlock::Paragraph(spans) => {
+                            Self::render_spans(spans, cx).into_any_element()
+                        }
+                        HtmlBlock::Table {
+                            rows,
+                            column_widths,
+                        } => Self::render_table(ix, rows, column_widths, cx).into_any_element(),
+                    }),
+            )
+    }
+}
+
+impl OutputContent for HtmlView {
+    fn clipboard_content(&self, _cx: &WindowContext) -> Option<ClipboardItem> {
+        Some(self.clipboard_content.clone())
+    }
+
+    fn has_clipboard_content(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+}
+
+fn parse_html(html: &str) -> Result<Vec<HtmlBlock>> {
+    let dom = parse_document(RcDom::default(), ParseOpts::default())
+        .from_utf8()
+        .read_from(&mut html.as_bytes())?;
+    let mut parser = HtmlParser::default();
+    parser.visit(&dom.document)?;
+    parser.finish_paragraph();
+    Ok(parser.blocks)
+}
+
+#[derive(Default)]
+struct HtmlParser {
+    blocks: Vec<HtmlBlock>,
+    paragraph: Vec<HtmlSpan>,
+    table: Option<Vec<Vec<HtmlCell>>>,
+    in_cell: bool,
+    bold: usize,
+    link: Option<SharedString>,
+}
+
+impl HtmlParser {
+    fn visit(&mut self, node: &Handle) -> Result<()> {
+        let (name, attrs) = match &node.data {
+            NodeData::Document => return self.visit_children(node),
+            NodeData::Text { contents } => {
+                self.push_text(&contents.borrow());
+                return Ok(());
+            }
+            NodeData::Element { name, attrs, .. } => (name, attrs),
+            NodeData::Doctype { .. }
+            | NodeData::Comment { .. }
+            | NodeData::ProcessingInstruction { .. } => return Ok(()),
+        };
+
+        match name.local.as_ref() {
+            "head" | "script" | "style" => {}
+            "html" | "body" | "div" | "span" | "thead" | "tbody" | "tfoot" => {
+                self.visit_children(node)?
+            }
+            "p" => {
+                self.finish_paragraph();
+                self.visit_children(node)?;
+                self.finish_paragraph();
+            }
+            "br" => self.push_span("\n".to_string()),
+            "b" | "strong" => {
+                self.bold += 1;
+                self.visit_children(node)?;
+                self.bold -= 1;
+            }
+            "a" => {
+                let href = attrs
+                    .borrow()
+                    .iter()
+                    .find(|attr| attr.name.local.as_ref() == "href")
+                    .map(|attr| attr.value.to_string())
+                    .filter(|href| href.starts_with("https://") || href.starts_with("http://"));
+                let outer_link = std::mem::replace(&mut self.link, href.map(Into::into));
+                self.visit_children(node)?;
+                self.link = outer_link;
+            }
+            "table" => {
+                if self.table.is_some() {
+                    bail!("nested tables are not supported");
+                }
+                self.finish_paragraph();
+                self.table = Some(Vec::new());
+                self.visit_children(node)?;
+                if let Some(rows) = self.table.take() {
+                    self.blocks.push(HtmlBlock::Table {
+                        rows,
+                        column_widths: Vec::new(),
+                    });
+                }
+            }
+            "tr" => {
+                let table = self
+                    .table
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("table row outside of a table"))?;
+                table.push(Vec::new());
+                self.visit_children(node)?;
+            }
+            tag @ ("th" | "td") => {
+                let row = self
+                    .table
+                    .as_mut()
+                    .and_then(|table| table.last_mut())
+                    .ok_or_else(|| anyhow!("table cell outside of a table row"))?;
+                row.push(HtmlCell {
+                    header: tag == "th",
+                    spans: Vec::new(),
+                });
+                self.in_cell = true;
+                self.visit_children(node)?;
+                self.in_cell = false;
+            }
+            tag => bail!("unsupported HTML element <{tag}>"),
+        }
+        Ok(())
+    }
+
+    fn visit_children(&mut self, node: &Handle) -> Result<()> {
+        for child in node.children.borrow().iter() {
+            self.visit(child)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the spans that text is currently added to, if any. Text inside
+    /// a table but outside of its cells is dropped.
+    fn current_spans(&mut self) -> Option<&mut Vec<HtmlSpan>> {
+        if self.in_cell {
+            let row = self.table.as_mut()?.last_mut()?;
+            Some(&mut row.last_mut()?.spans)
+        } else if self.table.is_none() {
+            Some(&mut self.paragraph)
+        } else {
+            None
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let text = collapse_whitespace(text);
+        let Some(spans) = self.current_spans() else {
+            return;
+        };
+        // Whitespace only separates the text around it.
+        if text.trim().is_empty() && spans.is_empty() {
+            return;
+        }
+        self.push_span(text);
+    }
+
+    fn push_span(&mut self, text: String) {
+        let span = HtmlSpan {
+            text,
+            bold: self.bold > 0,
+            link: self.link.clone(),
+        };
+        if let Some(spans) = self.current_spans() {
+            spans.push(span);
+        }
+    }
+
+    fn finish_paragraph(&mut self) {
+        if !self.paragraph.is_empty() {
+            self.blocks
+                .push(HtmlBlock::Paragraph(std::mem::take(&mut self.paragraph)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dataframe_html() {
+        let html = r#"<div>
+<style scoped>
+    .dataframe tbody tr th { vertical-align: top; }
+</style>
+<table border="1" class="dataframe">
+  <thead>
+    <tr style="text-align: right;"><th></th><th>name</th><th>age</th></tr>
+  </thead>
+  <tbody>
+    <tr><th>0</th><td>Alice</td><td>30</td></tr>
+    <tr><th>1</th><td><b>Bob</b></td><td>28</td></tr>
+  </tbody>
+</table>
+<p>2 rows × 2 columns</p>
+</div>"#;
+
+        let blocks = parse_html(html).unwrap();
+        assert_eq!(
+            HtmlView::plain_text(&blocks),
+            "\tname\tage\n0\tAlice\t30\n1\tBob\t28\n\n2 rows × 2 columns"
+        );
+        let HtmlBlock::Table { rows, .. } = &blocks[0] else {
+            panic!("expected a table");
+        };
+        assert!(rows[0][1].header);
+        assert!(!rows[1][1].header);
+        assert!(rows[2][1].spans[0].bold);
+    }
+
+    #[test]
+    fn test_parse_links() {
+        let html = r#"<p>See <a href="https://zed.dev">Zed</a> or <a href="javascript:alert(1)">this</a></p>"#;
+        let blocks = parse_html(html).unwrap();
+        let HtmlBlock::Paragraph(spans) = &blocks[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(HtmlView::plain_text(&blocks), "See Zed or this");
+        assert_eq!(spans[1].link.as_deref(), Some("https://zed.dev"));
+        assert_eq!(spans[3].link, None);
+    }
+
+    #[test]
+    fn test_unsupported_html_is_rejected() {
+        assert!(parse_html("<p>An image: <img src=\"plot.png\"></p>").is_err());
+        assert!(parse_html("<table><tr><td><table></table></td></tr></table>").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use base64::{
     alphabet,
-    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    engine::{general_purpose::STANDARD, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
     Engine as _,
 };
 use gpui::{img, ClipboardItem, Image, ImageFormat, Pixels, RenderImage, WindowContext};
@@ -71,6 +71,21 @@ impl ImageView {
             image: Arc::new(gpui_image_data),
         })
     }
+
+    /// Returns the image as a `data:` URL, for embedding it in markdown.
+    pub fn data_url(&self) -> String {
+        let mime_type = match self.clipboard_image.format {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Svg => "image/svg+xml",
+            ImageFormat::Bmp => "image/bmp",
+            ImageFormat::Tiff => "image/tiff",
+        };
+        let data = STANDARD.encode(&self.clipboard_image.bytes);
+        format!("data:{mime_type};base64,{data}")
+    }
 }
 
 impl Render for ImageView {
@@ -0,0 +1,426 @@
+//! # JSON Output for REPL
+//!
+//! Renders `application/json` outputs as a tree, whose objects and arrays can
+//! be expanded and collapsed. Only the top level is expanded at first, so that
+//! large API responses and config dumps stay readable.
+//!
+//! JSON that isn't an object or an array, or that kernels send as a string
+//! which doesn't parse, is shown as pretty-printed text instead.
+//!
+//! Expanding an object or an array shows its first [`CHILDREN_PER_PAGE`]
+//! children, and more are shown a page at a time, so that expanding an array
+//! of thousands of items doesn't lay them all out at once.
+
+use std::collections::{HashMap, HashSet};
+
+use gpui::{AnyElement, ClipboardItem, Model, ViewContext, WindowContext};
+use i18n::t_plural;
+use language::Buffer;
+use serde_json::Value;
+use ui::{prelude::*, v_flex, Disclosure};
+
+use crate::outputs::OutputContent;
+
+/// The number of children of an object or array shown when it's expanded, and
+/// each time more are shown.
+const CHILDREN_PER_PAGE: usize = 100;
+
+/// Returns the JSON to render as a tree, if it's an object or an array.
+/// Kernels sometimes send JSON as a string, so strings are parsed too.
+pub fn json_tree(value: &Value) -> Option<Value> {
+    match value {
+        Value::Object(_) | Value::Array(_) => Some(value.clone()),
+        Value::String(text) => match serde_json::from_str(text) {
+            Ok(value @ (Value::Object(_) | Value::Array(_))) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A line of the tree, for a value that's visible given which objects and
+/// arrays are expanded.
+#[derive(Debug, PartialEq)]
+struct JsonRow {
+    depth: usize,
+    /// The key or index of the value in its parent. The root has none.
+    key: Option<String>,
+    /// The JSON pointer of the value, which identifies it in the tree.
+    pointer: String,
+    kind: JsonRowKind,
+}
+
+#[derive(Debug, PartialEq)]
+enum JsonRowKind {
+    Object {
+        len: usize,
+        expanded: bool,
+    },
+    Array {
+        len: usize,
+        expanded: bool,
+    },
+    String(String),
+    Number(String),
+    Bool(bool),
+    Null,
+    /// Stands in for the children of the object or array at `pointer` that
+    /// aren't shown yet.
+    More {
+        remaining: usize,
+    },
+}
+
+/// Appends the rows for `value` and, if it's expanded, its children. Only as
+/// many children as `shown` allows for the value are appended, followed by a
+/// row for the rest.
+fn push_rows(
+    value: &Value,
+    key: Option<String>,
+    pointer: String,
+    depth: usize,
+    expanded: &HashSet<String>,
+    shown: &HashMap<String, usize>,
+    rows: &mut Vec<JsonRow>,
+) {
+    let is_expanded = expanded.contains(&pointer);
+    let kind = match value {
+        Value::Object(object) => JsonRowKind::Object {
+            len: object.len(),
+            expanded: is_expanded,
+        },
+        Value::Array(array) => JsonRowKind::Array {
+            len: array.len(),
+            expanded: is_expanded,
+        },
+        Value::String(string) => JsonRowKind::String(string.clone()),
+        Value::Number(number) => JsonRowKind::Number(number.to_string()),
+        Value::Bool(value) => JsonRowKind::Bool(*value),
+        Value::Null => JsonRowKind::Null,
+    };
+    rows.push(JsonRow {
+        depth,
+        key,
+        pointer: pointer.clone(),
+        kind,
+    });
+
+    if !is_expanded {
+        return;
+    }
+    let limit = shown.get(&pointer).copied().unwrap_or(CHILDREN_PER_PAGE);
+    let len = match value {
+        Value::Object(object) => {
+            for (key, child) in object.iter().take(limit) {
+                let child_pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                push_rows(
+                    child,
+                    Some(key.clone()),
+                    child_pointer,
+                    depth + 1,
+                    expanded,
+                    shown,
+                    rows,
+                );
+            }
+            object.len()
+        }
+        Value::Array(array) => {
+            for (ix, child) in array.iter().enumerate().take(limit) {
+                let child_pointer = format!("{pointer}/{ix}");
+                push_rows(
+                    child,
+                    Some(ix.to_string()),
+                    child_pointer,
+                    depth + 1,
+                    expanded,
+                    shown,
+                    rows,
+                );
+            }
+            array.len()
+        }
+        _ => return,
+    };
+    if len > limit {
+        rows.push(JsonRow {
+            depth: depth + 1,
+            key: None,
+            pointer,
+            kind: JsonRowKind::More {
+                remaining: len - limit,
+            },
+        });
+    }
+}
+
+/// Escapes a key for use in a JSON pointer, as described in RFC 6901.
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// JsonView renders JSON as a tree of collapsible objects and arrays.
+pub struct JsonView {
+    value: Value,
+    /// The JSON pointers of the objects and arrays that are expanded.
+    expanded: HashSet<String>,
+    /// How many children are shown of the objects and arrays that show more
+    /// than the first page of them, by JSON pointer.
+    shown: HashMap<String, usize>,
+    /// The visible lines of the tree, rebuilt when it's expanded or collapsed.
+    rows: Vec<JsonRow>,
+    pretty: String,
+}
+
+impl JsonView {
+    pub fn new(value: Value) -> Self {
+        let pretty = serde_json::to_string_pretty(&value).unwrap_or_default();
+        let mut view = Self {
+            value,
+            expanded: HashSet::from_iter([String::new()]),
+            shown: HashMap::default(),
+            rows: Vec::new(),
+            pretty,
+        };
+        view.rebuild_rows();
+        view
+    }
+
+    /// Expands or collapses the object or array at `pointer`.
+    fn toggle(&mut self, pointer: &str) {
+        if !self.expanded.remove(pointer) {
+            self.expanded.insert(pointer.to_string());
+        }
+        self.rebuild_rows();
+    }
+
+    /// Shows another page of the children of the object or array at `pointer`.
+    fn show_more(&mut self, pointer: &str) {
+        *self
+            .shown
+            .entry(pointer.to_string())
+            .or_insert(CHILDREN_PER_PAGE) += CHILDREN_PER_PAGE;
+        self.rebuild_rows();
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        push_rows(
+            &self.value,
+            None,
+            String::new(),
+            0,
+            &self.expanded,
+            &self.shown,
+            &mut self.rows,
+        );
+    }
+
+    fn render_more(
+        &self,
+        depth: usize,
+        pointer: &str,
+        remaining: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let pointer = pointer.to_string();
+        h_flex()
+            .pl(px(16. * depth as f32))
+            .child(
+                Button::new(
+                    ElementId::Name(format!("json-more-{pointer}").into()),
+                    t_plural!("repl.json.show_more", remaining.min(CHILDREN_PER_PAGE)).to_string(),
+                )
+                .style(ButtonStyle::Subtle)
+                .label_size(LabelSize::Small)
+                .on_click(cx.listener(move |json_view, _, cx| {
+                    json_view.show_more(&pointer);
+                    cx.notify();
+                })),
+            )
+            .into_any_element()
+    }
+
+    fn render_row(&self, row: &JsonRow, cx: &mut ViewContext<Self>) -> AnyElement {
+        let (value, color) = match &row.kind {
+            JsonRowKind::Object { len, expanded } => (
+                match expanded {
+                    true => "{".to_string(),
+                    false => format!("{{…}} {}", t_plural!("repl.json.keys", *len)),
+                },
+                Color::Muted,
+            ),
+            JsonRowKind::Array { len, expanded } => (
+                match expanded {
+                    true => "[".to_string(),
+                    false => format!("[…] {}", t_plural!("repl.json.items", *len)),
+                },
+                Color::Muted,
+            ),
+            JsonRowKind::String(string) => (
+                serde_json::to_string(string).unwrap_or_default(),
+                Color::Success,
+            ),
+            JsonRowKind::Number(number) => (number.clone(), Color::Accent),
+            JsonRowKind::Bool(value) => (value.to_string(), Color::Accent),
+            JsonRowKind::Null => ("null".to_string(), Color::Muted),
+            JsonRowKind::More { remaining } => {
+                return self.render_more(row.depth, &row.pointer, *remaining, cx);
+            }
+        };
+        let is_container = matches!(
+            row.kind,
+            JsonRowKind::Object { .. } | JsonRowKind::Array { .. }
+        );
+        let is_expanded = matches!(
+            row.kind,
+            JsonRowKind::Object { expanded: true, .. } | JsonRowKind::Array { expanded: true, .. }
+        );
+
+        let pointer = row.pointer.clone();
+        h_flex()
+            .pl(px(16. * row.depth as f32))
+            .gap_1()
+            .map(|el| {
+                if is_container {
+                    el.child(
+                        Disclosure::new(
+                            ElementId::Name(format!("json-{pointer}").into()),
+                            is_expanded,
+                        )
+                        .on_click(cx.listener(move |json_view, _, cx| {
+                            json_view.toggle(&pointer);
+                            cx.notify();
+                        })),
+                    )
+                } else {
+                    // Line leaves up with the keys of their collapsible siblings.
+                    el.child(div().w(px(16.)))
+                }
+            })
+            .when_some(row.key.as_ref(), |el, key| {
+                el.child(div().child(format!("{key}:")))
+            })
+            .child(div().text_color(color.color(cx)).child(value))
+            .into_any_element()
+    }
+}
+
+impl OutputContent for JsonView {
+    fn clipboard_content(&self, _cx: &WindowContext) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.pretty.clone()))
+    }
+
+    fn has_clipboard_content(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, cx: &mut WindowContext) -> Option<Model<Buffer>> {
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::local(self.pretty.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+impl Render for JsonView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().font_buffer(cx).children(
+            self.rows
+                .iter()
+                .map(|row| self.render_row(row, cx))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_tree() {
+        assert_eq!(json_tree(&json!({ "a": 1 })), Some(json!({ "a": 1 })));
+        assert_eq!(json_tree(&json!([1, 2])), Some(json!([1, 2])));
+        assert_eq!(json_tree(&json!("{\"a\": [1]}")), Some(json!({ "a": [1] })));
+        // Scalars and strings that don't parse are shown as text.
+        assert_eq!(json_tree(&json!("{\"a\":")), None);
+        assert_eq!(json_tree(&json!("text")), None);
+        assert_eq!(json_tree(&json!(1)), None);
+    }
+
+    #[test]
+    fn test_rows() {
+        let mut view = JsonView::new(json!({ "a/b": [true, null], "c": { "d": "e" } }));
+        let summarize = |view: &JsonView| {
+            view.rows
+                .iter()
+                .map(|row| (row.depth, row.key.clone(), row.pointer.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        // Only the root is expanded at first.
+        assert_eq!(
+            summarize(&view),
+            [
+                (0, None, "".to_string()),
+                (1, Some("a/b".to_string()), "/a~1b".to_string()),
+                (1, Some("c".to_string()), "/c".to_string()),
+            ]
+        );
+        assert_eq!(
+            view.rows[1].kind,
+            JsonRowKind::Array {
+                len: 2,
+                expanded: false
+            }
+        );
+
+        view.toggle("/a~1b");
+        assert_eq!(
+            summarize(&view),
+            [
+                (0, None, "".to_string()),
+                (1, Some("a/b".to_string()), "/a~1b".to_string()),
+                (2, Some("0".to_string()), "/a~1b/0".to_string()),
+                (2, Some("1".to_string()), "/a~1b/1".to_string()),
+                (1, Some("c".to_string()), "/c".to_string()),
+            ]
+        );
+        // Pointers can be resolved against the value they came from.
+        assert_eq!(view.value.pointer("/a~1b/0"), Some(&json!(true)));
+
+        view.toggle("");
+        assert_eq!(summarize(&view), [(0, None, "".to_string())]);
+    }
+
+    #[test]
+    fn test_large_arrays_show_a_page_at_a_time() {
+        let mut view = JsonView::new(Value::Array(vec![json!(0); 250]));
+        // The root is expanded, with a row for the items that aren't shown.
+        assert_eq!(view.rows.len(), 1 + CHILDREN_PER_PAGE + 1);
+        assert_eq!(
+            view.rows.last().unwrap().kind,
+            JsonRowKind::More { remaining: 150 }
+        );
+
+        view.show_more("");
+        assert_eq!(view.rows.len(), 1 + 2 * CHILDREN_PER_PAGE + 1);
+        assert_eq!(
+            view.rows.last().unwrap().kind,
+            JsonRowKind::More { remaining: 50 }
+        );
+
+        view.show_more("");
+        assert_eq!(view.rows.len(), 1 + 250);
+        assert_eq!(view.rows.last().unwrap().pointer, "/249");
+    }
+}
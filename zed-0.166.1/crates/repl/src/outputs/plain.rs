@@ -21,16 +21,19 @@ use alacritty_terminal::{
     term::Config,
     vte::ansi::Processor,
 };
-use gpui::{canvas, size, ClipboardItem, FontStyle, Model, TextStyle, WhiteSpace};
+use gpui::{
+    canvas, point, size, Bounds, ClipboardItem, FontStyle, HighlightStyle, Model, StyledText,
+    TextStyle, WhiteSpace,
+};
 use language::Buffer;
 use settings::Settings as _;
-use std::mem;
+use std::{mem, ops::Range};
 use terminal::ZedListener;
-use terminal_view::terminal_element::TerminalElement;
+use terminal_view::terminal_element::{LayoutCell, LayoutRect, TerminalElement};
 use theme::ThemeSettings;
 use ui::{prelude::*, IntoElement};
 
-use crate::outputs::OutputContent;
+use crate::{outputs::OutputContent, ReplSettings};
 
 /// The `TerminalOutput` struct handles the parsing and rendering of text input,
 /// simulating a basic terminal environment within REPL output.
@@ -51,10 +54,21 @@ pub struct TerminalOutput {
     parser: Processor,
     /// Alacritty terminal instance that manages the terminal state and content.
     handler: alacritty_terminal::Term<ZedListener>,
+    /// Whether long lines wrap, or extend past the output and scroll horizontally.
+    soft_wrap: bool,
+    /// When set, only the lines containing this query are shown.
+    filter: Option<String>,
+    /// Whether the output is capped at the `max_output_height` setting.
+    capped: bool,
+    /// The length of the longest line, in columns, which sets the width of
+    /// the output when long lines scroll horizontally.
+    widest_line: usize,
 }
 
 const DEFAULT_NUM_LINES: usize = 32;
 const DEFAULT_NUM_COLUMNS: usize = 128;
+/// The number of columns long lines fill before wrapping when soft wrap is off.
+const UNWRAPPED_NUM_COLUMNS: usize = 1024;
 
 /// Returns the default text style for the terminal output.
 pub fn text_style(cx: &mut WindowContext) -> TextStyle {
@@ -89,6 +103,10 @@ pub fn text_style(cx: &mut WindowContext) -> TextStyle {
 
 /// Returns the default terminal size for the terminal output.
 pub fn terminal_size(cx: &mut WindowContext) -> terminal::TerminalSize {
+    terminal_size_with_columns(DEFAULT_NUM_COLUMNS, cx)
+}
+
+fn terminal_size_with_columns(columns: usize, cx: &mut WindowContext) -> terminal::TerminalSize {
     let text_style = text_style(cx);
     let text_system = cx.text_system();
 
@@ -103,7 +121,6 @@ pub fn terminal_size(cx: &mut WindowContext) -> terminal::TerminalSize {
         .width;
 
     let num_lines = DEFAULT_NUM_LINES;
-    let columns = DEFAULT_NUM_COLUMNS;
 
     // Reversed math from terminal::TerminalSize to get pixel width according to terminal width
     let width = columns as f32 * cell_width;
@@ -135,9 +152,59 @@ impl TerminalOutput {
             parser: Processor::new(),
             handler: term,
             full_buffer: None,
+            soft_wrap: true,
+            filter: None,
+            capped: true,
+            widest_line: 0,
+        }
+    }
+
+    /// Sets whether long lines wrap at the default width, or extend past it
+    /// and scroll horizontally.
+    pub fn set_soft_wrap(&mut self, soft_wrap: bool, cx: &mut ViewContext<Self>) {
+        if self.soft_wrap == soft_wrap {
+            return;
+        }
+
+        self.soft_wrap = soft_wrap;
+        let columns = if soft_wrap {
+            DEFAULT_NUM_COLUMNS
+        } else {
+            UNWRAPPED_NUM_COLUMNS
+        };
+        self.handler.resize(terminal_size_with_columns(columns, cx));
+        // Resizing rewraps the lines, so they're measured again.
+        self.widest_line = self.measure_widest_line();
+        cx.notify();
+    }
+
+    /// Sets whether the output is capped at the `max_output_height` setting,
+    /// or grows to fit all of its lines.
+    pub fn set_capped(&mut self, capped: bool, cx: &mut ViewContext<Self>) {
+        if self.capped != capped {
+            self.capped = capped;
+            cx.notify();
         }
     }
 
+    /// Shows only the lines containing `query`, ignoring case, with the
+    /// matches highlighted. An empty query shows the whole output again.
+    pub fn set_filter(&mut self, query: &str, cx: &mut ViewContext<Self>) {
+        let filter = (!query.is_empty()).then(|| query.to_string());
+        if self.filter != filter {
+            self.filter = filter;
+            cx.notify();
+        }
+    }
+
+    /// Returns the number of matches for `query` in the output, ignoring case.
+    pub fn match_count(&self, query: &str) -> usize {
+        self.full_text()
+            .lines()
+            .map(|line| match_ranges(line, query).len())
+            .sum()
+    }
+
     /// Creates a new `TerminalOutput` instance with initial content.
     ///
     /// Initializes a new terminal output and populates it with the provided text.
@@ -185,6 +252,7 @@ impl TerminalOutput {
     pub fn append_text(&mut self, text: &str, cx: &mut WindowContext) {
         for byte in text.as_bytes() {
             if *byte == b'\n' {
+                self.measure_cursor_line();
                 // Dirty (?) hack to move the cursor down
                 self.parser.advance(&mut self.handler, b'\r');
                 self.parser.advance(&mut self.handler, b'\n');
@@ -192,6 +260,7 @@ impl TerminalOutput {
                 self.parser.advance(&mut self.handler, *byte);
             }
         }
+        self.measure_cursor_line();
 
         // This will keep the buffer up to date, though with some terminal codes it won't be perfect
         if let Some(buffer) = self.full_buffer.as_ref() {
@@ -201,6 +270,56 @@ impl TerminalOutput {
         }
     }
 
+    /// Returns the number of rows in the output, from the oldest line kept in
+    /// the scrollback history down to the last line with content.
+    pub(crate) fn row_count(&self) -> usize {
+        let grid = self.handler.grid();
+        let screen_rows = (0..grid.screen_lines())
+            .rev()
+            .find(|&line| !grid[Line(line as i32)].is_clear())
+            .map_or(0, |line| line + 1);
+        grid.history_size() + screen_rows
+    }
+
+    /// Widens the output to fit the line under the cursor. Text is only
+    /// written at the cursor, so lines are measured as the cursor leaves them.
+    fn measure_cursor_line(&mut self) {
+        let grid = self.handler.grid();
+        let length = grid[grid.cursor.point.line].line_length().0;
+        self.widest_line = self.widest_line.max(length);
+    }
+
+    /// Returns the length of the longest line in the output, in columns.
+    fn measure_widest_line(&self) -> usize {
+        let grid = self.handler.grid();
+        let history_size = grid.history_size() as i32;
+        (0..self.row_count())
+            .map(|row| grid[Line(row as i32 - history_size)].line_length().0)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Lays out the given rows of the output, counting from the oldest line
+    /// of the scrollback history. The cells are positioned relative to the
+    /// first of the rows.
+    fn layout_rows(
+        &self,
+        rows: Range<usize>,
+        text_style: &TextStyle,
+        cx: &WindowContext,
+    ) -> (Vec<LayoutCell>, Vec<LayoutRect>) {
+        let grid = self.handler.grid();
+        let history_size = grid.history_size() as i32;
+        let cells = rows.flat_map(|row| {
+            let line = Line(row as i32 - history_size);
+            (0..grid.columns()).map(move |column| terminal::IndexedCell {
+                point: Point::new(line, Column(column)),
+                cell: grid[line][Column(column)].clone(),
+            })
+        });
+        TerminalElement::layout_grid(cells, text_style, cx.text_system(), None, cx)
+    }
+
     fn full_text(&self) -> String {
         let mut full_text = String::new();
 
@@ -243,70 +362,155 @@ impl TerminalOutput {
 impl Render for TerminalOutput {
     /// Renders the terminal output as a GPUI element.
     ///
-    /// Converts the current terminal state into a renderable GPUI element. It handles
-    /// the layout of the terminal grid, calculates the dimensions of the output, and
-    /// creates a canvas element that paints the terminal cells and background rectangles.
+    /// Converts the current terminal state, including its scrollback history, into a
+    /// canvas element that paints the cells and background rectangles of each row.
+    /// Only the rows within the visible bounds are laid out, whether they're scrolled
+    /// into view within the output or within the editor.
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let text_style = text_style(cx);
-        let text_system = cx.text_system();
-
-        let grid = self
-            .handler
-            .renderable_content()
-            .display_iter
-            .map(|ic| terminal::IndexedCell {
-                point: ic.point,
-                cell: ic.cell.clone(),
-            });
-        let (cells, rects) = TerminalElement::layout_grid(grid, &text_style, text_system, None, cx);
 
-        // lines are 0-indexed, so we must add 1 to get the number of lines
-        let text_line_height = text_style.line_height_in_pixels(cx.rem_size());
-        let num_lines = cells.iter().map(|c| c.point.line).max().unwrap_or(0) + 1;
-        let height = num_lines as f32 * text_line_height;
+        if let Some(query) = self.filter.as_ref() {
+            let highlight = HighlightStyle {
+                background_color: Some(cx.theme().colors().search_match_background),
+                ..Default::default()
+            };
+
+            return v_flex()
+                .id("terminal-output")
+                .w_full()
+                .children(self.full_text().lines().filter_map(|line| {
+                    let ranges = match_ranges(line, query);
+                    if ranges.is_empty() {
+                        return None;
+                    }
+
+                    Some(
+                        div().child(StyledText::new(line.to_string()).with_highlights(
+                            &text_style,
+                            ranges.into_iter().map(|range| (range, highlight)),
+                        )),
+                    )
+                }))
+                .into_any_element();
+        }
 
+        let text_system = cx.text_system();
         let font_pixels = text_style.font_size.to_pixels(cx.rem_size());
         let font_id = text_system.resolve_font(&text_style.font());
-
         let cell_width = text_system
             .advance(font_id, font_pixels, 'w')
             .map(|advance| advance.width)
             .unwrap_or(Pixels(0.0));
 
-        canvas(
+        let text_line_height = text_style.line_height_in_pixels(cx.rem_size());
+        let max_height = ReplSettings::get_global(cx)
+            .max_output_height
+            .filter(|_| self.capped)
+            .map(|lines| text_line_height * lines as f32);
+        let soft_wrap = self.soft_wrap;
+        let row_count = self.row_count();
+        let height = text_line_height * row_count as f32;
+        let view = cx.view().clone();
+
+        let canvas = canvas(
             // prepaint
-            move |_bounds, _| {},
+            move |bounds, cx| {
+                let rows = visible_rows(
+                    bounds,
+                    cx.content_mask().bounds,
+                    text_line_height,
+                    row_count,
+                );
+                let origin = bounds.origin + point(px(0.), text_line_height * rows.start as f32);
+                let (cells, rects) = view.read(cx).layout_rows(rows, &text_style, cx);
+                (origin, cells, rects)
+            },
             // paint
-            move |bounds, _, cx| {
+            move |bounds, (origin, cells, rects), cx| {
+                let dimensions = terminal::TerminalSize {
+                    cell_width,
+                    line_height: text_line_height,
+                    size: bounds.size,
+                };
                 for rect in rects {
-                    rect.paint(
-                        bounds.origin,
-                        &terminal::TerminalSize {
-                            cell_width,
-                            line_height: text_line_height,
-                            size: bounds.size,
-                        },
-                        cx,
-                    );
+                    rect.paint(origin, &dimensions, cx);
                 }
-
                 for cell in cells {
-                    cell.paint(
-                        bounds.origin,
-                        &terminal::TerminalSize {
-                            cell_width,
-                            line_height: text_line_height,
-                            size: bounds.size,
-                        },
-                        bounds,
-                        cx,
-                    );
+                    cell.paint(origin, &dimensions, bounds, cx);
                 }
             },
         )
         // We must set the height explicitly for the editor block to size itself correctly
         .h(height)
+        .map(|canvas| {
+            if soft_wrap {
+                canvas.w_full()
+            } else {
+                canvas.w(self.widest_line.max(1) as f32 * cell_width)
+            }
+        });
+
+        div()
+            .id("terminal-output")
+            .w_full()
+            .when_some(max_height, |div, max_height| {
+                div.max_h(max_height).overflow_y_scroll()
+            })
+            .when(!soft_wrap, |div| div.overflow_x_scroll())
+            .child(canvas)
+            .into_any_element()
+    }
+}
+
+/// Returns the rows of an output laid out in `bounds` that fall within
+/// `visible_bounds`, given the height of each row.
+fn visible_rows(
+    bounds: Bounds<Pixels>,
+    visible_bounds: Bounds<Pixels>,
+    line_height: Pixels,
+    row_count: usize,
+) -> Range<usize> {
+    if line_height <= px(0.) {
+        return 0..0;
+    }
+    let top = (visible_bounds.top() - bounds.top()) / line_height;
+    let bottom = (visible_bounds.bottom() - bounds.top()) / line_height;
+    let end = (bottom.ceil().max(0.) as usize).min(row_count);
+    let start = (top.floor().max(0.) as usize).min(end);
+    start..end
+}
+
+/// Returns the byte ranges in `line` that match `query`, ignoring case.
+fn match_ranges(line: &str, query: &str) -> Vec<Range<usize>> {
+    let query = query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut search_start = 0;
+    for (start, _) in line.char_indices() {
+        if start < search_start {
+            continue;
+        }
+
+        let mut candidate = Vec::with_capacity(query.len());
+        for (offset, ch) in line[start..].char_indices() {
+            candidate.extend(ch.to_lowercase());
+            if candidate.len() >= query.len() {
+                if candidate == query {
+                    let end = start + offset + ch.len_utf8();
+                    ranges.push(start..end);
+                    search_start = end;
+                }
+                break;
+            }
+        }
     }
+    ranges
 }
 
 impl OutputContent for TerminalOutput {
@@ -338,3 +542,110 @@ impl OutputContent for TerminalOutput {
         Some(buffer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AvailableSpace, TestAppContext};
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_match_ranges() {
+        assert_eq!(
+            match_ranges("Epoch 1: loss=0.5, LOSS_avg=0.6", "loss"),
+            vec![9..13, 19..23]
+        );
+        assert_eq!(match_ranges("aaaa", "aa"), vec![0..2, 2..4]);
+        assert_eq!(match_ranges("Ünïcode ÜNÏ", "ünï"), vec![0..5, 10..15]);
+        assert_eq!(match_ranges("accuracy", "loss"), Vec::<Range<usize>>::new());
+        assert_eq!(match_ranges("accuracy", ""), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        let line_height = px(10.);
+        let bounds = |top: f32, rows: usize| {
+            Bounds::new(
+                point(px(0.), px(top)),
+                size(px(800.), line_height * rows as f32),
+            )
+        };
+        let viewport = bounds(0., 20);
+
+        // Only the rows within the viewport are laid out, however tall the output.
+        assert_eq!(
+            visible_rows(bounds(0., 50_000), viewport, line_height, 50_000),
+            0..20
+        );
+        assert_eq!(
+            visible_rows(bounds(-400_005., 50_000), viewport, line_height, 50_000),
+            40_000..40_021
+        );
+        assert_eq!(
+            visible_rows(bounds(-25., 50_000), viewport, line_height, 50_000),
+            2..23
+        );
+        assert_eq!(
+            visible_rows(bounds(150., 10), viewport, line_height, 10),
+            0..5
+        );
+        // Outputs scrolled out of view lay out nothing.
+        assert_eq!(
+            visible_rows(bounds(-500., 10), viewport, line_height, 10),
+            10..10
+        );
+        assert_eq!(
+            visible_rows(bounds(500., 10), viewport, line_height, 10),
+            0..0
+        );
+    }
+
+    #[gpui::test]
+    fn test_widest_line(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            ReplSettings::register(cx);
+        });
+
+        let (output, cx) = cx.add_window_view(|cx| TerminalOutput::from("ab\nabcdef\n", cx));
+        output.update(cx, |output, cx| {
+            assert_eq!(output.widest_line, 6);
+            output.append_text("abc", cx);
+            assert_eq!(output.widest_line, 6);
+            // Lines written across appends are measured as a whole.
+            output.append_text("defghij\nab", cx);
+            assert_eq!(output.widest_line, 10);
+            assert_eq!(output.widest_line, output.measure_widest_line());
+        });
+    }
+
+    #[gpui::test]
+    fn test_draw_long_output(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            ReplSettings::register(cx);
+        });
+
+        let text = (0..50_000)
+            .map(|ix| format!("line {ix}\n"))
+            .collect::<String>();
+        let (output, cx) = cx.add_window_view(|cx| TerminalOutput::from(&text, cx));
+        output.update(cx, |output, _| assert!(output.row_count() > 1_000));
+
+        for capped in [true, false] {
+            output.update(cx, |output, cx| output.set_capped(capped, cx));
+            cx.draw(
+                point(px(0.), px(0.)),
+                size(
+                    AvailableSpace::Definite(px(800.)),
+                    AvailableSpace::MinContent,
+                ),
+                |_| output.clone(),
+            );
+        }
+    }
+}
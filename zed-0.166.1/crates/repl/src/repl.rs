@@ -1,12 +1,18 @@
+pub mod cell_outline_panel;
 pub mod components;
 mod jupyter_settings;
 pub mod kernels;
 pub mod notebook;
+pub mod output_panel;
 mod outputs;
 mod repl_editor;
 mod repl_sessions_ui;
+mod repl_settings;
 mod repl_store;
+mod scratchpad;
 mod session;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test;
 
 use std::{sync::Arc, time::Duration};
 
@@ -16,14 +22,23 @@ use project::Fs;
 pub use runtimelib::ExecutionState;
 use settings::Settings as _;
 
+pub use crate::cell_outline_panel::CellOutlinePanel;
 pub use crate::jupyter_settings::JupyterSettings;
 pub use crate::kernels::{Kernel, KernelSpecification, KernelStatus};
+pub use crate::output_panel::ReplOutputPanel;
+pub use crate::outputs::ExecutionStatus;
 pub use crate::repl_editor::*;
 pub use crate::repl_sessions_ui::{
-    ClearOutputs, Interrupt, ReplSessionsPage, Restart, Run, Sessions, Shutdown,
+    ClearOutputs, CollapseAllOutputs, CopyCellAsMarkdown, CopyKernelConnectionInfo,
+    ExpandAllOutputs, InsertCell, Interrupt, MergeCellAbove, MergeCellBelow, NextOutput,
+    OpenOutputInNewTab, OpenScratchpad, PreviousOutput, ReplSessionsPage, ResetWorkingDirectory,
+    Restart, RestartAndClearOutputs, RestartAndRunAll, Run, RunAndAdvance, RunCell, Sessions,
+    SetWorkingDirectory, Shutdown, ShutdownAll,
 };
-use crate::repl_store::ReplStore;
-pub use crate::session::Session;
+use crate::repl_settings::{CellOutlinePanelSettings, ReplOutputPanelSettings};
+pub use crate::repl_settings::{CellSeparator, OutputPlacement, ReplSettings};
+pub use crate::repl_store::ReplStore;
+pub use crate::session::{CellInfo, Session, SessionEvent, DEFAULT_OUTPUT_BLOCK_PRIORITY};
 use client::telemetry::Telemetry;
 
 pub const KERNEL_DOCS_URL: &str = "https://zed.dev/docs/repl#changing-kernels";
@@ -31,6 +46,9 @@ pub const KERNEL_DOCS_URL: &str = "https://zed.dev/docs/repl#changing-kernels";
 pub fn init(fs: Arc<dyn Fs>, telemetry: Arc<Telemetry>, cx: &mut AppContext) {
     set_dispatcher(zed_dispatcher(cx));
     JupyterSettings::register(cx);
+    ReplSettings::register(cx);
+    CellOutlinePanelSettings::register(cx);
+    ReplOutputPanelSettings::register(cx);
     ::editor::init_settings(cx);
     repl_sessions_ui::init(cx);
     ReplStore::init(fs, telemetry, cx);
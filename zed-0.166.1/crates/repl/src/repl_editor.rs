@@ -4,15 +4,23 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
-use editor::Editor;
-use gpui::{prelude::*, Entity, View, WeakView, WindowContext};
-use language::{BufferSnapshot, Language, LanguageName, Point};
+use editor::{scroll::Autoscroll, Anchor, Editor, ToPoint as _};
+use gpui::{
+    prelude::*, AppContext, ClipboardItem, Entity, PathPromptOptions, View, ViewContext, WeakView,
+    WindowContext,
+};
+use language::{BufferEvent, BufferSnapshot, Language, LanguageName, Point};
 use project::{ProjectItem as _, WorktreeId};
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
 
 use crate::repl_store::ReplStore;
 use crate::session::SessionEvent;
 use crate::{
-    ClearOutputs, Interrupt, JupyterSettings, KernelSpecification, Restart, Session, Shutdown,
+    CellSeparator, ClearOutputs, CollapseAllOutputs, CopyCellAsMarkdown, CopyKernelConnectionInfo,
+    ExpandAllOutputs, Interrupt, JupyterSettings, KernelSpecification, NextOutput,
+    OpenOutputInNewTab, PreviousOutput, ReplSettings, ResetWorkingDirectory, Restart,
+    RestartAndClearOutputs, RestartAndRunAll, Session, SetWorkingDirectory, Shutdown,
 };
 
 pub fn assign_kernelspec(
@@ -32,9 +40,6 @@ pub fn assign_kernelspec(
         store.set_active_kernelspec(worktree_id, kernel_specification.clone(), cx);
     });
 
-    let fs = store.read(cx).fs().clone();
-    let telemetry = store.read(cx).telemetry().clone();
-
     if let Some(session) = store.read(cx).get_session(weak_editor.entity_id()).cloned() {
         // Drop previous session, start new one
         session.update(cx, |session, cx| {
@@ -44,6 +49,22 @@ pub fn assign_kernelspec(
         });
     }
 
+    start_session(kernel_specification, weak_editor, cx);
+
+    Ok(())
+}
+
+/// Starts a session for the editor with the given kernel, and registers it
+/// with the [`ReplStore`].
+pub(crate) fn start_session(
+    kernel_specification: KernelSpecification,
+    weak_editor: WeakView<Editor>,
+    cx: &mut WindowContext,
+) -> View<Session> {
+    let store = ReplStore::global(cx);
+    let fs = store.read(cx).fs().clone();
+    let telemetry = store.read(cx).telemetry().clone();
+
     let session = cx
         .new_view(|cx| Session::new(weak_editor.clone(), fs, telemetry, kernel_specification, cx));
 
@@ -55,87 +76,134 @@ pub fn assign_kernelspec(
                 let store = store.clone();
                 move |_this, _session, event, cx| match event {
                     SessionEvent::Shutdown(shutdown_event) => {
-                        store.update(cx, |store, _cx| {
-                            store.remove_session(shutdown_event.entity_id());
+                        store.update(cx, |store, cx| {
+                            store.remove_session(shutdown_event.entity_id(), cx);
                         });
                     }
+                    SessionEvent::ExecutionFinished { .. } => {}
                 }
             })
             .detach();
         })
         .ok();
 
-    store.update(cx, |store, _cx| {
-        store.insert_session(weak_editor.entity_id(), session.clone());
+    store.update(cx, |store, cx| {
+        store.insert_session(weak_editor.entity_id(), session.clone(), cx);
     });
 
-    Ok(())
+    session
 }
 
 pub fn run(editor: WeakView<Editor>, move_down: bool, cx: &mut WindowContext) -> Result<()> {
+    let editor = editor.upgrade().context("editor was dropped")?;
+    let selected_range = editor
+        .update(cx, |editor, cx| editor.selections.newest_adjusted(cx))
+        .range();
+    run_range(editor, selected_range, move_down, cx)
+}
+
+/// Runs the cell containing `position`, leaving the cursor where it is.
+pub fn run_cell(editor: WeakView<Editor>, position: Anchor, cx: &mut WindowContext) -> Result<()> {
+    let editor = editor.upgrade().context("editor was dropped")?;
+    let point = position.to_point(&editor.read(cx).buffer().read(cx).snapshot(cx));
+    run_range(editor, point..point, false, cx)
+}
+
+/// Runs the cell at the cursor and moves the cursor to the next cell, like
+/// Shift+Enter in a notebook. When there's no next cell in a buffer split into
+/// cells, a new one is inserted below the cell that ran, with the cursor inside
+/// it.
+pub fn run_and_advance(editor: WeakView<Editor>, cx: &mut WindowContext) -> Result<()> {
+    let editor = editor.upgrade().context("editor was dropped")?;
+    let cursor = editor
+        .update(cx, |editor, cx| editor.selections.newest::<Point>(cx))
+        .head();
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return Ok(());
+    };
+
+    let buffer = buffer.read(cx).snapshot();
+    let separator = cell_separator(&buffer, cx);
+    let (ranges, next_cell_point) = runnable_ranges(&buffer, cursor..cursor, &separator);
+    // A new cell is only added after the last cell of a buffer that's split
+    // into cells. Otherwise the cursor just moves down past the code.
+    let new_cell_edit = match (ranges.last(), next_cell_point) {
+        (Some(range), None) if has_cells(&buffer, &separator) => {
+            new_cell_edit(&buffer, range.end.row, &separator)
+        }
+        _ => None,
+    };
+    let Some((position, text, new_cursor)) = new_cell_edit else {
+        return run_range(editor, cursor..cursor, true, cx);
+    };
+
+    // Insert the new cell before running, so that it isn't mistaken for part
+    // of the code that ran.
+    let new_cursor = editor.update(cx, |editor, cx| {
+        editor.edit([(position..position, text)], cx);
+        editor
+            .buffer()
+            .read(cx)
+            .snapshot(cx)
+            .anchor_before(new_cursor)
+    });
+    run_range(editor.clone(), cursor..cursor, false, cx)?;
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+            selections.select_anchor_ranges([new_cursor..new_cursor]);
+        });
+    });
+    Ok(())
+}
+
+fn run_range(
+    editor: View<Editor>,
+    selected_range: Range<Point>,
+    move_down: bool,
+    cx: &mut WindowContext,
+) -> Result<()> {
     let store = ReplStore::global(cx);
     if !store.read(cx).is_enabled() {
         return Ok(());
     }
 
-    let editor = editor.upgrade().context("editor was dropped")?;
-    let selected_range = editor
-        .update(cx, |editor, cx| editor.selections.newest_adjusted(cx))
-        .range();
     let multibuffer = editor.read(cx).buffer().clone();
     let Some(buffer) = multibuffer.read(cx).as_singleton() else {
         return Ok(());
     };
 
-    let Some(project_path) = buffer.read(cx).project_path(cx) else {
+    // Buffers outside of a worktree, like scratchpads, can only run in a
+    // session that was started for them.
+    let worktree_id = buffer
+        .read(cx)
+        .project_path(cx)
+        .map(|path| path.worktree_id);
+    if worktree_id.is_none() && store.read(cx).get_session(editor.entity_id()).is_none() {
         return Ok(());
-    };
+    }
 
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let separator = cell_separator(&buffer_snapshot, cx);
     let (runnable_ranges, next_cell_point) =
-        runnable_ranges(&buffer.read(cx).snapshot(), selected_range);
+        runnable_ranges(&buffer_snapshot, selected_range, &separator);
 
     for runnable_range in runnable_ranges {
         let Some(language) = multibuffer.read(cx).language_at(runnable_range.start, cx) else {
             continue;
         };
 
-        let kernel_specification = store
-            .read(cx)
-            .active_kernelspec(project_path.worktree_id, Some(language.clone()), cx)
-            .ok_or_else(|| anyhow::anyhow!("No kernel found for language: {}", language.name()))?;
-
-        let fs = store.read(cx).fs().clone();
-        let telemetry = store.read(cx).telemetry().clone();
-
         let session = if let Some(session) = store.read(cx).get_session(editor.entity_id()).cloned()
         {
             session
         } else {
-            let weak_editor = editor.downgrade();
-            let session = cx
-                .new_view(|cx| Session::new(weak_editor, fs, telemetry, kernel_specification, cx));
-
-            editor.update(cx, |_editor, cx| {
-                cx.notify();
-
-                cx.subscribe(&session, {
-                    let store = store.clone();
-                    move |_this, _session, event, cx| match event {
-                        SessionEvent::Shutdown(shutdown_event) => {
-                            store.update(cx, |store, _cx| {
-                                store.remove_session(shutdown_event.entity_id());
-                            });
-                        }
-                    }
-                })
-                .detach();
-            });
-
-            store.update(cx, |store, _cx| {
-                store.insert_session(editor.entity_id(), session.clone());
-            });
-
-            session
+            let worktree_id = worktree_id.context("editor is not in a worktree")?;
+            let kernel_specification = store
+                .read(cx)
+                .active_kernelspec(worktree_id, Some(language.clone()), cx)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("No kernel found for language: {}", language.name())
+                })?;
+            start_session(kernel_specification, editor.downgrade(), cx)
         };
 
         let selected_text;
@@ -151,8 +219,19 @@ pub fn run(editor: WeakView<Editor>, move_down: bool, cx: &mut WindowContext) ->
             next_cursor = next_cell_point.map(|point| snapshot.anchor_after(point));
         }
 
+        let markdown = match &separator {
+            CellSeparator::Marker(marker) if ReplSettings::get_global(cx).render_markdown_cells => {
+                markdown_cell_text(&buffer_snapshot, runnable_range.clone(), marker)
+            }
+            _ => None,
+        };
+
         session.update(cx, |session, cx| {
-            session.execute(selected_text, anchor_range, next_cursor, move_down, cx);
+            if let Some(markdown) = markdown {
+                session.render_markdown(markdown, anchor_range, next_cursor, move_down, cx);
+            } else {
+                session.execute(selected_text, anchor_range, next_cursor, move_down, cx);
+            }
         });
     }
 
@@ -229,6 +308,22 @@ pub fn clear_outputs(editor: WeakView<Editor>, cx: &mut WindowContext) {
     });
 }
 
+/// Collapses or expands the outputs of every execution in the editor's session.
+pub fn set_all_outputs_collapsed(
+    editor: WeakView<Editor>,
+    collapsed: bool,
+    cx: &mut WindowContext,
+) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    session.update(cx, |session, cx| {
+        session.set_all_outputs_collapsed(collapsed, cx);
+    });
+}
+
 pub fn interrupt(editor: WeakView<Editor>, cx: &mut WindowContext) {
     let store = ReplStore::global(cx);
     let entity_id = editor.entity_id();
@@ -250,12 +345,13 @@ pub fn shutdown(editor: WeakView<Editor>, cx: &mut WindowContext) {
     };
 
     session.update(cx, |session, cx| {
-        session.shutdown(cx);
+        session.confirm_shutdown(cx);
         cx.notify();
     });
 }
 
-pub fn restart(editor: WeakView<Editor>, cx: &mut WindowContext) {
+/// Restarts the editor's kernel, clearing its outputs if `clear_outputs` is set.
+pub fn restart(editor: WeakView<Editor>, clear_outputs: bool, cx: &mut WindowContext) {
     let Some(editor) = editor.upgrade() else {
         return;
     };
@@ -271,21 +367,354 @@ pub fn restart(editor: WeakView<Editor>, cx: &mut WindowContext) {
     };
 
     session.update(cx, |session, cx| {
-        session.restart(cx);
+        session.restart(clear_outputs, cx);
         cx.notify();
     });
 }
 
-pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakView<Editor>) {
+/// Prompts for a folder to run the editor's kernel in, and restarts the kernel
+/// there.
+pub fn set_working_directory(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+    });
+
+    cx.spawn(|mut cx| async move {
+        let Some(working_directory) = paths.await??.and_then(|paths| paths.into_iter().next())
+        else {
+            return Ok(());
+        };
+
+        session.update(&mut cx, |session, cx| {
+            session.set_working_directory(Some(working_directory), cx);
+            session.restart(false, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Clears the folder set with [`set_working_directory`], and restarts the
+/// editor's kernel in the directory of its file.
+pub fn reset_working_directory(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    session.update(cx, |session, cx| {
+        if session.working_directory_override().is_some() {
+            session.set_working_directory(None, cx);
+            session.restart(false, cx);
+        }
+    });
+}
+
+pub fn restart_and_run_all(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    session.update(cx, |session, cx| {
+        session.restart_and_run_all(cx);
+        cx.notify();
+    });
+}
+
+pub fn next_output(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    session.update(cx, |session, cx| {
+        session.select_next_output(cx);
+    });
+}
+
+pub fn previous_output(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+
+    session.update(cx, |session, cx| {
+        session.select_previous_output(cx);
+    });
+}
+
+pub fn open_output_in_new_tab(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Ok(cursor) = editor.update(cx, |editor, _| editor.selections.newest_anchor().head()) else {
+        return;
+    };
+
+    session.update(cx, |session, cx| {
+        session.open_output_in_new_tab(cursor, cx);
+    });
+}
+
+/// Starts a new cell on the line below the cursor, by inserting the cell
+/// separator of the buffer's language, and moves the cursor into it.
+pub fn insert_cell(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    editor
+        .update(cx, |editor, cx| {
+            let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let buffer = buffer.read(cx).snapshot();
+            let row = editor.selections.newest::<Point>(cx).head().row;
+            let separator = cell_separator(&buffer, cx);
+            let Some((position, text, cursor)) = new_cell_edit(&buffer, row, &separator) else {
+                return;
+            };
+
+            editor.transact(cx, |editor, cx| {
+                editor.edit([(position..position, text)], cx);
+                editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                    selections.select_ranges([cursor..cursor]);
+                });
+            });
+        })
+        .ok();
+}
+
+/// Merges the cell at the cursor with the one above or below it, by removing
+/// the separator between them. The outputs of both cells are cleared, as they
+/// no longer match the merged code.
+pub fn merge_cell(editor: WeakView<Editor>, above: bool, cx: &mut WindowContext) {
+    let Some(editor) = editor.upgrade() else {
+        return;
+    };
+    let merged_range = editor.update(cx, |editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        let buffer = buffer.read(cx).snapshot();
+        let row = editor.selections.newest::<Point>(cx).head().row;
+        let separator = cell_separator(&buffer, cx);
+        let (boundary, merged_range) = merge_cells_edit(&buffer, row, &separator, above)?;
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let merged_range =
+            snapshot.anchor_before(merged_range.start)..snapshot.anchor_after(merged_range.end);
+        editor.edit([(boundary, "")], cx);
+        Some(merged_range)
+    });
+    let Some(merged_range) = merged_range else {
+        return;
+    };
+
+    let store = ReplStore::global(cx);
+    if let Some(session) = store.read(cx).get_session(editor.entity_id()).cloned() {
+        session.update(cx, |session, cx| {
+            session.clear_outputs_in_range(merged_range, cx);
+        });
+    }
+}
+
+/// Copies the code of the cell at the cursor, followed by its outputs, to the
+/// clipboard as markdown.
+pub fn copy_cell_as_markdown(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Ok(cursor) = editor.update(cx, |editor, _| editor.selections.newest_anchor().head()) else {
+        return;
+    };
+
+    if let Some(markdown) = session.read(cx).cell_markdown(cursor, cx) {
+        cx.write_to_clipboard(ClipboardItem::new_string(markdown));
+    }
+}
+
+/// Copies the connection info of the editor's kernel to the clipboard as JSON,
+/// for connecting other Jupyter clients, like `jupyter console --existing`, to
+/// it. Does nothing unless Zed launched the kernel.
+pub fn copy_kernel_connection_info(editor: WeakView<Editor>, cx: &mut WindowContext) {
+    let store = ReplStore::global(cx);
+    let entity_id = editor.entity_id();
+    let Some(session) = store.read(cx).get_session(entity_id).cloned() else {
+        return;
+    };
+    let Some(connection_info) = session.read(cx).kernel_connection_info() else {
+        return;
+    };
+
+    if let Some(json) = serde_json::to_string_pretty(connection_info).log_err() {
+        cx.write_to_clipboard(ClipboardItem::new_string(json));
+    }
+}
+
+pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakView<Editor>) {
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ClearOutputs, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::clear_outputs(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &CollapseAllOutputs, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::set_all_outputs_collapsed(editor_handle.clone(), true, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ExpandAllOutputs, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::set_all_outputs_collapsed(editor_handle.clone(), false, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &Interrupt, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::interrupt(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &Shutdown, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::shutdown(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &Restart, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::restart(editor_handle.clone(), false, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &RestartAndClearOutputs, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::restart(editor_handle.clone(), true, cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &RestartAndRunAll, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::restart_and_run_all(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &SetWorkingDirectory, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::set_working_directory(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &ResetWorkingDirectory, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::reset_working_directory(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
+    editor
+        .register_action({
+            let editor_handle = editor_handle.clone();
+            move |_: &NextOutput, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::next_output(editor_handle.clone(), cx);
+            }
+        })
+        .detach();
+
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
-            move |_: &ClearOutputs, cx| {
+            move |_: &PreviousOutput, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
                 }
 
-                crate::clear_outputs(editor_handle.clone(), cx);
+                crate::previous_output(editor_handle.clone(), cx);
             }
         })
         .detach();
@@ -293,12 +722,12 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakView
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
-            move |_: &Interrupt, cx| {
+            move |_: &OpenOutputInNewTab, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
                 }
 
-                crate::interrupt(editor_handle.clone(), cx);
+                crate::open_output_in_new_tab(editor_handle.clone(), cx);
             }
         })
         .detach();
@@ -306,12 +735,12 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakView
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
-            move |_: &Shutdown, cx| {
+            move |_: &CopyCellAsMarkdown, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
                 }
 
-                crate::shutdown(editor_handle.clone(), cx);
+                crate::copy_cell_as_markdown(editor_handle.clone(), cx);
             }
         })
         .detach();
@@ -319,17 +748,66 @@ pub fn setup_editor_session_actions(editor: &mut Editor, editor_handle: WeakView
     editor
         .register_action({
             let editor_handle = editor_handle.clone();
-            move |_: &Restart, cx| {
+            move |_: &CopyKernelConnectionInfo, cx| {
                 if !JupyterSettings::enabled(cx) {
                     return;
                 }
 
-                crate::restart(editor_handle.clone(), cx);
+                crate::copy_kernel_connection_info(editor_handle.clone(), cx);
             }
         })
         .detach();
 }
 
+/// Shows a run button in the gutter at the start of every Jupytext cell, and
+/// keeps the buttons up to date as the buffer and the settings change.
+pub(crate) fn setup_cell_run_buttons(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+
+    refresh_cell_run_buttons(editor, cx);
+    cx.subscribe(&buffer, |editor, _, event, cx| {
+        if matches!(event, BufferEvent::Edited | BufferEvent::LanguageChanged) {
+            refresh_cell_run_buttons(editor, cx);
+        }
+    })
+    .detach();
+    cx.observe_global::<SettingsStore>(refresh_cell_run_buttons)
+        .detach();
+}
+
+fn refresh_cell_run_buttons(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx).snapshot();
+    let rows = match cell_separator(&buffer, cx) {
+        CellSeparator::Marker(marker) if JupyterSettings::enabled(cx) => {
+            jupytext_cell_starts(&buffer, &marker)
+        }
+        _ => Vec::new(),
+    };
+    if rows.is_empty() {
+        editor.clear_gutter_run_buttons(cx);
+        return;
+    }
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let positions = rows
+        .into_iter()
+        .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+        .collect();
+    let editor_handle = cx.view().downgrade();
+    editor.set_gutter_run_buttons(
+        positions,
+        move |position, cx| {
+            run_cell(editor_handle.clone(), position, cx).log_err();
+        },
+        cx,
+    );
+}
+
 fn cell_range(buffer: &BufferSnapshot, start_row: u32, end_row: u32) -> Range<Point> {
     let mut snippet_end_row = end_row;
     while buffer.is_line_blank(snippet_end_row) && snippet_end_row > start_row {
@@ -338,28 +816,162 @@ fn cell_range(buffer: &BufferSnapshot, start_row: u32, end_row: u32) -> Range<Po
     Point::new(start_row, 0)..Point::new(snippet_end_row, buffer.line_len(snippet_end_row))
 }
 
+/// Returns how cells are separated in the buffer, based on its language.
+pub(crate) fn cell_separator(buffer: &BufferSnapshot, cx: &AppContext) -> CellSeparator {
+    match buffer.language() {
+        Some(language) => ReplSettings::get_global(cx).cell_separator(language.name().0.as_ref()),
+        None => CellSeparator::default(),
+    }
+}
+
+/// Returns the line prefixes that start a Jupytext cell, e.g. `# %%` for the
+/// marker `%%` in Python.
+fn jupytext_prefixes(buffer: &BufferSnapshot, marker: &str) -> Vec<String> {
+    let Some(language) = buffer.language() else {
+        return Vec::new();
+    };
+
+    language
+        .default_scope()
+        .line_comment_prefixes()
+        .iter()
+        .map(|comment_prefix| format!("{comment_prefix}{marker}"))
+        .collect()
+}
+
+/// Returns where and what to insert to start a new cell on the line below
+/// `row`, along with where the cursor goes in the new cell. Returns `None` when
+/// the language has no line comment to write a cell marker with.
+pub(crate) fn new_cell_edit(
+    buffer: &BufferSnapshot,
+    row: u32,
+    separator: &CellSeparator,
+) -> Option<(Point, String, Point)> {
+    let separator_line = match separator {
+        CellSeparator::Marker(marker) => jupytext_prefixes(buffer, marker).into_iter().next()?,
+        CellSeparator::BlankLine => String::new(),
+    };
+    let line_end = Point::new(row, buffer.line_len(row));
+    Some((
+        line_end,
+        format!("\n{separator_line}\n"),
+        Point::new(row + 2, 0),
+    ))
+}
+
+/// Whether the buffer is split into cells, rather than run a selection or a
+/// line at a time.
+fn has_cells(buffer: &BufferSnapshot, separator: &CellSeparator) -> bool {
+    match separator {
+        CellSeparator::Marker(marker) => !jupytext_cell_starts(buffer, marker).is_empty(),
+        CellSeparator::BlankLine => true,
+    }
+}
+
+/// Returns the rows of every cell in the buffer, in order. Marker cells extend
+/// up to the next marker, while cells separated by blank lines end before
+/// them. Code before the first marker makes up a cell of its own.
+fn cell_rows(buffer: &BufferSnapshot, separator: &CellSeparator) -> Vec<Range<u32>> {
+    let end_row = buffer.max_point().row + 1;
+    match separator {
+        CellSeparator::Marker(marker) => {
+            let mut starts = jupytext_cell_starts(buffer, marker);
+            let Some(&first_start) = starts.first() else {
+                return Vec::new();
+            };
+            if (0..first_start).any(|row| !buffer.is_line_blank(row)) {
+                starts.insert(0, 0);
+            }
+
+            let ends = starts.iter().skip(1).copied().chain([end_row]);
+            starts
+                .iter()
+                .zip(ends)
+                .map(|(start, end)| *start..end)
+                .collect()
+        }
+        CellSeparator::BlankLine => {
+            let mut cells = Vec::new();
+            let mut cell_start = None;
+            for row in 0..end_row {
+                match (buffer.is_line_blank(row), cell_start) {
+                    (false, None) => cell_start = Some(row),
+                    (true, Some(start)) => {
+                        cells.push(start..row);
+                        cell_start = None;
+                    }
+                    _ => {}
+                }
+            }
+            cells.extend(cell_start.map(|start| start..end_row));
+            cells
+        }
+    }
+}
+
+/// Returns the separator to delete to merge the cell containing `row` with the
+/// one above or below it, along with the rows of both cells, or `None` when
+/// there's no cell there to merge with.
+pub(crate) fn merge_cells_edit(
+    buffer: &BufferSnapshot,
+    row: u32,
+    separator: &CellSeparator,
+    above: bool,
+) -> Option<(Range<Point>, Range<Point>)> {
+    let cells = cell_rows(buffer, separator);
+    let ix = cells.iter().rposition(|cell| cell.start <= row)?;
+    let (first, second) = if above {
+        (cells.get(ix.checked_sub(1)?)?, &cells[ix])
+    } else {
+        (&cells[ix], cells.get(ix + 1)?)
+    };
+
+    let boundary = match separator {
+        // Remove the marker line of the second cell.
+        CellSeparator::Marker(_) if second.start < buffer.max_point().row => {
+            Point::new(second.start, 0)..Point::new(second.start + 1, 0)
+        }
+        CellSeparator::Marker(_) => {
+            let previous_row = second.start - 1;
+            Point::new(previous_row, buffer.line_len(previous_row))
+                ..Point::new(second.start, buffer.line_len(second.start))
+        }
+        CellSeparator::BlankLine => Point::new(first.end, 0)..Point::new(second.start, 0),
+    };
+    let last_row = second.end - 1;
+    let merged_range = Point::new(first.start, 0)..Point::new(last_row, buffer.line_len(last_row));
+    Some((boundary, merged_range))
+}
+
+/// Returns the rows at which Jupytext cells start.
+fn jupytext_cell_starts(buffer: &BufferSnapshot, marker: &str) -> Vec<u32> {
+    let jupytext_prefixes = jupytext_prefixes(buffer, marker);
+    if jupytext_prefixes.is_empty() {
+        return Vec::new();
+    }
+
+    (0..=buffer.max_point().row)
+        .filter(|row| {
+            jupytext_prefixes
+                .iter()
+                .any(|prefix| buffer.contains_str_at(Point::new(*row, 0), prefix))
+        })
+        .collect()
+}
+
 // Returns the ranges of the snippets in the buffer and the next point for moving the cursor to
 fn jupytext_cells(
     buffer: &BufferSnapshot,
     range: Range<Point>,
+    marker: &str,
 ) -> (Vec<Range<Point>>, Option<Point>) {
     let mut current_row = range.start.row;
 
-    let Some(language) = buffer.language() else {
-        return (Vec::new(), None);
-    };
-
-    let default_scope = language.default_scope();
-    let comment_prefixes = default_scope.line_comment_prefixes();
-    if comment_prefixes.is_empty() {
+    let jupytext_prefixes = jupytext_prefixes(buffer, marker);
+    if jupytext_prefixes.is_empty() {
         return (Vec::new(), None);
     }
 
-    let jupytext_prefixes = comment_prefixes
-        .iter()
-        .map(|comment_prefix| format!("{comment_prefix}%%"))
-        .collect::<Vec<_>>();
-
     let mut snippet_start_row = None;
     loop {
         if jupytext_prefixes
@@ -404,9 +1016,83 @@ fn jupytext_cells(
     (snippets, None)
 }
 
-fn runnable_ranges(
+// Returns the runs of non-blank lines intersecting the range (cells separated by blank lines)
+// and the next point for moving the cursor to
+fn blank_line_cells(
+    buffer: &BufferSnapshot,
+    range: Range<Point>,
+) -> (Vec<Range<Point>>, Option<Point>) {
+    let max_row = buffer.max_point().row;
+
+    // Start from the beginning of the cell containing the start of the range
+    let mut row = range.start.row;
+    while row > 0 && !buffer.is_line_blank(row) && !buffer.is_line_blank(row - 1) {
+        row -= 1;
+    }
+
+    let mut cells = Vec::new();
+    loop {
+        while row <= max_row && buffer.is_line_blank(row) {
+            row += 1;
+        }
+        if row > max_row {
+            return (cells, None);
+        }
+        if row > range.end.row && !cells.is_empty() {
+            return (cells, Some(Point::new(row, 0)));
+        }
+
+        let start_row = row;
+        while row < max_row && !buffer.is_line_blank(row + 1) {
+            row += 1;
+        }
+        cells.push(Point::new(start_row, 0)..Point::new(row, buffer.line_len(row)));
+        row += 1;
+    }
+}
+
+/// Returns the rendered source of a Jupytext markdown cell (one whose header
+/// reads e.g. `# %% [markdown]` for the marker `%%`), with the header line and
+/// the comment prefixes removed. Returns `None` for any other range.
+pub(crate) fn markdown_cell_text(
+    buffer: &BufferSnapshot,
+    range: Range<Point>,
+    marker: &str,
+) -> Option<String> {
+    let language = buffer.language_at(range.start)?;
+    let default_scope = language.default_scope();
+    let comment_prefixes = default_scope.line_comment_prefixes();
+
+    let text = buffer.text_for_range(range).collect::<String>();
+    let mut lines = text.lines();
+    let header = lines.next()?;
+    comment_prefixes.iter().find_map(|comment_prefix| {
+        if header
+            .strip_prefix(&**comment_prefix)?
+            .strip_prefix(marker)?
+            .trim()
+            != "[markdown]"
+        {
+            return None;
+        }
+
+        let markdown = lines
+            .clone()
+            .map(|line| {
+                line.strip_prefix(&**comment_prefix)
+                    .or_else(|| line.strip_prefix(comment_prefix.trim_end()))
+                    .unwrap_or(line)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(markdown)
+    })
+}
+
+pub(crate) fn runnable_ranges(
     buffer: &BufferSnapshot,
     range: Range<Point>,
+    separator: &CellSeparator,
 ) -> (Vec<Range<Point>>, Option<Point>) {
     if let Some(language) = buffer.language() {
         if language.name() == "Markdown".into() {
@@ -414,9 +1100,12 @@ fn runnable_ranges(
         }
     }
 
-    let (jupytext_snippets, next_cursor) = jupytext_cells(buffer, range.clone());
-    if !jupytext_snippets.is_empty() {
-        return (jupytext_snippets, next_cursor);
+    let (cells, next_cursor) = match separator {
+        CellSeparator::Marker(marker) => jupytext_cells(buffer, range.clone(), marker),
+        CellSeparator::BlankLine => blank_line_cells(buffer, range.clone()),
+    };
+    if !cells.is_empty() {
+        return (cells, next_cursor);
     }
 
     let snippet_range = cell_range(buffer, range.start.row, range.end.row);
@@ -466,9 +1155,72 @@ fn get_language(editor: WeakView<Editor>, cx: &mut WindowContext) -> Option<Arc<
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::{AppContext, Context};
+    use crate::test::FakeSession;
+    use gpui::{AppContext, Context, TestAppContext};
     use indoc::indoc;
     use language::{Buffer, Language, LanguageConfig, LanguageRegistry};
+    use project::Project;
+    use runtimelib::JupyterMessageContent;
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    /// Runs and advances from `cursor`, returning the code the kernel was sent,
+    /// the editor's text and where the cursor ended up.
+    fn run_and_advance_from(session: &mut FakeSession, cursor: Point) -> (String, String, Point) {
+        let editor = session.editor.clone();
+        editor.update(session.cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.select_ranges([cursor..cursor]);
+            });
+        });
+        session
+            .cx
+            .update(|cx| run_and_advance(editor.downgrade(), cx))
+            .unwrap();
+        session.cx.run_until_parked();
+
+        let request = session.next_request();
+        let JupyterMessageContent::ExecuteRequest(execute_request) = request.content else {
+            panic!("expected an execute request, got {:?}", request.content);
+        };
+        editor.update(session.cx, |editor, cx| {
+            let cursor = editor.selections.newest::<Point>(cx).head();
+            (execute_request.code, editor.text(cx), cursor)
+        })
+    }
+
+    #[gpui::test]
+    async fn test_run_and_advance(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("# %%\na = 1\n", cx).await;
+        session.set_cell_language();
+
+        // Running the last cell adds a new one, with the cursor inside it.
+        let (code, text, cursor) = run_and_advance_from(&mut session, Point::new(1, 0));
+        assert_eq!(code, "# %%\na = 1");
+        assert_eq!(text, "# %%\na = 1\n# %%\n\n");
+        assert_eq!(cursor, Point::new(3, 0));
+
+        // Without cells, the cursor just moves down past the code that ran.
+        session.editor.update(session.cx, |editor, cx| {
+            editor.set_text("a = 1\nb = 2\n", cx);
+        });
+        let (code, text, cursor) = run_and_advance_from(&mut session, Point::new(0, 0));
+        assert_eq!(code, "a = 1");
+        assert_eq!(text, "a = 1\nb = 2\n");
+        assert_eq!(cursor, Point::new(1, 0));
+    }
 
     #[gpui::test]
     fn test_snippet_ranges(cx: &mut AppContext) {
@@ -499,7 +1251,11 @@ mod tests {
         let snapshot = buffer.read(cx).snapshot();
 
         // Single-point selection
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(0, 4)..Point::new(0, 4));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(0, 4)..Point::new(0, 4),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
@@ -507,7 +1263,11 @@ mod tests {
         assert_eq!(snippets, vec!["print(1 + 1)"]);
 
         // Multi-line selection
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(0, 5)..Point::new(2, 0));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(0, 5)..Point::new(2, 0),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
@@ -520,7 +1280,11 @@ mod tests {
         );
 
         // Trimming multiple trailing blank lines
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(0, 5)..Point::new(5, 0));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(0, 5)..Point::new(5, 0),
+            &CellSeparator::default(),
+        );
 
         let snippets = snippets
             .into_iter()
@@ -573,7 +1337,11 @@ mod tests {
         let snapshot = buffer.read(cx).snapshot();
 
         // Jupytext snippet surrounding an empty selection
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(2, 5)..Point::new(2, 5));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(2, 5)..Point::new(2, 5),
+            &CellSeparator::default(),
+        );
 
         let snippets = snippets
             .into_iter()
@@ -589,7 +1357,11 @@ mod tests {
         );
 
         // Jupytext snippets intersecting a non-empty selection
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(2, 5)..Point::new(6, 2));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(2, 5)..Point::new(6, 2),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
@@ -612,6 +1384,238 @@ mod tests {
                 }
             ]
         );
+
+        // Rows that get a run button in the gutter
+        assert_eq!(jupytext_cell_starts(&snapshot, "%%"), vec![1, 6]);
+    }
+
+    #[gpui::test]
+    fn test_cell_separators(cx: &mut AppContext) {
+        let test_language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["-- ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                indoc! { r#"
+                    -- In[1]
+                    print(1 + 1)
+                    print(2 + 2)
+
+                    -- In[2]
+                    print(3 + 3)
+
+
+                    print(4 + 4)
+                "# },
+                cx,
+            )
+            .with_language(test_language, cx)
+        });
+        let snapshot = buffer.read(cx).snapshot();
+        let cell_texts = |ranges: Vec<Range<Point>>| {
+            ranges
+                .into_iter()
+                .map(|range| snapshot.text_for_range(range).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        // A custom marker
+        let (snippets, next_cursor) = runnable_ranges(
+            &snapshot,
+            Point::new(1, 0)..Point::new(1, 0),
+            &CellSeparator::Marker("In[".into()),
+        );
+        assert_eq!(
+            cell_texts(snippets),
+            vec![indoc! { r#"
+                -- In[1]
+                print(1 + 1)
+                print(2 + 2)"# }]
+        );
+        assert_eq!(next_cursor, Some(Point::new(4, 0)));
+
+        // Blank lines, with the cursor in the middle of a cell
+        let (snippets, next_cursor) = runnable_ranges(
+            &snapshot,
+            Point::new(5, 3)..Point::new(5, 3),
+            &CellSeparator::BlankLine,
+        );
+        assert_eq!(cell_texts(snippets), vec!["-- In[2]\nprint(3 + 3)"]);
+        assert_eq!(next_cursor, Some(Point::new(8, 0)));
+
+        // Blank lines, with a selection spanning several cells
+        let (snippets, next_cursor) = runnable_ranges(
+            &snapshot,
+            Point::new(2, 0)..Point::new(8, 0),
+            &CellSeparator::BlankLine,
+        );
+        assert_eq!(
+            cell_texts(snippets),
+            vec![
+                "-- In[1]\nprint(1 + 1)\nprint(2 + 2)",
+                "-- In[2]\nprint(3 + 3)",
+                "print(4 + 4)",
+            ]
+        );
+        assert_eq!(next_cursor, None);
+    }
+
+    #[gpui::test]
+    fn test_new_cell_edit(cx: &mut AppContext) {
+        let test_language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["# ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+        let insert_cell = |separator: CellSeparator, cx: &mut AppContext| {
+            let buffer = cx.new_model(|cx| {
+                Buffer::local("a = 1\nb = 2\n", cx).with_language(test_language.clone(), cx)
+            });
+            let snapshot = buffer.read(cx).snapshot();
+            let (position, text, cursor) = new_cell_edit(&snapshot, 0, &separator).unwrap();
+            buffer.update(cx, |buffer, cx| {
+                buffer.edit([(position..position, text)], None, cx);
+            });
+            (buffer.read(cx).text(), cursor)
+        };
+
+        assert_eq!(
+            insert_cell(CellSeparator::default(), cx),
+            ("a = 1\n# %%\n\nb = 2\n".to_string(), Point::new(2, 0))
+        );
+        assert_eq!(
+            insert_cell(CellSeparator::BlankLine, cx),
+            ("a = 1\n\n\nb = 2\n".to_string(), Point::new(2, 0))
+        );
+
+        // Markers can't be written without a line comment.
+        let plain_text = cx.new_model(|cx| Buffer::local("a = 1\n", cx));
+        let snapshot = plain_text.read(cx).snapshot();
+        assert_eq!(new_cell_edit(&snapshot, 0, &CellSeparator::default()), None);
+    }
+
+    #[gpui::test]
+    fn test_merge_cells_edit(cx: &mut AppContext) {
+        let test_language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["# ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                indoc! { r#"
+                    import os
+
+                    # %%
+                    a = 1
+
+                    # %%
+                    b = 2"# },
+                cx,
+            )
+            .with_language(test_language, cx)
+        });
+        let snapshot = buffer.read(cx).snapshot();
+        let merge = |row, separator: &CellSeparator, above| {
+            let (boundary, merged_range) = merge_cells_edit(&snapshot, row, separator, above)?;
+            let mut text = snapshot.text();
+            text.replace_range(
+                snapshot.point_to_offset(boundary.start)..snapshot.point_to_offset(boundary.end),
+                "",
+            );
+            Some((text, merged_range))
+        };
+        let marker = CellSeparator::default();
+
+        // The code before the first marker is a cell of its own.
+        assert_eq!(
+            merge(3, &marker, true),
+            Some((
+                "import os\n\na = 1\n\n# %%\nb = 2".to_string(),
+                Point::new(0, 0)..Point::new(4, 0)
+            ))
+        );
+        assert_eq!(
+            merge(3, &marker, false),
+            Some((
+                "import os\n\n# %%\na = 1\n\nb = 2".to_string(),
+                Point::new(2, 0)..Point::new(6, 5)
+            ))
+        );
+        // There's no cell below the last one, nor above the first one.
+        assert_eq!(merge(6, &marker, false), None);
+        assert_eq!(merge(0, &marker, true), None);
+
+        assert_eq!(
+            merge(3, &CellSeparator::BlankLine, false),
+            Some((
+                "import os\n\n# %%\na = 1\n# %%\nb = 2".to_string(),
+                Point::new(2, 0)..Point::new(6, 5)
+            ))
+        );
+        // Blank lines between cells belong to the cell above.
+        assert_eq!(
+            merge(1, &CellSeparator::BlankLine, false),
+            Some((
+                "import os\n# %%\na = 1\n\n# %%\nb = 2".to_string(),
+                Point::new(0, 0)..Point::new(3, 5)
+            ))
+        );
+    }
+
+    #[gpui::test]
+    fn test_jupytext_markdown_cells(cx: &mut AppContext) {
+        let test_language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["# ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                indoc! { r#"
+                    # %% [markdown]
+                    # # Arithmetic
+                    #
+                    # Some **sums**.
+
+                    # %%
+                    print(1 + 1)
+                "# },
+                cx,
+            )
+            .with_language(test_language, cx)
+        });
+        let snapshot = buffer.read(cx).snapshot();
+
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(0, 0)..Point::new(5, 0),
+            &CellSeparator::default(),
+        );
+        let markdown = snippets
+            .into_iter()
+            .map(|range| markdown_cell_text(&snapshot, range, "%%"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            markdown,
+            vec![Some("# Arithmetic\n\nSome **sums**.".to_string()), None]
+        );
     }
 
     #[gpui::test]
@@ -651,7 +1655,11 @@ mod tests {
         });
         let snapshot = buffer.read(cx).snapshot();
 
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(3, 5)..Point::new(8, 5));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(3, 5)..Point::new(8, 5),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
@@ -696,7 +1704,11 @@ mod tests {
         });
         let snapshot = buffer.read(cx).snapshot();
 
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(3, 5)..Point::new(12, 5));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(3, 5)..Point::new(12, 5),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
@@ -735,7 +1747,11 @@ mod tests {
         });
         let snapshot = buffer.read(cx).snapshot();
 
-        let (snippets, _) = runnable_ranges(&snapshot, Point::new(4, 5)..Point::new(5, 5));
+        let (snippets, _) = runnable_ranges(
+            &snapshot,
+            Point::new(4, 5)..Point::new(5, 5),
+            &CellSeparator::default(),
+        );
         let snippets = snippets
             .into_iter()
             .map(|range| snapshot.text_for_range(range).collect::<String>())
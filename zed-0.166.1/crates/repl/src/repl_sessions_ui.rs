@@ -1,6 +1,6 @@
 use editor::Editor;
 use gpui::{
-    actions, prelude::*, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    actions, prelude::*, Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
     Subscription, View,
 };
 use project::ProjectItem as _;
@@ -18,12 +18,30 @@ actions!(
     [
         Run,
         RunInPlace,
+        RunCell,
+        RunAndAdvance,
+        InsertCell,
+        MergeCellAbove,
+        MergeCellBelow,
         ClearOutputs,
+        CollapseAllOutputs,
+        ExpandAllOutputs,
         Sessions,
         Interrupt,
         Shutdown,
+        ShutdownAll,
         Restart,
-        RefreshKernelspecs
+        RestartAndClearOutputs,
+        RestartAndRunAll,
+        SetWorkingDirectory,
+        ResetWorkingDirectory,
+        NextOutput,
+        PreviousOutput,
+        OpenOutputInNewTab,
+        CopyCellAsMarkdown,
+        CopyKernelConnectionInfo,
+        RefreshKernelspecs,
+        OpenScratchpad
     ]
 );
 
@@ -45,6 +63,25 @@ pub fn init(cx: &mut AppContext) {
                 }
             });
 
+            workspace.register_action(|_workspace, _: &ShutdownAll, cx| {
+                let sessions = ReplStore::global(cx)
+                    .read(cx)
+                    .sessions()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for session in sessions {
+                    session.update(cx, |session, cx| session.shutdown(cx));
+                }
+            });
+
+            workspace.register_action(|workspace, _: &OpenScratchpad, cx| {
+                if !JupyterSettings::enabled(cx) {
+                    return;
+                }
+
+                crate::scratchpad::open_scratchpad(workspace, cx);
+            });
+
             workspace.register_action(|_workspace, _: &RefreshKernelspecs, cx| {
                 let store = ReplStore::global(cx);
                 store.update(cx, |store, cx| {
@@ -121,6 +158,79 @@ pub fn init(cx: &mut AppContext) {
                     }
                 })
                 .detach();
+
+            editor
+                .register_action({
+                    let editor_handle = editor_handle.clone();
+                    move |_: &RunCell, cx| {
+                        if !JupyterSettings::enabled(cx) {
+                            return;
+                        }
+
+                        let Some(cursor) = editor_handle
+                            .update(cx, |editor, _| editor.selections.newest_anchor().head())
+                            .ok()
+                        else {
+                            return;
+                        };
+                        crate::run_cell(editor_handle.clone(), cursor, cx).log_err();
+                    }
+                })
+                .detach();
+
+            editor
+                .register_action({
+                    let editor_handle = editor_handle.clone();
+                    move |_: &RunAndAdvance, cx| {
+                        if !JupyterSettings::enabled(cx) {
+                            return;
+                        }
+
+                        crate::run_and_advance(editor_handle.clone(), cx).log_err();
+                    }
+                })
+                .detach();
+
+            editor
+                .register_action({
+                    let editor_handle = editor_handle.clone();
+                    move |_: &InsertCell, cx| {
+                        if !JupyterSettings::enabled(cx) {
+                            return;
+                        }
+
+                        crate::insert_cell(editor_handle.clone(), cx);
+                    }
+                })
+                .detach();
+
+            editor
+                .register_action({
+                    let editor_handle = editor_handle.clone();
+                    move |_: &MergeCellAbove, cx| {
+                        if !JupyterSettings::enabled(cx) {
+                            return;
+                        }
+
+                        crate::merge_cell(editor_handle.clone(), true, cx);
+                    }
+                })
+                .detach();
+
+            editor
+                .register_action({
+                    let editor_handle = editor_handle.clone();
+                    move |_: &MergeCellBelow, cx| {
+                        if !JupyterSettings::enabled(cx) {
+                            return;
+                        }
+
+                        crate::merge_cell(editor_handle.clone(), false, cx);
+                    }
+                })
+                .detach();
+
+            crate::repl_editor::setup_cell_run_buttons(editor, cx);
         });
     })
     .detach();
@@ -234,7 +344,15 @@ impl Render for ReplSessionsPage {
             );
         }
 
-        ReplSessionsContainer::new("Jupyter Kernel Sessions").children(sessions)
+        ReplSessionsContainer::new("Jupyter Kernel Sessions")
+            .child(
+                h_flex().justify_end().child(
+                    Button::new("shutdown-all", "Shut Down All")
+                        .style(ButtonStyle::Subtle)
+                        .on_click(|_, cx| cx.dispatch_action(ShutdownAll.boxed_clone())),
+                ),
+            )
+            .children(sessions)
     }
 }
 
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use gpui::{AppContext, Pixels};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use workspace::dock::DockPosition;
+
+/// Settings for how REPL sessions display their results.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReplSettings {
+    /// The maximum height of an output area, in lines, before it scrolls.
+    pub max_output_height: Option<u32>,
+    /// How long to wait for an interrupted kernel to go idle before restarting it, in milliseconds.
+    pub interrupt_timeout: u64,
+    /// Whether markdown cells are rendered beneath their source instead of being executed.
+    pub render_markdown_cells: bool,
+    /// How cells are separated, by language name.
+    pub cell_separators: HashMap<String, CellSeparator>,
+    /// Whether to scroll down to an execution's output when it first appears.
+    pub follow_output: bool,
+    /// Whether to ask before shutting down a kernel that is still running code.
+    pub confirm_shutdown: bool,
+    /// Where the outputs of executions are shown.
+    pub output_placement: OutputPlacement,
+    /// Whether kernels are only started once code is first run in their session.
+    pub lazy_start: bool,
+}
+
+impl ReplSettings {
+    /// Returns how cells are separated in code of the given language.
+    pub fn cell_separator(&self, language_name: &str) -> CellSeparator {
+        self.cell_separators
+            .get(language_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// How the code in a buffer is split into cells that are run one at a time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CellSeparator {
+    /// Cells start at lines made of a line comment followed by this marker,
+    /// such as `# %%` for the marker `%%`.
+    Marker(String),
+    /// Cells are separated by one or more blank lines.
+    BlankLine,
+}
+
+impl Default for CellSeparator {
+    fn default() -> Self {
+        Self::Marker("%%".to_string())
+    }
+}
+
+/// Where the outputs of executions are shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputPlacement {
+    /// In blocks below the code that produced them.
+    #[default]
+    Inline,
+    /// In the REPL output panel, keeping the code free of outputs.
+    Panel,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ReplSettingsContent {
+    /// The maximum height of an output area, in lines. Taller outputs are
+    /// scrolled within the output area instead of growing it. When unset,
+    /// output areas grow to fit their outputs.
+    ///
+    /// Default: null
+    pub max_output_height: Option<u32>,
+    /// How long to wait, in milliseconds, for a kernel to go idle after an
+    /// interrupt. Kernels that are still busy afterwards are restarted.
+    ///
+    /// Default: 5000
+    pub interrupt_timeout: Option<u64>,
+    /// Whether Jupytext cells marked `[markdown]` (e.g. `# %% [markdown]`)
+    /// are rendered as formatted text beneath their source when run,
+    /// instead of being sent to the kernel.
+    ///
+    /// Default: false
+    pub render_markdown_cells: Option<bool>,
+    /// How cells are separated, by language name. Either `{ "marker": "%%" }`,
+    /// for cells that start at a line comment followed by the marker (such as
+    /// `# %%`), or `"blank_line"`, for cells separated by blank lines.
+    /// Languages without an entry use the `%%` marker.
+    ///
+    /// Default: {}
+    pub cell_separators: Option<HashMap<String, CellSeparator>>,
+    /// Whether to scroll the editor down when an execution's first output
+    /// appears in the lower half of the viewport, so that there's room to
+    /// show it. The editor isn't scrolled if the code that produced the
+    /// output has been scrolled out of view.
+    ///
+    /// Default: false
+    pub follow_output: Option<bool>,
+    /// Whether to ask for confirmation before shutting down a kernel that is
+    /// busy or has executions queued, since their results would be lost.
+    ///
+    /// Default: true
+    pub confirm_shutdown: Option<bool>,
+    /// Where to show the outputs of executions: `"inline"`, in blocks below
+    /// the code that produced them, or `"panel"`, in the REPL output panel.
+    /// Changes apply to outputs of later executions.
+    ///
+    /// Default: inline
+    pub output_placement: Option<OutputPlacement>,
+    /// Whether to wait until code is first run in a session before starting
+    /// its kernel, instead of starting it as soon as the session is created.
+    /// Avoids running kernels for editors that never run any code.
+    ///
+    /// Default: false
+    pub lazy_start: Option<bool>,
+}
+
+impl Settings for ReplSettings {
+    const KEY: Option<&'static str> = Some("repl");
+
+    type FileContent = ReplSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// Settings for the panel listing the cells of the active REPL session.
+#[derive(Deserialize, Debug)]
+pub struct CellOutlinePanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct CellOutlinePanelSettingsContent {
+    /// Whether to show the cell outline panel button in the status bar
+    /// while the active editor has a REPL session.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the cell outline panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the cell outline panel in pixels.
+    ///
+    /// Default: 240
+    pub default_width: Option<f32>,
+}
+
+impl Settings for CellOutlinePanelSettings {
+    const KEY: Option<&'static str> = Some("cell_outline_panel");
+
+    type FileContent = CellOutlinePanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
+/// Settings for the panel showing REPL outputs, when they aren't shown inline.
+#[derive(Deserialize, Debug)]
+pub struct ReplOutputPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+    pub default_height: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ReplOutputPanelSettingsContent {
+    /// Whether to show the REPL output panel button in the status bar while
+    /// outputs are placed in the panel and the active editor has a REPL
+    /// session.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the REPL output panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the REPL output panel in pixels, when docked to the
+    /// left or right.
+    ///
+    /// Default: 480
+    pub default_width: Option<f32>,
+    /// Default height of the REPL output panel in pixels, when docked to the
+    /// bottom.
+    ///
+    /// Default: 320
+    pub default_height: Option<f32>,
+}
+
+impl Settings for ReplOutputPanelSettings {
+    const KEY: Option<&'static str> = Some("repl_output_panel");
+
+    type FileContent = ReplOutputPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -15,7 +15,7 @@ use settings::{Settings, SettingsStore};
 use crate::kernels::{
     list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
 };
-use crate::{JupyterSettings, KernelSpecification, Session};
+use crate::{JupyterSettings, KernelSpecification, KernelStatus, Session};
 
 struct GlobalReplStore(Model<ReplStore>);
 
@@ -25,6 +25,8 @@ pub struct ReplStore {
     fs: Arc<dyn Fs>,
     enabled: bool,
     sessions: HashMap<EntityId, View<Session>>,
+    session_subscriptions: HashMap<EntityId, Subscription>,
+    any_kernel_busy: bool,
     kernel_specifications: Vec<KernelSpecification>,
     selected_kernel_for_worktree: HashMap<WorktreeId, KernelSpecification>,
     kernel_specifications_for_worktree: HashMap<WorktreeId, Vec<KernelSpecification>>,
@@ -45,10 +47,24 @@ impl ReplStore {
         cx.set_global(GlobalReplStore(store))
     }
 
+    /// Sets up the global store like [`ReplStore::init`], without looking for
+    /// kernels.
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn init_test(fs: Arc<dyn Fs>, telemetry: Arc<Telemetry>, cx: &mut AppContext) {
+        let store = cx.new_model(move |cx| Self::new(fs, telemetry, cx));
+        cx.set_global(GlobalReplStore(store))
+    }
+
     pub fn global(cx: &AppContext) -> Model<Self> {
         cx.global::<GlobalReplStore>().0.clone()
     }
 
+    /// Returns the global store, if the REPL has been initialized.
+    pub fn try_global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalReplStore>()
+            .map(|store| store.0.clone())
+    }
+
     pub fn new(fs: Arc<dyn Fs>, telemetry: Arc<Telemetry>, cx: &mut ModelContext<Self>) -> Self {
         let subscriptions = vec![cx.observe_global::<SettingsStore>(move |this, cx| {
             this.set_enabled(JupyterSettings::enabled(cx), cx);
@@ -59,6 +75,8 @@ impl ReplStore {
             telemetry,
             enabled: JupyterSettings::enabled(cx),
             sessions: HashMap::default(),
+            session_subscriptions: HashMap::default(),
+            any_kernel_busy: false,
             kernel_specifications: Vec::new(),
             _subscriptions: subscriptions,
             kernel_specifications_for_worktree: HashMap::default(),
@@ -99,6 +117,22 @@ impl ReplStore {
         self.sessions.values()
     }
 
+    /// Whether the kernel of any session is executing code.
+    pub fn any_kernel_busy(&self) -> bool {
+        self.any_kernel_busy
+    }
+
+    fn update_any_kernel_busy(&mut self, cx: &mut ModelContext<Self>) {
+        let any_kernel_busy = self
+            .sessions
+            .values()
+            .any(|session| matches!(session.read(cx).kernel.status(), KernelStatus::Busy));
+        if self.any_kernel_busy != any_kernel_busy {
+            self.any_kernel_busy = any_kernel_busy;
+            cx.notify();
+        }
+    }
+
     fn set_enabled(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
         if self.enabled == enabled {
             return;
@@ -281,11 +315,23 @@ impl ReplStore {
         self.sessions.get(&entity_id)
     }
 
-    pub fn insert_session(&mut self, entity_id: EntityId, session: View<Session>) {
+    pub fn insert_session(
+        &mut self,
+        entity_id: EntityId,
+        session: View<Session>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let subscription = cx.observe(&session, |this, _, cx| this.update_any_kernel_busy(cx));
+        self.session_subscriptions.insert(entity_id, subscription);
         self.sessions.insert(entity_id, session);
+        self.update_any_kernel_busy(cx);
+        cx.notify();
     }
 
-    pub fn remove_session(&mut self, entity_id: EntityId) {
+    pub fn remove_session(&mut self, entity_id: EntityId, cx: &mut ModelContext<Self>) {
         self.sessions.remove(&entity_id);
+        self.session_subscriptions.remove(&entity_id);
+        self.update_any_kernel_busy(cx);
+        cx.notify();
     }
 }
@@ -0,0 +1,74 @@
+//! REPL scratchpads: untitled buffers with a session of their own, for trying
+//! things out without creating a file.
+
+use editor::Editor;
+use gpui::{prelude::*, rems, ViewContext, WeakView, WindowContext};
+use picker::Picker;
+use workspace::Workspace;
+
+use crate::components::KernelPickerDelegate;
+use crate::repl_editor::start_session;
+use crate::repl_store::ReplStore;
+use crate::KernelSpecification;
+
+/// Asks for a kernel, then opens an untitled buffer in the kernel's language
+/// with a session already attached.
+pub(crate) fn open_scratchpad(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+
+    let store = ReplStore::global(cx).read(cx);
+    let kernels = match project.visible_worktrees(cx).next() {
+        Some(worktree) => store
+            .kernel_specifications_for_worktree(worktree.read(cx).id())
+            .cloned()
+            .collect::<Vec<_>>(),
+        None => store
+            .pure_jupyter_kernel_specifications()
+            .cloned()
+            .collect(),
+    };
+    let selected_kernelspec = kernels.first().cloned();
+
+    let workspace_handle = cx.view().downgrade();
+    workspace.toggle_modal(cx, move |cx| {
+        let delegate = KernelPickerDelegate::new(
+            kernels,
+            selected_kernelspec,
+            Box::new(move |kernel_specification, cx| {
+                open_scratchpad_with_kernel(workspace_handle.clone(), kernel_specification, cx)
+            }),
+        );
+        Picker::uniform_list(delegate, cx).width(rems(30.))
+    });
+}
+
+fn open_scratchpad_with_kernel(
+    workspace: WeakView<Workspace>,
+    kernel_specification: KernelSpecification,
+    cx: &mut WindowContext,
+) {
+    let Ok(project) = workspace.update(cx, |workspace, _| workspace.project().clone()) else {
+        return;
+    };
+    let language = project
+        .read(cx)
+        .languages()
+        .language_for_name(&kernel_specification.language());
+
+    cx.spawn(|mut cx| async move {
+        // Kernels for languages Zed doesn't know still get a plain text buffer.
+        let language = language.await.ok();
+        workspace.update(&mut cx, |workspace, cx| {
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer("", language, cx)
+            });
+            let editor = cx.new_view(|cx| Editor::for_buffer(buffer, Some(project), cx));
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+            start_session(kernel_specification, editor.downgrade(), cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
@@ -1,33 +1,48 @@
 use crate::components::KernelListItem;
 use crate::kernels::RemoteRunningKernel;
+use crate::repl_editor::{cell_separator, runnable_ranges};
+use crate::repl_store::ReplStore;
 use crate::setup_editor_session_actions;
 use crate::{
     kernels::{Kernel, KernelSpecification, NativeRunningKernel},
-    outputs::{ExecutionStatus, ExecutionView},
-    KernelStatus,
+    outputs::{fenced_code_block, ExecutionStatus, ExecutionView},
+    KernelStatus, OutputPlacement, ReplSettings,
 };
 use client::telemetry::Telemetry;
 use collections::{HashMap, HashSet};
 use editor::{
     display_map::{
         BlockContext, BlockId, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId,
-        RenderBlock,
+        RenderBlock, ToDisplayPoint,
     },
     scroll::Autoscroll,
-    Anchor, AnchorRangeExt as _, Editor, MultiBuffer, ToPoint,
+    Anchor, AnchorRangeExt as _, Editor, MultiBuffer, MultiBufferSnapshot, ToPoint,
 };
-use futures::FutureExt as _;
+use futures::{channel::oneshot, FutureExt as _};
 use gpui::{
-    div, prelude::*, EventEmitter, Model, Render, Subscription, Task, View, ViewContext, WeakView,
+    black, div, point, prelude::*, white, AppContext, ClickEvent, EventEmitter, Hsla, Model,
+    PromptLevel, Render, Subscription, Task, View, ViewContext, WeakView,
 };
-use language::Point;
+use i18n::{t, t_plural};
+use jupyter_protocol::connection_info::ConnectionInfo;
+use language::{Bias, Edit, Point, Subscription as BufferSubscription};
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    KernelInfoReply, MimeBundle, ShutdownRequest,
 };
-use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
-use theme::ActiveTheme;
+use settings::Settings as _;
+use std::{
+    cell::RefCell,
+    env::temp_dir,
+    mem,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use theme::{ActiveTheme, ThemeColors};
 use ui::{prelude::*, IconButtonShape, Tooltip};
 use util::ResultExt as _;
 
@@ -37,17 +52,322 @@ pub struct Session {
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
     pub kernel_specification: KernelSpecification,
+    /// The directory to start the kernel in, overriding the one derived from
+    /// the editor.
+    working_directory: Option<PathBuf>,
     telemetry: Arc<Telemetry>,
-    _buffer_subscription: Subscription,
+    /// Restarts the kernel if an interrupt doesn't bring it back to idle in time.
+    pending_interrupt: Option<Task<()>>,
+    /// Code to execute, in order, once the kernel is running.
+    queued_executions: Vec<(String, Range<Anchor>)>,
+    /// Execute requests sent while the kernel was starting, to deliver in
+    /// order once it's running.
+    pending_requests: Vec<JupyterMessage>,
+    /// The priority of output blocks among other blocks below the same row.
+    block_priority: usize,
+    pending_display_updates: PendingDisplayUpdates,
+    code_range_offsets: CodeRangeOffsets,
+    block_removals: PendingBlockRemovals,
+    /// Blocks whose code was deleted, keyed like the blocks, oldest first.
+    detached_blocks: Vec<(String, DetachedBlock)>,
+    /// Senders for [`Session::execute_and_wait`], keyed by execute request message id.
+    execution_waiters: HashMap<String, oneshot::Sender<anyhow::Result<ExecutionStatus>>>,
+    /// Edits to the editor's buffer since it was last searched for moved code.
+    buffer_edits: Option<BufferSubscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Display data updates that arrived before the display they update, keyed by
+/// display id. Fast kernels can send an `update_display_data` message before
+/// the `display_data` message that creates its display.
+#[derive(Default)]
+struct PendingDisplayUpdates(HashMap<String, MimeBundle>);
+
+impl PendingDisplayUpdates {
+    /// Holds on to an update until its display appears. Only the latest
+    /// update for each display is kept.
+    fn defer(&mut self, display_id: String, data: MimeBundle) {
+        self.0.insert(display_id, data);
+    }
+
+    /// Takes the pending update for the display created by `message`, if any.
+    fn take(&mut self, message: &JupyterMessageContent) -> Option<(String, MimeBundle)> {
+        let transient = match message {
+            JupyterMessageContent::DisplayData(display_data) => display_data.transient.as_ref(),
+            JupyterMessageContent::ExecuteResult(result) => result.transient.as_ref(),
+            _ => None,
+        }?;
+        let display_id = transient.display_id.clone()?;
+        let data = self.0.remove(&display_id)?;
+        Some((display_id, data))
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// The offsets of each block's code range, keyed like the blocks, as of the
+/// buffer's latest edit. Executing again without editing in between reuses
+/// them, instead of resolving every block's anchors to check for overlaps.
+#[derive(Default)]
+struct CodeRangeOffsets {
+    edit_count: usize,
+    ranges: HashMap<String, Range<usize>>,
+}
+
+impl CodeRangeOffsets {
+    fn range(
+        &mut self,
+        key: &str,
+        code_range: &Range<Anchor>,
+        buffer: &MultiBufferSnapshot,
+    ) -> Range<usize> {
+        if self.edit_count != buffer.edit_count() {
+            self.edit_count = buffer.edit_count();
+            self.ranges.clear();
+        }
+        self.ranges
+            .entry(key.to_string())
+            .or_insert_with(|| code_range.to_offset(buffer))
+            .clone()
+    }
+}
+
+/// Output blocks waiting to be removed from the editor. Removals queued during
+/// the same update are made together once it ends, so that invalidating many
+/// outputs at once, as when deleting a buffer's contents, only updates the
+/// editor's blocks once.
+#[derive(Clone, Default)]
+struct PendingBlockRemovals(Rc<RefCell<HashSet<CustomBlockId>>>);
+
+impl PendingBlockRemovals {
+    fn queue(
+        &self,
+        block_ids: impl IntoIterator<Item = CustomBlockId>,
+        editor: &WeakView<Editor>,
+        cx: &mut WindowContext,
+    ) {
+        let mut pending = self.0.borrow_mut();
+        let was_empty = pending.is_empty();
+        pending.extend(block_ids);
+        if was_empty && !pending.is_empty() {
+            let this = self.clone();
+            let editor = editor.clone();
+            cx.defer(move |cx| this.flush(&editor, cx));
+        }
+    }
+
+    fn flush(&self, editor: &WeakView<Editor>, cx: &mut WindowContext) {
+        let block_ids = mem::take(&mut *self.0.borrow_mut());
+        if block_ids.is_empty() {
+            return;
+        }
+        editor
+            .update(cx, |editor, cx| editor.remove_blocks(block_ids, None, cx))
+            .ok();
+    }
+}
+
+/// A cell of the code in a session's editor, as returned by [`Session::cells`].
+#[derive(Clone, Debug)]
+pub struct CellInfo {
+    /// The cell's code, as an offset range in the editor's buffer.
+    pub range: Range<usize>,
+    /// The status of the cell's latest execution, if it has been run.
+    pub status: Option<ExecutionStatus>,
+    /// The execution count the kernel reported for the cell's latest
+    /// execution, if any.
+    pub execution_count: Option<usize>,
+}
+
+/// The outputs of an execution, as returned by [`Session::outputs`].
+pub(crate) struct CellOutput {
+    /// The key to close the outputs with, using [`Session::close_output`].
+    pub key: String,
+    /// The executed code, as an offset range in the editor's buffer.
+    pub range: Range<usize>,
+    pub execution_view: View<ExecutionView>,
 }
 
 struct EditorBlock {
     code_range: Range<Anchor>,
+    /// The text of the code, as it was when the block was inserted.
+    code: String,
     invalidation_anchor: Anchor,
-    block_id: CustomBlockId,
+    /// The block showing the outputs in the editor, unless they're shown in
+    /// the output panel instead.
+    block_id: Option<CustomBlockId>,
+    execution_view: View<ExecutionView>,
+    /// When the kernel started executing this block's code.
+    execution_started: Option<Instant>,
+}
+
+/// An output block whose code was deleted from the buffer. Its outputs are
+/// kept in case the same code reappears elsewhere, as when a cell is cut and
+/// pasted or moved a line at a time, so that they can follow it there.
+struct DetachedBlock {
+    code: String,
+    execution_view: View<ExecutionView>,
+}
+
+/// The most detached blocks kept at once. The oldest are dropped first.
+const MAX_DETACHED_BLOCKS: usize = 8;
+
+/// The number of bands the fade at the bottom of a capped output area is
+/// drawn with.
+const OVERFLOW_FADE_STEPS: usize = 4;
+
+/// The buttons below a capped output area, for showing the lines that don't
+/// fit in it.
+fn overflow_buttons(
     execution_view: View<ExecutionView>,
+    hidden_lines: usize,
+    text_line_height: Pixels,
+) -> impl IntoElement {
+    let open_in_new_tab = {
+        let execution_view = execution_view.clone();
+        move |_: &ClickEvent, cx: &mut WindowContext| {
+            execution_view.update(cx, |execution_view, cx| execution_view.open_in_new_tab(cx))
+        }
+    };
+
+    h_flex()
+        .gap_1()
+        .pb(text_line_height / 2.)
+        .child(
+            Button::new(
+                "show-all-output",
+                t_plural!("repl.show_all_output", hidden_lines),
+            )
+            .label_size(LabelSize::Small)
+            .icon(IconName::ChevronDown)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Muted)
+            .on_click(move |_, cx| {
+                execution_view.update(cx, |execution_view, cx| execution_view.expand(cx))
+            }),
+        )
+        .child(
+            Button::new("open-output-in-new-tab", t!("repl.open_output_in_new_tab"))
+                .label_size(LabelSize::Small)
+                .icon(IconName::FileText)
+                .icon_size(IconSize::Small)
+                .icon_color(Color::Muted)
+                .on_click(open_in_new_tab),
+        )
+}
+
+/// Fades the bottom of a capped output area into `background`, to show that
+/// the output continues past the cut.
+fn overflow_fade(background: Hsla, height: Pixels) -> impl IntoElement {
+    v_flex()
+        .absolute()
+        .bottom_0()
+        .left_0()
+        .w_full()
+        .children((1..=OVERFLOW_FADE_STEPS).map(|step| {
+            div()
+                .w_full()
+                .h(height / OVERFLOW_FADE_STEPS as f32)
+                .bg(background.opacity(step as f32 / OVERFLOW_FADE_STEPS as f32))
+        }))
+}
+
+/// The colors an output area is drawn with.
+#[derive(Debug, PartialEq)]
+struct OutputAreaColors {
+    border: Hsla,
+    background: Hsla,
+    /// The color of the buttons that collapse and close the output area.
+    icon: Hsla,
+}
+
+impl OutputAreaColors {
+    /// Picks the output area's colors from the theme. Custom themes can leave
+    /// colors transparent, which would hide the output area's borders and
+    /// buttons or blend it into the code around it, so the first visible color
+    /// of a few candidates is used. When none is, black or white is, whichever
+    /// stands out.
+    fn new(colors: &ThemeColors) -> Self {
+        fn first_visible(candidates: &[Hsla], fallback: Hsla) -> Hsla {
+            candidates
+                .iter()
+                .copied()
+                .find(|color| color.a > 0.)
+                .unwrap_or(fallback)
+        }
+
+        let background = first_visible(
+            &[
+                colors.background,
+                colors.surface_background,
+                colors.editor_background,
+            ],
+            if colors.text.a > 0. && colors.text.l < 0.5 {
+                white()
+            } else {
+                black()
+            },
+        );
+        let contrasting = if background.l < 0.5 { white() } else { black() };
+
+        Self {
+            border: first_visible(
+                &[
+                    colors.border,
+                    colors.border_variant,
+                    colors.text_muted.opacity(0.5),
+                ],
+                contrasting.opacity(0.5),
+            ),
+            background,
+            icon: first_visible(&[colors.text_muted, colors.text], contrasting),
+        }
+    }
+}
+
+/// Finds where `code` was moved to in `text` by an edit of the `edited` range.
+/// Only matches starting at the beginning of a line and touching the edit
+/// count, and there must be exactly one of them, so that outputs aren't
+/// attached to the wrong copy of duplicated code.
+fn find_moved_code(text: &str, code: &str, edited: Range<usize>) -> Option<usize> {
+    if code.trim().is_empty() {
+        return None;
+    }
+    let mut matches = text
+        .match_indices(code)
+        .map(|(start, _)| start)
+        .filter(|&start| start == 0 || text.as_bytes()[start - 1] == b'\n')
+        .filter(|&start| start <= edited.end && edited.start <= start + code.len());
+    let start = matches.next()?;
+    matches.next().is_none().then_some(start)
+}
+
+/// Finds where `code` was moved to by one of `edits`, searching only the
+/// lines around each edit rather than the whole buffer.
+fn find_moved_code_near_edits(
+    snapshot: &MultiBufferSnapshot,
+    code: &str,
+    edits: &[Edit<usize>],
+) -> Option<Range<usize>> {
+    edits.iter().find_map(|edit| {
+        let start = snapshot.clip_offset(edit.new.start.saturating_sub(code.len()), Bias::Left);
+        let start = snapshot.point_to_offset(Point::new(snapshot.offset_to_point(start).row, 0));
+        let end = (edit.new.end + code.len()).min(snapshot.len());
+        let end = snapshot.clip_offset(end, Bias::Right);
+        let text = snapshot.text_for_range(start..end).collect::<String>();
+        let edited = edit.new.start - start..edit.new.end - start;
+        find_moved_code(&text, code, edited)
+            .map(|offset| start + offset..start + offset + code.len())
+    })
 }
 
+/// The default priority of output blocks. Blocks placed below the same row are
+/// ordered by ascending priority, so outputs render beneath diagnostics, which
+/// are inserted at priority 0.
+pub const DEFAULT_OUTPUT_BLOCK_PRIORITY: usize = 1;
+
 type CloseBlockFn =
     Arc<dyn for<'a> Fn(CustomBlockId, &'a mut WindowContext) + Send + Sync + 'static>;
 
@@ -56,6 +376,7 @@ impl EditorBlock {
         editor: WeakView<Editor>,
         code_range: Range<Anchor>,
         status: ExecutionStatus,
+        priority: usize,
         on_close: CloseBlockFn,
         cx: &mut ViewContext<Session>,
     ) -> anyhow::Result<Self> {
@@ -70,9 +391,26 @@ impl EditorBlock {
         let execution_view =
             cx.new_view(|cx| ExecutionView::new(status, workspace.downgrade(), cx));
 
-        let (block_id, invalidation_anchor) = editor.update(cx, |editor, cx| {
+        Self::attach(editor, code_range, execution_view, priority, on_close, cx)
+    }
+
+    /// Shows an existing execution view for the code in `code_range`, in a
+    /// block below it or in the output panel, depending on the
+    /// `output_placement` setting.
+    fn attach(
+        editor: View<Editor>,
+        code_range: Range<Anchor>,
+        execution_view: View<ExecutionView>,
+        priority: usize,
+        on_close: CloseBlockFn,
+        cx: &mut ViewContext<Session>,
+    ) -> anyhow::Result<Self> {
+        let (block_id, code, invalidation_anchor) = editor.update(cx, |editor, cx| {
             let buffer = editor.buffer().clone();
             let buffer_snapshot = buffer.read(cx).snapshot(cx);
+            let code = buffer_snapshot
+                .text_for_range(code_range.clone())
+                .collect::<String>();
             let end_point = code_range.end.to_point(&buffer_snapshot);
             let next_row_start = end_point + Point::new(1, 0);
             if next_row_start > buffer_snapshot.max_point() {
@@ -89,24 +427,30 @@ impl EditorBlock {
             }
 
             let invalidation_anchor = buffer.read(cx).read(cx).anchor_before(next_row_start);
+            if ReplSettings::get_global(cx).output_placement == OutputPlacement::Panel {
+                return (None, code, invalidation_anchor);
+            }
+
             let block = BlockProperties {
                 placement: BlockPlacement::Below(code_range.end),
                 // Take up at least one height for status, allow the editor to determine the real height based on the content from render
                 height: 1,
                 style: BlockStyle::Sticky,
                 render: Self::create_output_area_renderer(execution_view.clone(), on_close.clone()),
-                priority: 0,
+                priority,
             };
 
             let block_id = editor.insert_blocks([block], None, cx)[0];
-            (block_id, invalidation_anchor)
+            (Some(block_id), code, invalidation_anchor)
         });
 
         anyhow::Ok(Self {
             code_range,
+            code,
             invalidation_anchor,
             block_id,
             execution_view,
+            execution_started: None,
         })
     }
 
@@ -132,6 +476,63 @@ impl EditorBlock {
             let rem_size = cx.rem_size();
 
             let text_line_height = text_style.line_height_in_pixels(rem_size);
+            let is_collapsed = execution_view.read(cx).is_collapsed();
+            let has_outputs = !execution_view.read(cx).outputs.is_empty();
+            let max_output_lines = ReplSettings::get_global(cx)
+                .max_output_height
+                .filter(|_| !execution_view.read(cx).is_expanded() && !is_collapsed);
+            let max_output_height = max_output_lines.map(|lines| text_line_height * lines as f32);
+            let hidden_lines = max_output_lines.map_or(0, |lines| {
+                execution_view
+                    .read(cx)
+                    .hidden_line_count(lines as usize, cx)
+            });
+            let colors = OutputAreaColors::new(cx.theme().colors());
+            let background = colors.background;
+
+            // 2px is a magic number to nudge the button just a bit closer to
+            // the line number start
+            let close_button_right = gutter.full_width() / 2.0 - text_line_height / 2.0 - px(2.);
+
+            let collapse_button = has_outputs.then(|| {
+                let execution_view = execution_view.clone();
+                h_flex()
+                    .flex_none()
+                    .items_center()
+                    .justify_center()
+                    .absolute()
+                    .top(text_line_height / 2.)
+                    .right(close_button_right + text_line_height)
+                    .w(text_line_height)
+                    .h(text_line_height)
+                    .child(
+                        IconButton::new(
+                            "toggle_output_area_collapsed",
+                            if is_collapsed {
+                                IconName::ChevronRight
+                            } else {
+                                IconName::ChevronDown
+                            },
+                        )
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Custom(colors.icon))
+                        .size(ButtonSize::Compact)
+                        .shape(IconButtonShape::Square)
+                        .tooltip(move |cx| {
+                            let label = if is_collapsed {
+                                t!("repl.expand_output_area")
+                            } else {
+                                t!("repl.collapse_output_area")
+                            };
+                            Tooltip::text(label, cx)
+                        })
+                        .on_click(move |_, cx| {
+                            execution_view.update(cx, |execution_view, cx| {
+                                execution_view.toggle_collapsed(cx)
+                            })
+                        }),
+                    )
+            });
 
             let close_button = h_flex()
                 .flex_none()
@@ -139,20 +540,16 @@ impl EditorBlock {
                 .justify_center()
                 .absolute()
                 .top(text_line_height / 2.)
-                .right(
-                    // 2px is a magic number to nudge the button just a bit closer to
-                    // the line number start
-                    gutter.full_width() / 2.0 - text_line_height / 2.0 - px(2.),
-                )
+                .right(close_button_right)
                 .w(text_line_height)
                 .h(text_line_height)
                 .child(
                     IconButton::new("close_output_area", IconName::Close)
                         .icon_size(IconSize::Small)
-                        .icon_color(Color::Muted)
+                        .icon_color(Color::Custom(colors.icon))
                         .size(ButtonSize::Compact)
                         .shape(IconButtonShape::Square)
-                        .tooltip(|cx| Tooltip::text("Close output area", cx))
+                        .tooltip(|cx| Tooltip::text(t!("repl.close_output_area"), cx))
                         .on_click(move |_, cx| {
                             if let BlockId::Custom(block_id) = block_id {
                                 (on_close)(block_id, cx)
@@ -168,22 +565,44 @@ impl EditorBlock {
                 .min_h(text_line_height)
                 .w_full()
                 .border_y_1()
-                .border_color(cx.theme().colors().border)
-                .bg(cx.theme().colors().background)
+                .border_color(colors.border)
+                .bg(background)
                 .child(
                     div()
                         .relative()
                         .w(gutter.full_width())
                         .h(text_line_height * 2)
+                        .children(collapse_button)
                         .child(close_button),
                 )
                 .child(
-                    div()
+                    v_flex()
                         .flex_1()
-                        .size_full()
-                        .py(text_line_height / 2.)
                         .mr(gutter.width)
-                        .child(execution_view),
+                        .child(
+                            div()
+                                .relative()
+                                .child(
+                                    div()
+                                        .id("output-area")
+                                        .size_full()
+                                        .py(text_line_height / 2.)
+                                        .when_some(max_output_height, |div, max_output_height| {
+                                            div.max_h(max_output_height).overflow_y_scroll()
+                                        })
+                                        .child(execution_view.clone()),
+                                )
+                                .when(hidden_lines > 0, |div| {
+                                    div.child(overflow_fade(background, text_line_height * 2.))
+                                }),
+                        )
+                        .when(hidden_lines > 0, |div| {
+                            div.child(overflow_buttons(
+                                execution_view,
+                                hidden_lines,
+                                text_line_height,
+                            ))
+                        }),
                 )
                 .into_any_element()
         })
@@ -198,14 +617,61 @@ impl Session {
         kernel_specification: KernelSpecification,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        let subscription = match editor.upgrade() {
+        let mut session = Self::without_kernel(editor, fs, telemetry, kernel_specification, cx);
+        if !ReplSettings::get_global(cx).lazy_start {
+            session.start_kernel(cx);
+        }
+        session
+    }
+
+    /// Creates a session attached to an already running `kernel`, such as a
+    /// [`crate::test::FakeKernel`], instead of starting one.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn test(
+        editor: WeakView<Editor>,
+        fs: Arc<dyn Fs>,
+        telemetry: Arc<Telemetry>,
+        kernel_specification: KernelSpecification,
+        kernel: Box<dyn crate::kernels::RunningKernel>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut session = Self::without_kernel(editor, fs, telemetry, kernel_specification, cx);
+        session.kernel = Kernel::RunningKernel(kernel);
+        session
+    }
+
+    fn without_kernel(
+        editor: WeakView<Editor>,
+        fs: Arc<dyn Fs>,
+        telemetry: Arc<Telemetry>,
+        kernel_specification: KernelSpecification,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let subscriptions = match editor.upgrade() {
             Some(editor) => {
                 let buffer = editor.read(cx).buffer().clone();
-                cx.subscribe(&buffer, Self::on_buffer_event)
+                let editor_id = editor.entity_id();
+                vec![
+                    cx.subscribe(&buffer, Self::on_buffer_event),
+                    // Don't leave the kernel running once its editor is closed.
+                    cx.observe_release(&editor, move |session, _editor: &mut Editor, cx| {
+                        session.shutdown(cx);
+                        ReplStore::global(cx).update(cx, |store, cx| {
+                            store.remove_session(editor_id, cx);
+                        });
+                    }),
+                ]
             }
-            None => Subscription::new(|| {}),
+            None => Vec::new(),
         };
 
+        let buffer_edits = editor.upgrade().map(|editor| {
+            editor
+                .read(cx)
+                .buffer()
+                .update(cx, |buffer, _| buffer.subscribe())
+        });
+
         let editor_handle = editor.clone();
 
         editor
@@ -214,27 +680,47 @@ impl Session {
             })
             .ok();
 
-        let mut session = Self {
+        Self {
             fs,
             editor,
-            kernel: Kernel::StartingKernel(Task::ready(()).shared()),
+            kernel: Kernel::NotStarted,
             blocks: HashMap::default(),
             kernel_specification,
-            _buffer_subscription: subscription,
+            working_directory: None,
+            pending_interrupt: None,
+            queued_executions: Vec::new(),
+            pending_requests: Vec::new(),
+            block_priority: DEFAULT_OUTPUT_BLOCK_PRIORITY,
+            pending_display_updates: PendingDisplayUpdates::default(),
+            code_range_offsets: CodeRangeOffsets::default(),
+            block_removals: PendingBlockRemovals::default(),
+            detached_blocks: Vec::new(),
+            execution_waiters: HashMap::default(),
+            buffer_edits,
+            _subscriptions: subscriptions,
             telemetry,
-        };
-
-        session.start_kernel(cx);
-        session
+        }
     }
 
     fn start_kernel(&mut self, cx: &mut ViewContext<Self>) {
         let kernel_language = self.kernel_specification.language();
         let entity_id = self.editor.entity_id();
+        // The session's own working directory takes precedence. Otherwise,
+        // buffers that aren't backed by a file, like scratchpads, run in the
+        // root of the project.
         let working_directory = self
-            .editor
-            .upgrade()
-            .and_then(|editor| editor.read(cx).working_directory(cx))
+            .working_directory
+            .clone()
+            .or_else(|| {
+                let editor = self.editor.upgrade()?;
+                let editor = editor.read(cx);
+                editor.working_directory(cx).or_else(|| {
+                    let project = editor.workspace()?.read(cx).project().clone();
+                    let worktree = project.read(cx).visible_worktrees(cx).next()?;
+                    let abs_path = worktree.read(cx).abs_path().to_path_buf();
+                    Some(abs_path)
+                })
+            })
             .unwrap_or_else(temp_dir);
 
         self.telemetry.report_repl_event(
@@ -276,7 +762,7 @@ impl Session {
                     }
                     Err(err) => {
                         this.update(&mut cx, |session, cx| {
-                            session.kernel_errored(err.to_string(), cx);
+                            session.kernel_errored(format!("{err:#}"), cx);
                         })
                         .ok();
                     }
@@ -288,8 +774,31 @@ impl Session {
         cx.notify();
     }
 
+    /// Sets the directory to start the kernel in, or clears the override with
+    /// `None`. Takes effect the next time the kernel starts.
+    pub fn set_working_directory(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.working_directory = working_directory;
+        cx.notify();
+    }
+
+    /// Returns the directory set with [`Session::set_working_directory`], if any.
+    pub fn working_directory_override(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    /// Sets the priority of output blocks inserted from now on, relative to
+    /// other blocks placed below the same row. Lower priorities render first.
+    pub fn set_block_priority(&mut self, priority: usize) {
+        self.block_priority = priority;
+    }
+
     pub fn kernel_errored(&mut self, error_message: String, cx: &mut ViewContext<Self>) {
         self.kernel(Kernel::ErroredLaunch(error_message.clone()), cx);
+        self.resolve_execution_waiters(ExecutionStatus::KernelErrored(error_message.clone()));
 
         self.blocks.values().for_each(|block| {
             block.execution_view.update(cx, |execution_view, cx| {
@@ -308,6 +817,301 @@ impl Session {
         });
     }
 
+    /// Marks a remote kernel as reconnecting after its connection dropped, or as
+    /// connected again. Executions still waiting on the kernel show the
+    /// reconnection in their output blocks. Their replies were lost with the
+    /// connection, so once it's back they're marked as errored.
+    pub fn kernel_reconnecting(&mut self, reconnecting: bool, cx: &mut ViewContext<Self>) {
+        if self.kernel.is_reconnecting() == reconnecting {
+            return;
+        }
+        self.kernel.set_reconnecting(reconnecting);
+
+        self.telemetry.report_repl_event(
+            self.kernel_specification.language().into(),
+            KernelStatus::from(&self.kernel).to_string(),
+            cx.entity_id().to_string(),
+        );
+
+        if reconnecting {
+            for block in self.blocks.values() {
+                block.execution_view.update(cx, |execution_view, cx| {
+                    if let ExecutionStatus::Queued | ExecutionStatus::Executing =
+                        execution_view.status
+                    {
+                        execution_view.status = ExecutionStatus::Reconnecting;
+                        cx.notify();
+                    }
+                });
+            }
+        } else {
+            self.fail_executions(
+                ExecutionStatus::KernelErrored(t!("repl.execution.connection_lost").to_string()),
+                |status| matches!(status, ExecutionStatus::Reconnecting),
+                cx,
+            );
+        }
+
+        cx.notify();
+    }
+
+    /// Gives the executions whose status matches `is_affected` the final
+    /// `status`, and resolves their waiters with it.
+    fn fail_executions(
+        &mut self,
+        status: ExecutionStatus,
+        is_affected: impl Fn(&ExecutionStatus) -> bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut failed_executions = Vec::new();
+        for (key, block) in &self.blocks {
+            block.execution_view.update(cx, |execution_view, cx| {
+                if is_affected(&execution_view.status) {
+                    execution_view.status = status.clone();
+                    failed_executions.push(key.clone());
+                    cx.notify();
+                }
+            });
+        }
+        for key in failed_executions {
+            if let Some(waiter) = self.execution_waiters.remove(&key) {
+                waiter.send(Ok(status.clone())).ok();
+            }
+        }
+    }
+
+    /// Returns the code range and output text of the execution shown in the
+    /// editor's block `block_id`, if it's one of this session's blocks.
+    pub fn output_block(
+        &self,
+        block_id: CustomBlockId,
+        cx: &WindowContext,
+    ) -> Option<(Range<usize>, String)> {
+        let editor = self.editor.upgrade()?;
+        let block = self
+            .blocks
+            .values()
+            .find(|block| block.block_id == Some(block_id))?;
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        Some((
+            block.code_range.to_offset(&snapshot),
+            block.execution_view.read(cx).text_content(cx),
+        ))
+    }
+
+    /// Returns every cell of the editor's code, in document order, along with
+    /// the status of the execution shown below it. Cells that were run in
+    /// parts, like line by line, report their longest execution.
+    pub fn cells(&self, cx: &AppContext) -> Vec<CellInfo> {
+        let Some(editor) = self.editor.upgrade() else {
+            return Vec::new();
+        };
+        let multibuffer = editor.read(cx).buffer().clone();
+        let Some(buffer) = multibuffer.read(cx).as_singleton() else {
+            return Vec::new();
+        };
+
+        let buffer = buffer.read(cx).snapshot();
+        let separator = cell_separator(&buffer, cx);
+        let (cell_ranges, _) =
+            runnable_ranges(&buffer, Point::zero()..buffer.max_point(), &separator);
+
+        let snapshot = multibuffer.read(cx).snapshot(cx);
+        let blocks = self
+            .blocks
+            .values()
+            .map(|block| (block.code_range.to_offset(&snapshot), &block.execution_view))
+            .collect::<Vec<_>>();
+
+        cell_ranges
+            .into_iter()
+            .map(|range| {
+                let range =
+                    snapshot.point_to_offset(range.start)..snapshot.point_to_offset(range.end);
+                let execution_view = blocks
+                    .iter()
+                    .filter(|(code_range, _)| {
+                        range.start <= code_range.start && code_range.end <= range.end
+                    })
+                    .max_by_key(|(code_range, _)| code_range.len())
+                    .map(|(_, execution_view)| execution_view.read(cx));
+                CellInfo {
+                    range,
+                    status: execution_view.map(|execution_view| execution_view.status.clone()),
+                    execution_count: execution_view
+                        .and_then(|execution_view| execution_view.execution_count),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the outputs of every execution, in document order, for showing
+    /// them outside the editor.
+    pub(crate) fn outputs(&self, cx: &AppContext) -> Vec<CellOutput> {
+        let Some(editor) = self.editor.upgrade() else {
+            return Vec::new();
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let mut outputs = self
+            .blocks
+            .iter()
+            .map(|(key, block)| CellOutput {
+                key: key.clone(),
+                range: block.code_range.to_offset(&snapshot),
+                execution_view: block.execution_view.clone(),
+            })
+            .collect::<Vec<_>>();
+        outputs.sort_by_key(|output| output.range.start);
+        outputs
+    }
+
+    /// Collapses or expands the outputs of every execution at once. Whether
+    /// each one's outputs are shown in full is kept.
+    pub fn set_all_outputs_collapsed(&mut self, collapsed: bool, cx: &mut ViewContext<Self>) {
+        let execution_views = self
+            .blocks
+            .values()
+            .map(|block| &block.execution_view)
+            .chain(
+                self.detached_blocks
+                    .iter()
+                    .map(|(_, detached)| &detached.execution_view),
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        for execution_view in execution_views {
+            execution_view.update(cx, |execution_view, _| {
+                execution_view.set_collapsed(collapsed)
+            });
+        }
+        cx.notify();
+    }
+
+    /// Removes the outputs of the executions whose code overlaps `range`.
+    pub fn clear_outputs_in_range(&mut self, range: Range<Anchor>, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let range = range.to_offset(&snapshot);
+
+        let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
+        self.blocks.retain(|_, block| {
+            let code_range = block.code_range.to_offset(&snapshot);
+            if code_range.start <= range.end && range.start <= code_range.end {
+                blocks_to_remove.extend(block.block_id);
+                false
+            } else {
+                true
+            }
+        });
+
+        if !blocks_to_remove.is_empty() {
+            self.block_removals
+                .queue(blocks_to_remove, &self.editor, cx);
+        }
+        cx.notify();
+    }
+
+    /// Removes the outputs stored under `key`, along with their block.
+    pub(crate) fn close_output(&mut self, key: &str, cx: &mut ViewContext<Self>) {
+        if let Some(block) = self.blocks.remove(key) {
+            self.block_removals.queue(block.block_id, &self.editor, cx);
+            cx.notify();
+        }
+    }
+
+    /// Selects the code of the next execution with an output block after the
+    /// newest selection, in document order.
+    pub fn select_next_output(&mut self, cx: &mut ViewContext<Self>) {
+        self.select_adjacent_output(true, cx);
+    }
+
+    /// Selects the code of the previous execution with an output block before
+    /// the newest selection, in document order.
+    pub fn select_previous_output(&mut self, cx: &mut ViewContext<Self>) {
+        self.select_adjacent_output(false, cx);
+    }
+
+    fn select_adjacent_output(&mut self, next: bool, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        let cursor = editor.update(cx, |editor, cx| editor.selections.newest::<usize>(cx).start);
+
+        let mut code_ranges = self
+            .blocks
+            .values()
+            .map(|block| block.code_range.to_offset(&snapshot))
+            .collect::<Vec<_>>();
+        code_ranges.sort_by_key(|range| (range.start, range.end));
+
+        let target = if next {
+            code_ranges.into_iter().find(|range| range.start > cursor)
+        } else {
+            code_ranges
+                .into_iter()
+                .rev()
+                .find(|range| range.start < cursor)
+        };
+        let Some(target) = target else {
+            return;
+        };
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([target]);
+            });
+        });
+    }
+
+    /// Returns the status of the execution whose code contains `anchor`, or
+    /// `None` if that code has no output block.
+    pub fn has_output_at(&self, anchor: Anchor, cx: &WindowContext) -> Option<ExecutionStatus> {
+        self.execution_view_at(anchor, cx)
+            .map(|execution_view| execution_view.read(cx).status.clone())
+    }
+
+    /// Opens the output of the execution whose code contains `anchor` in a
+    /// new read-only tab.
+    pub fn open_output_in_new_tab(&self, anchor: Anchor, cx: &mut WindowContext) {
+        if let Some(execution_view) = self.execution_view_at(anchor, cx).cloned() {
+            execution_view.update(cx, |execution_view, cx| execution_view.open_in_new_tab(cx));
+        }
+    }
+
+    /// Returns the code of the execution whose code contains `anchor` as a
+    /// fenced code block, followed by its outputs, as markdown.
+    pub fn cell_markdown(&self, anchor: Anchor, cx: &WindowContext) -> Option<String> {
+        let block = self.block_at(anchor, cx)?;
+        let language = self.kernel_specification.language().to_lowercase();
+        let source = fenced_code_block(&language, &block.code);
+        let outputs = block.execution_view.read(cx).markdown_content(cx);
+        if outputs.is_empty() {
+            Some(source)
+        } else {
+            Some(format!("{source}\n\n{outputs}"))
+        }
+    }
+
+    fn execution_view_at(
+        &self,
+        anchor: Anchor,
+        cx: &WindowContext,
+    ) -> Option<&View<ExecutionView>> {
+        self.block_at(anchor, cx).map(|block| &block.execution_view)
+    }
+
+    fn block_at(&self, anchor: Anchor, cx: &WindowContext) -> Option<&EditorBlock> {
+        let editor = self.editor.upgrade()?;
+        let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+        self.blocks.values().find(|block| {
+            block.code_range.start.cmp(&anchor, &snapshot).is_le()
+                && block.code_range.end.cmp(&anchor, &snapshot).is_ge()
+        })
+    }
+
     fn on_buffer_event(
         &mut self,
         buffer: Model<MultiBuffer>,
@@ -319,21 +1123,91 @@ impl Session {
 
             let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
 
-            self.blocks.retain(|_id, block| {
+            let detached_blocks = &mut self.detached_blocks;
+            self.blocks.retain(|key, block| {
                 if block.invalidation_anchor.is_valid(&snapshot) {
                     true
                 } else {
-                    blocks_to_remove.insert(block.block_id);
+                    blocks_to_remove.extend(block.block_id);
+                    detached_blocks.push((
+                        key.clone(),
+                        DetachedBlock {
+                            code: block.code.clone(),
+                            execution_view: block.execution_view.clone(),
+                        },
+                    ));
                     false
                 }
             });
+            let excess = detached_blocks.len().saturating_sub(MAX_DETACHED_BLOCKS);
+            detached_blocks.drain(..excess);
 
             if !blocks_to_remove.is_empty() {
-                self.editor
-                    .update(cx, |editor, cx| {
-                        editor.remove_blocks(blocks_to_remove, None, cx);
-                    })
-                    .ok();
+                self.block_removals
+                    .queue(blocks_to_remove, &self.editor, cx);
+                cx.notify();
+            }
+
+            let edits = self
+                .buffer_edits
+                .as_ref()
+                .map(|buffer_edits| buffer_edits.consume().into_inner())
+                .unwrap_or_default();
+            self.reattach_moved_blocks(&snapshot, &edits, cx);
+        }
+    }
+
+    /// Moves the outputs of detached blocks below their code, if `edits` made
+    /// it reappear in the buffer since it was deleted.
+    fn reattach_moved_blocks(
+        &mut self,
+        snapshot: &MultiBufferSnapshot,
+        edits: &[Edit<usize>],
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.detached_blocks.is_empty() || edits.is_empty() {
+            return;
+        }
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        let mut code_ranges = self
+            .blocks
+            .values()
+            .map(|block| block.code_range.to_offset(snapshot))
+            .collect::<Vec<_>>();
+
+        let mut ix = 0;
+        while ix < self.detached_blocks.len() {
+            let code = &self.detached_blocks[ix].1.code;
+            let Some(range) = find_moved_code_near_edits(snapshot, code, edits) else {
+                ix += 1;
+                continue;
+            };
+            if code_ranges
+                .iter()
+                .any(|other| range.start < other.end && other.start < range.end)
+            {
+                ix += 1;
+                continue;
+            }
+
+            let (key, detached) = self.detached_blocks.remove(ix);
+            let code_range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            let on_close = self.close_block_fn(key.clone(), cx);
+            if let Some(block) = EditorBlock::attach(
+                editor.clone(),
+                code_range,
+                detached.execution_view,
+                self.block_priority,
+                on_close,
+                cx,
+            )
+            .log_err()
+            {
+                code_ranges.push(range);
+                self.blocks.insert(key, block);
                 cx.notify();
             }
         }
@@ -348,16 +1222,18 @@ impl Session {
     }
 
     pub fn clear_outputs(&mut self, cx: &mut ViewContext<Self>) {
-        let blocks_to_remove: HashSet<CustomBlockId> =
-            self.blocks.values().map(|block| block.block_id).collect();
-
-        self.editor
-            .update(cx, |editor, cx| {
-                editor.remove_blocks(blocks_to_remove, None, cx);
-            })
-            .ok();
+        self.block_removals.queue(
+            self.blocks.values().filter_map(|block| block.block_id),
+            &self.editor,
+            cx,
+        );
 
         self.blocks.clear();
+        self.detached_blocks.clear();
+        self.pending_requests.clear();
+        self.pending_display_updates.clear();
+        self.execution_waiters.clear();
+        cx.notify();
     }
 
     pub fn execute(
@@ -368,12 +1244,66 @@ impl Session {
         move_down: bool,
         cx: &mut ViewContext<Self>,
     ) {
-        let Some(editor) = self.editor.upgrade() else {
-            return;
-        };
+        self.send_execute_request(code, anchor_range, next_cell, move_down, cx);
+    }
 
-        if code.is_empty() {
-            return;
+    /// Executes `code` like [`Session::execute`], resolving with the execution's
+    /// status once it finishes, or once the kernel fails, restarts or shuts
+    /// down. Fails if the code raises an error, or if the session's outputs
+    /// are cleared before the execution finishes.
+    pub fn execute_and_wait(
+        &mut self,
+        code: String,
+        anchor_range: Range<Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<ExecutionStatus>> {
+        let Some(message_id) = self.send_execute_request(code, anchor_range, None, false, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!("the code could not be executed")));
+        };
+
+        match &self.kernel {
+            Kernel::ErroredLaunch(error) => {
+                return Task::ready(Ok(ExecutionStatus::KernelErrored(error.clone())))
+            }
+            Kernel::ShuttingDown | Kernel::Shutdown => {
+                return Task::ready(Ok(ExecutionStatus::Shutdown))
+            }
+            _ => {}
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.execution_waiters.insert(message_id, tx);
+        cx.background_executor().spawn(async move {
+            rx.await
+                .map_err(|_| anyhow::anyhow!("the execution's outputs were cleared"))?
+        })
+    }
+
+    /// Resolves every pending [`Session::execute_and_wait`] with `status`.
+    fn resolve_execution_waiters(&mut self, status: ExecutionStatus) {
+        for (_, waiter) in self.execution_waiters.drain() {
+            waiter.send(Ok(status.clone())).ok();
+        }
+    }
+
+    /// Sends an execute request for `code`, returning its message id.
+    fn send_execute_request(
+        &mut self,
+        code: String,
+        anchor_range: Range<Anchor>,
+        next_cell: Option<Anchor>,
+        move_down: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<String> {
+        let editor = self.editor.upgrade()?;
+
+        if code.is_empty() {
+            return None;
+        }
+
+        if let Kernel::NotStarted = self.kernel {
+            self.start_kernel(cx);
         }
 
         let execute_request = ExecuteRequest {
@@ -383,61 +1313,19 @@ impl Session {
 
         let message: JupyterMessage = execute_request.into();
 
-        let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
-
-        let buffer = editor.read(cx).buffer().read(cx).snapshot(cx);
-
-        self.blocks.retain(|_key, block| {
-            if anchor_range.overlaps(&block.code_range, &buffer) {
-                blocks_to_remove.insert(block.block_id);
-                false
-            } else {
-                true
-            }
-        });
-
-        self.editor
-            .update(cx, |editor, cx| {
-                editor.remove_blocks(blocks_to_remove, None, cx);
-            })
-            .ok();
-
+        // Requests sent while a remote kernel is reconnecting are delivered
+        // once the connection is back, so they're queued like any other.
         let status = match &self.kernel {
             Kernel::Restarting => ExecutionStatus::Restarting,
             Kernel::RunningKernel(_) => ExecutionStatus::Queued,
-            Kernel::StartingKernel(_) => ExecutionStatus::ConnectingToKernel,
+            Kernel::NotStarted | Kernel::StartingKernel(_) => ExecutionStatus::ConnectingToKernel,
             Kernel::ErroredLaunch(error) => ExecutionStatus::KernelErrored(error.clone()),
             Kernel::ShuttingDown => ExecutionStatus::ShuttingDown,
             Kernel::Shutdown => ExecutionStatus::Shutdown,
         };
 
-        let parent_message_id = message.header.msg_id.clone();
-        let session_view = cx.view().downgrade();
-        let weak_editor = self.editor.clone();
-
-        let on_close: CloseBlockFn =
-            Arc::new(move |block_id: CustomBlockId, cx: &mut WindowContext| {
-                if let Some(session) = session_view.upgrade() {
-                    session.update(cx, |session, cx| {
-                        session.blocks.remove(&parent_message_id);
-                        cx.notify();
-                    });
-                }
-
-                if let Some(editor) = weak_editor.upgrade() {
-                    editor.update(cx, |editor, cx| {
-                        let mut block_ids = HashSet::default();
-                        block_ids.insert(block_id);
-                        editor.remove_blocks(block_ids, None, cx);
-                    });
-                }
-            });
-
-        let Ok(editor_block) =
-            EditorBlock::new(self.editor.clone(), anchor_range, status, on_close, cx)
-        else {
-            return;
-        };
+        let message_id = message.header.msg_id.clone();
+        let editor_block = self.insert_block(message_id.clone(), anchor_range, status, cx)?;
 
         let new_cursor_pos = if let Some(next_cursor) = next_cell {
             next_cursor
@@ -445,26 +1333,13 @@ impl Session {
             editor_block.invalidation_anchor
         };
 
-        self.blocks
-            .insert(message.header.msg_id.clone(), editor_block);
-
         match &self.kernel {
             Kernel::RunningKernel(_) => {
                 self.send(message, cx).ok();
             }
-            Kernel::StartingKernel(task) => {
-                // Queue up the execution as a task to run after the kernel starts
-                let task = task.clone();
-                let message = message.clone();
-
-                cx.spawn(|this, mut cx| async move {
-                    task.await;
-                    this.update(&mut cx, |session, cx| {
-                        session.send(message, cx).ok();
-                    })
-                    .ok();
-                })
-                .detach();
+            Kernel::StartingKernel(_) | Kernel::Restarting => {
+                // Queue up the execution to send once the kernel starts
+                self.pending_requests.push(message);
             }
             _ => {}
         }
@@ -476,6 +1351,114 @@ impl Session {
                 });
             });
         }
+
+        Some(message_id)
+    }
+
+    /// Replaces the output blocks overlapping `anchor_range` with a new one,
+    /// stored under `key`.
+    fn insert_block(
+        &mut self,
+        key: String,
+        anchor_range: Range<Anchor>,
+        status: ExecutionStatus,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<&EditorBlock> {
+        let editor = self.editor.upgrade()?;
+
+        let mut blocks_to_remove: HashSet<CustomBlockId> = HashSet::default();
+
+        let buffer = editor.read(cx).buffer().read(cx).read(cx);
+        let new_range = anchor_range.to_offset(&buffer);
+        let code_range_offsets = &mut self.code_range_offsets;
+        self.blocks.retain(|key, block| {
+            let range = code_range_offsets.range(key, &block.code_range, &buffer);
+            if new_range.end >= range.start && new_range.start <= range.end {
+                blocks_to_remove.extend(block.block_id);
+                false
+            } else {
+                true
+            }
+        });
+        drop(buffer);
+        code_range_offsets
+            .ranges
+            .retain(|key, _| self.blocks.contains_key(key));
+
+        self.block_removals
+            .queue(blocks_to_remove, &self.editor, cx);
+
+        let on_close = self.close_block_fn(key.clone(), cx);
+        let editor_block = EditorBlock::new(
+            self.editor.clone(),
+            anchor_range,
+            status,
+            self.block_priority,
+            on_close,
+            cx,
+        )
+        .ok()?;
+
+        Some(self.blocks.entry(key).or_insert(editor_block))
+    }
+
+    /// Returns the callback that closes the output block stored under `key`.
+    fn close_block_fn(&self, key: String, cx: &mut ViewContext<Self>) -> CloseBlockFn {
+        let session_view = cx.view().downgrade();
+        let weak_editor = self.editor.clone();
+
+        Arc::new(move |block_id: CustomBlockId, cx: &mut WindowContext| {
+            if let Some(session) = session_view.upgrade() {
+                session.update(cx, |session, cx| {
+                    session.blocks.remove(&key);
+                    cx.notify();
+                });
+            }
+
+            if let Some(editor) = weak_editor.upgrade() {
+                editor.update(cx, |editor, cx| {
+                    let mut block_ids = HashSet::default();
+                    block_ids.insert(block_id);
+                    editor.remove_blocks(block_ids, None, cx);
+                });
+            }
+        })
+    }
+
+    /// Renders a markdown cell beneath its source, in place of kernel output.
+    pub fn render_markdown(
+        &mut self,
+        markdown: String,
+        anchor_range: Range<Anchor>,
+        next_cell: Option<Anchor>,
+        move_down: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+
+        let key = uuid::Uuid::new_v4().to_string();
+        let Some(editor_block) =
+            self.insert_block(key, anchor_range, ExecutionStatus::Finished, cx)
+        else {
+            return;
+        };
+
+        editor_block
+            .execution_view
+            .update(cx, |execution_view, cx| {
+                execution_view.set_markdown(markdown, cx);
+            });
+
+        let new_cursor_pos = next_cell.unwrap_or(editor_block.invalidation_anchor);
+        if move_down {
+            editor.update(cx, move |editor, cx| {
+                editor.change_selections(Some(Autoscroll::top_relative(8)), cx, |selections| {
+                    selections.select_ranges([new_cursor_pos..new_cursor_pos]);
+                });
+            });
+        }
     }
 
     pub fn route(&mut self, message: &JupyterMessage, cx: &mut ViewContext<Self>) {
@@ -487,6 +1470,9 @@ impl Session {
         match &message.content {
             JupyterMessageContent::Status(status) => {
                 self.kernel.set_execution_state(&status.execution_state);
+                if matches!(status.execution_state, ExecutionState::Idle) {
+                    self.pending_interrupt = None;
+                }
 
                 self.telemetry.report_repl_event(
                     self.kernel_specification.language().into(),
@@ -507,28 +1493,160 @@ impl Session {
                     return;
                 };
 
-                self.blocks.iter_mut().for_each(|(_, block)| {
-                    block.execution_view.update(cx, |execution_view, cx| {
-                        execution_view.update_display_data(&update.data, &display_id, cx);
+                let mut updated = false;
+                for block in self.blocks.values() {
+                    updated |= block.execution_view.update(cx, |execution_view, cx| {
+                        execution_view.update_display_data(&update.data, &display_id, cx)
                     });
-                });
+                }
+                if !updated {
+                    self.pending_display_updates
+                        .defer(display_id, update.data.clone());
+                }
                 return;
             }
             _ => {}
         }
 
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
+            let had_outputs = !block.execution_view.read(cx).outputs.is_empty();
             block.handle_message(message, cx);
+
+            if !had_outputs
+                && block.block_id.is_some()
+                && !block.execution_view.read(cx).outputs.is_empty()
+                && ReplSettings::get_global(cx).follow_output
+            {
+                Self::follow_output(&self.editor, block.code_range.end, cx);
+            }
+
+            if let Some((display_id, data)) = self.pending_display_updates.take(&message.content) {
+                block.execution_view.update(cx, |execution_view, cx| {
+                    execution_view.update_display_data(&data, &display_id, cx);
+                });
+            }
+
+            if let JupyterMessageContent::Status(status) = &message.content {
+                match status.execution_state {
+                    ExecutionState::Busy => {
+                        block.execution_started.get_or_insert_with(Instant::now);
+                    }
+                    ExecutionState::Idle => {
+                        let anchor_range = block.code_range.clone();
+                        let success = !block.execution_view.read(cx).has_error();
+                        let duration = block
+                            .execution_started
+                            .take()
+                            .map_or(Duration::ZERO, |started| started.elapsed());
+
+                        self.telemetry.report_repl_execution_event(
+                            self.kernel_specification.language().into(),
+                            cx.entity_id().to_string(),
+                            success,
+                            duration,
+                        );
+
+                        cx.emit(SessionEvent::ExecutionFinished {
+                            anchor_range,
+                            success,
+                        });
+                    }
+                }
+            }
+        } else if let Some((_, detached)) = self
+            .detached_blocks
+            .iter()
+            .find(|(key, _)| key == parent_message_id)
+        {
+            // Keep collecting outputs while the code is being moved.
+            detached.execution_view.update(cx, |execution_view, cx| {
+                execution_view.push_message(&message.content, cx);
+            });
+        }
+
+        if let JupyterMessageContent::Status(status) = &message.content {
+            if matches!(status.execution_state, ExecutionState::Idle) {
+                if let Some(waiter) = self.execution_waiters.remove(parent_message_id) {
+                    let execution_view = self
+                        .blocks
+                        .get(parent_message_id)
+                        .map(|block| &block.execution_view)
+                        .or_else(|| {
+                            self.detached_blocks
+                                .iter()
+                                .find(|(key, _)| key == parent_message_id)
+                                .map(|(_, detached)| &detached.execution_view)
+                        });
+                    let result = match execution_view
+                        .and_then(|execution_view| execution_view.read(cx).error())
+                    {
+                        Some(error) => Err(anyhow::anyhow!(error)),
+                        None => Ok(ExecutionStatus::Finished),
+                    };
+                    waiter.send(result).ok();
+                }
+            }
         }
     }
 
+    /// Scrolls the editor so that the last line of an execution's code is at
+    /// the top of the viewport, leaving room for its output below. Does nothing
+    /// if that line is out of view, as the user has scrolled away from it, or
+    /// in the upper half of the viewport, where the output is already in view.
+    fn follow_output(editor: &WeakView<Editor>, code_end: Anchor, cx: &mut WindowContext) {
+        editor
+            .update(cx, |editor, cx| {
+                let Some(visible_line_count) = editor.visible_line_count() else {
+                    return;
+                };
+                let snapshot = editor.snapshot(cx);
+                let scroll_position = snapshot.scroll_position();
+                let code_end_row = code_end.to_display_point(&snapshot).row().0 as f32;
+
+                let viewport_middle = scroll_position.y + visible_line_count / 2.;
+                let viewport_end = scroll_position.y + visible_line_count;
+                if code_end_row < viewport_middle || code_end_row >= viewport_end {
+                    return;
+                }
+
+                editor.set_scroll_position(point(scroll_position.x, code_end_row), cx);
+            })
+            .ok();
+    }
+
     pub fn interrupt(&mut self, cx: &mut ViewContext<Self>) {
         match &mut self.kernel {
             Kernel::RunningKernel(_kernel) => {
                 self.send(InterruptRequest {}.into(), cx).ok();
+
+                // Some kernels ignore interrupts while stuck in native code, so
+                // restart the kernel if it hasn't gone back to idle in time.
+                let timeout = Duration::from_millis(ReplSettings::get_global(cx).interrupt_timeout);
+                self.pending_interrupt = Some(cx.spawn(|this, mut cx| async move {
+                    cx.background_executor().timer(timeout).await;
+                    this.update(&mut cx, |session, cx| {
+                        if let Kernel::RunningKernel(kernel) = &session.kernel {
+                            if matches!(kernel.execution_state(), ExecutionState::Busy) {
+                                log::warn!("kernel did not respond to interrupt, restarting");
+                                // Keep what the interrupted code printed.
+                                session.restart(false, cx);
+                            }
+                        }
+                    })
+                    .ok();
+                }));
             }
-            Kernel::StartingKernel(_task) => {
-                // NOTE: If we switch to a literal queue instead of chaining on to the task, clear all queued executions
+            Kernel::StartingKernel(_) => {
+                // Nothing has reached the kernel yet, so what would be sent
+                // once it's running is dropped instead.
+                self.pending_requests.clear();
+                self.queued_executions.clear();
+                self.fail_executions(
+                    ExecutionStatus::KernelErrored(t!("repl.execution.interrupted").to_string()),
+                    |status| matches!(status, ExecutionStatus::ConnectingToKernel),
+                    cx,
+                );
+                cx.notify();
             }
             _ => {}
         }
@@ -536,6 +1654,7 @@ impl Session {
 
     pub fn kernel(&mut self, kernel: Kernel, cx: &mut ViewContext<Self>) {
         if let Kernel::Shutdown = kernel {
+            self.resolve_execution_waiters(ExecutionStatus::Shutdown);
             cx.emit(SessionEvent::Shutdown(self.editor.clone()));
         }
 
@@ -549,6 +1668,22 @@ impl Session {
         );
 
         self.kernel = kernel;
+
+        match &self.kernel {
+            Kernel::RunningKernel(_) => {
+                for message in mem::take(&mut self.pending_requests) {
+                    self.send(message, cx).ok();
+                }
+                for (code, anchor_range) in mem::take(&mut self.queued_executions) {
+                    self.execute(code, anchor_range, None, false, cx);
+                }
+            }
+            Kernel::ErroredLaunch(_) | Kernel::Shutdown => {
+                self.pending_requests.clear();
+                self.queued_executions.clear();
+            }
+            _ => {}
+        }
     }
 
     pub fn shutdown(&mut self, cx: &mut ViewContext<Self>) {
@@ -585,8 +1720,80 @@ impl Session {
         cx.notify();
     }
 
-    pub fn restart(&mut self, cx: &mut ViewContext<Self>) {
+    /// Shuts the kernel down like [`Session::shutdown`], but first asks for
+    /// confirmation if code is still running and `repl.confirm_shutdown` is on.
+    pub fn confirm_shutdown(&mut self, cx: &mut ViewContext<Self>) {
+        if !ReplSettings::get_global(cx).confirm_shutdown || !self.has_pending_executions(cx) {
+            self.shutdown(cx);
+            return;
+        }
+
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &t!("repl.shutdown_confirmation.message"),
+            Some(&t!("repl.shutdown_confirmation.detail")),
+            &[
+                &t!("repl.shutdown"),
+                &t!("repl.shutdown_confirmation.cancel"),
+            ],
+        );
+        cx.spawn(|this, mut cx| async move {
+            if answer.await? != 0 {
+                return Ok(());
+            }
+            this.update(&mut cx, |session, cx| session.shutdown(cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Whether the kernel is busy or has executions waiting to run.
+    fn has_pending_executions(&self, cx: &AppContext) -> bool {
+        let is_busy = matches!(
+            &self.kernel,
+            Kernel::RunningKernel(kernel) if matches!(kernel.execution_state(), ExecutionState::Busy)
+        );
+        is_busy
+            || !self.queued_executions.is_empty()
+            || self.blocks.values().any(|block| {
+                matches!(
+                    block.execution_view.read(cx).status,
+                    ExecutionStatus::Queued
+                        | ExecutionStatus::Executing
+                        | ExecutionStatus::ConnectingToKernel
+                        | ExecutionStatus::Reconnecting
+                )
+            })
+    }
+
+    /// Restarts the kernel, clearing the outputs of previous executions if
+    /// `clear_outputs` is set. Otherwise, executions the old kernel hadn't
+    /// finished are marked as failed.
+    pub fn restart(&mut self, clear_outputs: bool, cx: &mut ViewContext<Self>) {
         let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
+        // The old kernel won't finish what it was running.
+        let restarted =
+            ExecutionStatus::KernelErrored(t!("repl.execution.kernel_restarted").to_string());
+        self.resolve_execution_waiters(restarted.clone());
+        if clear_outputs {
+            self.clear_outputs(cx);
+        } else {
+            // Requests waiting for the kernel are dropped with their executions.
+            self.pending_requests.clear();
+            self.fail_executions(
+                restarted,
+                |status| {
+                    matches!(
+                        status,
+                        ExecutionStatus::ConnectingToKernel
+                            | ExecutionStatus::Queued
+                            | ExecutionStatus::Executing
+                            | ExecutionStatus::Restarting
+                            | ExecutionStatus::Reconnecting
+                    )
+                },
+                cx,
+            );
+        }
 
         match kernel {
             Kernel::Restarting => {
@@ -610,75 +1817,1033 @@ impl Session {
                     forced.await.log_err();
 
                     // Start a new kernel
-                    this.update(&mut cx, |session, cx| {
-                        // todo!(): Differentiate between restart and restart+clear-outputs
-                        session.clear_outputs(cx);
-                        session.start_kernel(cx);
-                    })
-                    .ok();
+                    this.update(&mut cx, |session, cx| session.start_kernel(cx))
+                        .ok();
                 })
                 .detach();
             }
             _ => {
-                self.clear_outputs(cx);
                 self.start_kernel(cx);
             }
         }
         cx.notify();
     }
+
+    /// Restarts the kernel and then runs every cell in the editor, in order,
+    /// once the new kernel is running.
+    pub fn restart_and_run_all(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let multibuffer = editor.read(cx).buffer().clone();
+        let Some(buffer) = multibuffer.read(cx).as_singleton() else {
+            return;
+        };
+
+        let buffer = buffer.read(cx).snapshot();
+        let separator = cell_separator(&buffer, cx);
+        let (cell_ranges, _) =
+            runnable_ranges(&buffer, Point::zero()..buffer.max_point(), &separator);
+
+        let snapshot = multibuffer.read(cx).snapshot(cx);
+        self.queued_executions = cell_ranges
+            .into_iter()
+            .map(|range| {
+                let code = snapshot.text_for_range(range.clone()).collect::<String>();
+                let anchor_range =
+                    snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+                (code, anchor_range)
+            })
+            .collect();
+
+        self.restart(true, cx);
+    }
+
+    /// Returns the connection info of the session's kernel, if Zed launched it
+    /// as a local process.
+    pub fn kernel_connection_info(&self) -> Option<&ConnectionInfo> {
+        match &self.kernel {
+            Kernel::RunningKernel(kernel) => kernel.connection_info(),
+            _ => None,
+        }
+    }
+
+    /// The color used to indicate the kernel's current status.
+    pub fn status_color(&self) -> Color {
+        match &self.kernel {
+            Kernel::RunningKernel(kernel) if kernel.is_reconnecting() => Color::Warning,
+            Kernel::RunningKernel(kernel) => match kernel.execution_state() {
+                ExecutionState::Idle => Color::Success,
+                ExecutionState::Busy => Color::Modified,
+            },
+            Kernel::NotStarted => Color::Muted,
+            Kernel::StartingKernel(_) => Color::Modified,
+            Kernel::ErroredLaunch(_) => Color::Error,
+            Kernel::ShuttingDown => Color::Modified,
+            Kernel::Shutdown => Color::Disabled,
+            Kernel::Restarting => Color::Modified,
+        }
+    }
 }
 
 pub enum SessionEvent {
     Shutdown(WeakView<Editor>),
+    /// A cell's execution finished and the kernel went back to idle.
+    ExecutionFinished {
+        /// The code that was executed.
+        anchor_range: Range<Anchor>,
+        /// Whether the execution completed without an error.
+        success: bool,
+    },
 }
 
 impl EventEmitter<SessionEvent> for Session {}
 
+/// Returns the language of a running kernel, for its status. Kernels that
+/// leave it out of their `kernel_info` reply, or haven't replied yet, fall
+/// back to the language of their kernelspec.
+fn kernel_language(
+    kernel_info: Option<&KernelInfoReply>,
+    kernel_specification: &KernelSpecification,
+) -> Option<String> {
+    kernel_info
+        .map(|info| info.language_info.name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            Some(kernel_specification.language().to_string()).filter(|name| !name.is_empty())
+        })
+}
+
 impl Render for Session {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let (status_text, interrupt_button) = match &self.kernel {
+            Kernel::RunningKernel(kernel) if kernel.is_reconnecting() => {
+                (Some(t!("repl.status.reconnecting").to_string()), None)
+            }
             Kernel::RunningKernel(kernel) => (
-                kernel
-                    .kernel_info()
-                    .as_ref()
-                    .map(|info| info.language_info.name.clone()),
+                kernel_language(kernel.kernel_info(), &self.kernel_specification),
                 Some(
-                    Button::new("interrupt", "Interrupt")
+                    Button::new("interrupt", t!("repl.interrupt"))
                         .style(ButtonStyle::Subtle)
                         .on_click(cx.listener(move |session, _, cx| {
                             session.interrupt(cx);
                         })),
                 ),
             ),
-            Kernel::StartingKernel(_) => (Some("Starting".into()), None),
-            Kernel::ErroredLaunch(err) => (Some(format!("Error: {err}")), None),
-            Kernel::ShuttingDown => (Some("Shutting Down".into()), None),
-            Kernel::Shutdown => (Some("Shutdown".into()), None),
-            Kernel::Restarting => (Some("Restarting".into()), None),
+            Kernel::NotStarted => (Some(t!("repl.status.not_started").to_string()), None),
+            Kernel::StartingKernel(_) => (Some(t!("repl.status.starting").to_string()), None),
+            Kernel::ErroredLaunch(err) => {
+                (Some(format!("{}: {err}", t!("repl.status.error"))), None)
+            }
+            Kernel::ShuttingDown => (Some(t!("repl.status.shutting_down").to_string()), None),
+            Kernel::Shutdown => (Some(t!("repl.status.shutdown").to_string()), None),
+            Kernel::Restarting => (Some(t!("repl.status.restarting").to_string()), None),
+        };
+
+        let working_directory = match &self.kernel {
+            Kernel::RunningKernel(kernel) => {
+                Some(kernel.working_directory().to_string_lossy().to_string())
+            }
+            _ => None,
         };
 
         KernelListItem::new(self.kernel_specification.clone())
-            .status_color(match &self.kernel {
-                Kernel::RunningKernel(kernel) => match kernel.execution_state() {
-                    ExecutionState::Idle => Color::Success,
-                    ExecutionState::Busy => Color::Modified,
-                },
-                Kernel::StartingKernel(_) => Color::Modified,
-                Kernel::ErroredLaunch(_) => Color::Error,
-                Kernel::ShuttingDown => Color::Modified,
-                Kernel::Shutdown => Color::Disabled,
-                Kernel::Restarting => Color::Modified,
-            })
+            .status_color(self.status_color())
             .child(Label::new(self.kernel_specification.name()))
             .children(status_text.map(|status_text| Label::new(format!("({status_text})"))))
+            .children(working_directory.map(|working_directory| {
+                Label::new(working_directory)
+                    .color(Color::Muted)
+                    .size(LabelSize::Small)
+            }))
             .button(
-                Button::new("shutdown", "Shutdown")
+                Button::new("clear-outputs", t!("repl.clear_all_outputs"))
+                    .style(ButtonStyle::Subtle)
+                    .disabled(self.blocks.is_empty())
+                    .on_click(cx.listener(move |session, _, cx| {
+                        session.clear_outputs(cx);
+                    })),
+            )
+            .button(
+                Button::new("shutdown", t!("repl.shutdown"))
                     .style(ButtonStyle::Subtle)
                     .disabled(self.kernel.is_shutting_down())
                     .on_click(cx.listener(move |session, _, cx| {
-                        session.shutdown(cx);
+                        session.confirm_shutdown(cx);
                     })),
             )
             .buttons(interrupt_button)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{FakeKernel, FakeSession, ReplEditorTestContext};
+    use editor::{display_map::DisplayRow, test::editor_test_context::EditorTestContext};
+    use gpui::{size, AppContext, TestAppContext};
+    use project::Project;
+    use runtimelib::{
+        DisplayData, ErrorOutput, ExecuteInput, ExecutionCount, MimeType, Stdio, StreamContent,
+        Transient,
+    };
+    use settings::SettingsStore;
+    use theme::ThemeSettings;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    /// The statuses of the session's executions, by the offset range of their
+    /// code.
+    fn execution_statuses(session: &mut FakeSession) -> Vec<(Range<usize>, ExecutionStatus)> {
+        let editor = session.editor.clone();
+        session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let mut statuses = session
+                .blocks
+                .values()
+                .map(|block| {
+                    (
+                        block.code_range.to_offset(&snapshot),
+                        block.execution_view.read(cx).status.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            statuses.sort_by_key(|(range, _)| range.start);
+            statuses
+        })
+    }
+
+    fn stdout(text: &str) -> StreamContent {
+        StreamContent {
+            name: Stdio::Stdout,
+            text: text.to_string(),
+        }
+    }
+
+    fn custom_block_ids(cx: &mut EditorTestContext) -> Vec<CustomBlockId> {
+        cx.update_editor(|editor, cx| {
+            editor
+                .snapshot(cx)
+                .blocks_in_range(DisplayRow(0)..DisplayRow(u32::MAX))
+                .filter_map(|(_, block)| match block.id() {
+                    BlockId::Custom(block_id) => Some(block_id),
+                    _ => None,
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_output_blocks_render_below_diagnostics(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state("ˇprint(1 + 1)\nprint(2 + 2)\n");
+
+        // Insert the output block first, so that it would sort first if block
+        // ids broke the tie.
+        let (output_block, diagnostic_block) = cx.update_editor(|editor, cx| {
+            let anchor = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .anchor_after(Point::new(0, 12));
+            let block = |priority| BlockProperties {
+                placement: BlockPlacement::Below(anchor),
+                height: 1,
+                style: BlockStyle::Fixed,
+                render: Arc::new(|_: &mut BlockContext| div().into_any_element()),
+                priority,
+            };
+            let output_block =
+                editor.insert_blocks([block(DEFAULT_OUTPUT_BLOCK_PRIORITY)], None, cx)[0];
+            let diagnostic_block = editor.insert_blocks([block(0)], None, cx)[0];
+            (output_block, diagnostic_block)
+        });
+
+        assert_eq!(
+            custom_block_ids(&mut cx),
+            vec![diagnostic_block, output_block]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_block_removals_are_batched(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state("ˇone\ntwo\nthree\n");
+        let block_ids = cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let blocks = (0..3).map(|row| BlockProperties {
+                placement: BlockPlacement::Below(snapshot.anchor_after(Point::new(row, 0))),
+                height: 1,
+                style: BlockStyle::Fixed,
+                render: Arc::new(|_: &mut BlockContext| div().into_any_element()),
+                priority: DEFAULT_OUTPUT_BLOCK_PRIORITY,
+            });
+            editor.insert_blocks(blocks, None, cx)
+        });
+
+        let editor = cx.editor.downgrade();
+        let removals = PendingBlockRemovals::default();
+        cx.update(|cx| {
+            for block_id in &block_ids {
+                removals.queue([*block_id], &editor, cx);
+            }
+            // Nothing is removed until the update ends.
+            assert_eq!(removals.0.borrow().len(), 3);
+        });
+
+        assert!(removals.0.borrow().is_empty());
+        assert_eq!(custom_block_ids(&mut cx), Vec::new());
+    }
+
+    fn display_data(display_id: &str, text: &str) -> JupyterMessageContent {
+        JupyterMessageContent::DisplayData(DisplayData {
+            data: MimeBundle {
+                content: vec![MimeType::Plain(text.to_string())],
+            },
+            metadata: Default::default(),
+            transient: Some(Transient {
+                display_id: Some(display_id.to_string()),
+            }),
+        })
+    }
+
+    #[test]
+    fn test_display_updates_that_arrive_before_their_display() {
+        let mut pending = PendingDisplayUpdates::default();
+        let update = |text: &str| MimeBundle {
+            content: vec![MimeType::Plain(text.to_string())],
+        };
+
+        // Two updates arrive before the display they update; only the latest is kept.
+        pending.defer("progress".into(), update("50%"));
+        pending.defer("progress".into(), update("100%"));
+
+        assert!(pending.take(&display_data("other", "0%")).is_none());
+
+        let (display_id, data) = pending
+            .take(&display_data("progress", "0%"))
+            .expect("the pending update should apply to the new display");
+        assert_eq!(display_id, "progress");
+        assert!(matches!(
+            data.content.as_slice(),
+            [MimeType::Plain(text)] if text == "100%"
+        ));
+
+        // Once applied, the update no longer applies to later displays with the same id.
+        assert!(pending.take(&display_data("progress", "0%")).is_none());
+    }
+
+    #[gpui::test]
+    fn test_code_range_offsets_follow_edits(cx: &mut AppContext) {
+        let buffer = MultiBuffer::build_simple("one\ntwo\n", cx);
+        let snapshot = buffer.read(cx).snapshot(cx);
+        let code_range = snapshot.anchor_before(4)..snapshot.anchor_after(7);
+
+        let mut offsets = CodeRangeOffsets::default();
+        assert_eq!(offsets.range("two", &code_range, &snapshot), 4..7);
+
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        let snapshot = buffer.read(cx).snapshot(cx);
+        assert_eq!(offsets.range("two", &code_range, &snapshot), 9..12);
+    }
+
+    #[test]
+    fn test_find_moved_code() {
+        let code = "x = 1\nprint(x)";
+        assert_eq!(
+            find_moved_code("a = 2\nx = 1\nprint(x)\n", code, 6..21),
+            Some(6)
+        );
+        // Matches must start a line.
+        assert_eq!(find_moved_code("ax = 1\nprint(x)\n", code, 0..16), None);
+        // Duplicated code is ambiguous.
+        assert_eq!(
+            find_moved_code("x = 1\nprint(x)\nx = 1\nprint(x)\n", code, 14..15),
+            None
+        );
+        // Code the edit didn't touch wasn't moved there.
+        assert_eq!(
+            find_moved_code("x = 1\nprint(x)\nx = 1\nprint(x)\n", code, 15..30),
+            Some(15)
+        );
+        assert_eq!(
+            find_moved_code("a = 2\nx = 1\nprint(x)\n", code, 0..0),
+            None
+        );
+        assert_eq!(find_moved_code("a = 2\n", code, 0..6), None);
+        assert_eq!(find_moved_code("\n\n", "\n", 0..2), None);
+    }
+
+    #[gpui::test]
+    async fn test_moved_code_keeps_its_outputs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("x = 1\nprint(x)\n\nx = 1\nprint(x)\n", cx).await;
+
+        let request = session.execute(0..14);
+        session.reply(&request, stdout("1"));
+        session.reply_status(&request, ExecutionState::Idle);
+
+        // Cutting the code detaches its outputs. They don't jump to the copy
+        // below, as the edit didn't touch it.
+        session.editor.update(session.cx, |editor, cx| {
+            editor
+                .buffer()
+                .update(cx, |buffer, cx| buffer.edit([(0..15, "")], None, cx))
+        });
+        session.cx.run_until_parked();
+        session.assert_output_blocks(&[]);
+
+        // Pasting it elsewhere brings them back, below the pasted code.
+        session.editor.update(session.cx, |editor, cx| {
+            editor.buffer().update(cx, |buffer, cx| {
+                buffer.edit([(16..16, "x = 1\nprint(x)\n")], None, cx)
+            })
+        });
+        session.cx.run_until_parked();
+        session.assert_output_blocks(&[(16..30, "1")]);
+    }
+
+    #[gpui::test]
+    async fn test_execution_lifecycle(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("print(1 + 1)\n", cx).await;
+
+        let request = session.execute(0..12);
+        let JupyterMessageContent::ExecuteRequest(execute_request) = &request.content else {
+            panic!("expected an execute request, got {:?}", request.content);
+        };
+        assert_eq!(execute_request.code, "print(1 + 1)");
+        let statuses = execution_statuses(&mut session);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].0, 0..12);
+        assert!(matches!(statuses[0].1, ExecutionStatus::Queued));
+
+        session.reply_status(&request, ExecutionState::Busy);
+        assert!(matches!(
+            execution_statuses(&mut session)[0].1,
+            ExecutionStatus::Executing
+        ));
+
+        session.reply(&request, stdout("2\n"));
+        session.reply_status(&request, ExecutionState::Idle);
+        assert!(matches!(
+            execution_statuses(&mut session)[0].1,
+            ExecutionStatus::Finished
+        ));
+        session.assert_output_blocks(&[(0..12, "2")]);
+        session.assert_no_requests();
+    }
+
+    #[gpui::test]
+    async fn test_messages_are_routed_to_their_execution(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\n", cx).await;
+
+        let first = session.execute(0..5);
+        let second = session.execute(6..11);
+
+        // The kernel runs one execution at a time, so the second stays queued
+        // while the first runs.
+        session.reply_status(&first, ExecutionState::Busy);
+        session.reply(&first, stdout("one"));
+        let statuses = execution_statuses(&mut session);
+        assert!(matches!(statuses[0].1, ExecutionStatus::Executing));
+        assert!(matches!(statuses[1].1, ExecutionStatus::Queued));
+
+        session.reply_status(&first, ExecutionState::Idle);
+        session.reply_status(&second, ExecutionState::Busy);
+        session.reply(&second, stdout("two"));
+        session.reply_status(&second, ExecutionState::Idle);
+
+        let statuses = execution_statuses(&mut session);
+        assert!(matches!(statuses[0].1, ExecutionStatus::Finished));
+        assert!(matches!(statuses[1].1, ExecutionStatus::Finished));
+        session.assert_output_blocks(&[(0..5, "one"), (6..11, "two")]);
+    }
+
+    #[gpui::test]
+    async fn test_rerunning_code_replaces_its_outputs(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("x = 1\n", cx).await;
+
+        let first = session.execute(0..5);
+        session.reply(&first, stdout("first"));
+        let second = session.execute(0..5);
+        // Late messages for the replaced execution are dropped.
+        session.reply(&first, stdout("late"));
+        session.reply(&second, stdout("second"));
+
+        session.assert_output_blocks(&[(0..5, "second")]);
+    }
+
+    #[gpui::test]
+    async fn test_clear_outputs_in_range(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\nc = 3\n", cx).await;
+
+        for range in [0..5, 6..11, 12..17] {
+            let request = session.execute(range);
+            session.reply(&request, stdout("done"));
+        }
+
+        let editor = session.editor.clone();
+        session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let range = snapshot.anchor_before(6)..snapshot.anchor_after(17);
+            session.clear_outputs_in_range(range, cx);
+        });
+        session.cx.run_until_parked();
+
+        session.assert_output_blocks(&[(0..5, "done")]);
+    }
+
+    #[gpui::test]
+    async fn test_store_tracks_busy_kernels(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\n", cx).await;
+        let any_kernel_busy = |session: &mut FakeSession| {
+            session
+                .cx
+                .update(|cx| ReplStore::global(cx).read(cx).any_kernel_busy())
+        };
+        assert!(!any_kernel_busy(&mut session));
+
+        let request = session.execute(0..5);
+        session.reply_status(&request, ExecutionState::Busy);
+        assert!(any_kernel_busy(&mut session));
+
+        session.reply_status(&request, ExecutionState::Idle);
+        assert!(!any_kernel_busy(&mut session));
+
+        session.reply_status(&request, ExecutionState::Busy);
+        let editor_id = session.editor.entity_id();
+        session.cx.update(|cx| {
+            ReplStore::global(cx).update(cx, |store, cx| store.remove_session(editor_id, cx))
+        });
+        assert!(!any_kernel_busy(&mut session));
+    }
+
+    #[test]
+    fn test_kernel_language_falls_back_to_kernelspec() {
+        let kernel_specification = crate::test::fake_kernel_specification();
+        let kernel_info = |language_info| {
+            serde_json::from_value::<KernelInfoReply>(serde_json::json!({
+                "status": "ok",
+                "protocol_version": "5.3",
+                "implementation": "fake",
+                "implementation_version": "1.0",
+                "language_info": language_info,
+                "banner": "",
+                "help_links": []
+            }))
+            .unwrap()
+        };
+
+        let reply = kernel_info(serde_json::json!({ "name": "ir", "version": "4.4" }));
+        assert_eq!(
+            kernel_language(Some(&reply), &kernel_specification),
+            Some("ir".to_string())
+        );
+
+        let reply = kernel_info(serde_json::json!({ "name": "", "version": "" }));
+        assert_eq!(
+            kernel_language(Some(&reply), &kernel_specification),
+            Some("python".to_string())
+        );
+        assert_eq!(
+            kernel_language(None, &kernel_specification),
+            Some("python".to_string())
+        );
+    }
+
+    #[gpui::test]
+    async fn test_lazy_start(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\n", cx).await;
+        let lazy_session = new_lazy_session(&mut session);
+
+        lazy_session.update(session.cx, |session, cx| {
+            assert!(matches!(session.kernel, Kernel::NotStarted));
+            assert!(matches!(session.kernel.status(), KernelStatus::NotStarted));
+            assert_eq!(session.status_color(), Color::Muted);
+        });
+
+        // Running code starts the kernel, and the request is delivered once
+        // it's running. The fake kernel stands in for the one being launched,
+        // which is dropped before it runs.
+        let editor = session.editor.clone();
+        let (kernel, mut requests) = FakeKernel::new();
+        lazy_session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let anchor_range = snapshot.anchor_before(0)..snapshot.anchor_after(5);
+            session.execute("a = 1".to_string(), anchor_range, None, false, cx);
+            assert!(matches!(session.kernel, Kernel::StartingKernel(_)));
+            assert!(requests.try_next().is_err());
+
+            session.kernel(Kernel::RunningKernel(Box::new(kernel)), cx);
+        });
+        let request = requests.try_next().unwrap().unwrap();
+        let JupyterMessageContent::ExecuteRequest(execute_request) = &request.content else {
+            panic!("expected an execute request, got {:?}", request.content);
+        };
+        assert_eq!(execute_request.code, "a = 1");
+
+        // Closing a session that never started a kernel doesn't need one to
+        // shut down.
+        let lazy_session = new_lazy_session(&mut session);
+        lazy_session.update(session.cx, |session, cx| {
+            session.shutdown(cx);
+            assert!(matches!(session.kernel, Kernel::Shutdown));
+        });
+    }
+
+    /// Creates another session for the editor, with `repl.lazy_start` on so
+    /// that its kernel isn't started until code is run.
+    fn new_lazy_session(session: &mut FakeSession) -> View<Session> {
+        session.cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ReplSettings>(cx, |settings| {
+                    settings.lazy_start = Some(true);
+                });
+            });
+        });
+
+        let editor = session.editor.downgrade();
+        let lazy_session = session.cx.new_view(|cx| {
+            let store = ReplStore::global(cx);
+            let fs = store.read(cx).fs().clone();
+            let telemetry = store.read(cx).telemetry().clone();
+            Session::new(
+                editor,
+                fs,
+                telemetry,
+                crate::test::fake_kernel_specification(),
+                cx,
+            )
+        });
+        session.cx.run_until_parked();
+        lazy_session
+    }
+
+    #[gpui::test]
+    async fn test_interrupt_while_the_kernel_starts(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\n", cx).await;
+        let lazy_session = new_lazy_session(&mut session);
+
+        let editor = session.editor.clone();
+        let (kernel, mut requests) = FakeKernel::new();
+        let task = lazy_session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            session.queued_executions.push((
+                "b = 2".to_string(),
+                snapshot.anchor_before(6)..snapshot.anchor_after(11),
+            ));
+            let anchor_range = snapshot.anchor_before(0)..snapshot.anchor_after(5);
+            let task = session.execute_and_wait("a = 1".to_string(), anchor_range, cx);
+            assert!(matches!(session.kernel, Kernel::StartingKernel(_)));
+
+            session.interrupt(cx);
+            task
+        });
+        assert!(matches!(task.await, Ok(ExecutionStatus::KernelErrored(_))));
+
+        // Nothing that was waiting for the kernel runs once it's up.
+        lazy_session.update(session.cx, |session, cx| {
+            assert!(session.execution_waiters.is_empty());
+            assert!(session.blocks.values().all(|block| matches!(
+                block.execution_view.read(cx).status,
+                ExecutionStatus::KernelErrored(_)
+            )));
+            session.kernel(Kernel::RunningKernel(Box::new(kernel)), cx);
+        });
+        assert!(requests.try_next().is_err());
+    }
+
+    #[gpui::test]
+    async fn test_execute_while_restarting(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\n", cx).await;
+        session
+            .session
+            .update(session.cx, |session, cx| session.restart(false, cx));
+
+        let editor = session.editor.clone();
+        session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let anchor_range = snapshot.anchor_before(0)..snapshot.anchor_after(5);
+            session.execute("a = 1".to_string(), anchor_range, None, false, cx);
+        });
+        assert!(matches!(
+            execution_statuses(&mut session)[0].1,
+            ExecutionStatus::Restarting
+        ));
+
+        // The request is delivered to the new kernel once it's running.
+        let (kernel, requests) = FakeKernel::new();
+        session.requests = requests;
+        session.session.update(session.cx, |session, cx| {
+            session.kernel(Kernel::RunningKernel(Box::new(kernel)), cx)
+        });
+        let request = session.next_request();
+        let JupyterMessageContent::ExecuteRequest(execute_request) = &request.content else {
+            panic!("expected an execute request, got {:?}", request.content);
+        };
+        assert_eq!(execute_request.code, "a = 1");
+
+        session.reply_status(&request, ExecutionState::Busy);
+        session.reply_status(&request, ExecutionState::Idle);
+        assert!(matches!(
+            execution_statuses(&mut session)[0].1,
+            ExecutionStatus::Finished
+        ));
+    }
+
+    /// Executes the code at `range` like [`FakeSession::execute`], returning
+    /// the task awaiting its completion along with the kernel's request.
+    fn execute_and_wait(
+        session: &mut FakeSession,
+        range: Range<usize>,
+    ) -> (Task<anyhow::Result<ExecutionStatus>>, JupyterMessage) {
+        let editor = session.editor.clone();
+        let task = session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let code = snapshot.text_for_range(range.clone()).collect::<String>();
+            let anchor_range =
+                snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            session.execute_and_wait(code, anchor_range, cx)
+        });
+        session.cx.run_until_parked();
+        (task, session.next_request())
+    }
+
+    #[gpui::test]
+    async fn test_cells(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("# %%\na = 1\n# %%\nb = 2\n# %%\nc = 3\n", cx).await;
+        session.set_cell_language();
+
+        let third = session.execute(22..32);
+        session.reply(
+            &third,
+            ExecuteInput {
+                code: "# %%\nc = 3".to_string(),
+                execution_count: ExecutionCount(1),
+            },
+        );
+        session.reply_status(&third, ExecutionState::Idle);
+        // Running part of a cell counts as running the cell.
+        let first = session.execute(5..10);
+        session.reply(
+            &first,
+            ExecuteInput {
+                code: "a = 1".to_string(),
+                execution_count: ExecutionCount(2),
+            },
+        );
+
+        let cells = session
+            .cx
+            .update(|cx| session.session.read(cx).cells(cx))
+            .into_iter()
+            .map(|cell| (cell.range, cell.status, cell.execution_count))
+            .collect::<Vec<_>>();
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0].0, 0..10);
+        assert!(matches!(cells[0].1, Some(ExecutionStatus::Queued)));
+        assert_eq!(cells[0].2, Some(2));
+        assert_eq!(cells[1].0, 11..21);
+        assert!(cells[1].1.is_none());
+        assert_eq!(cells[1].2, None);
+        assert_eq!(cells[2].0, 22..32);
+        assert!(matches!(cells[2].1, Some(ExecutionStatus::Finished)));
+        assert_eq!(cells[2].2, Some(1));
+    }
+
+    #[gpui::test]
+    async fn test_execute_and_wait(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\n1 / 0\nb = 2\n", cx).await;
+
+        let (task, request) = execute_and_wait(&mut session, 0..5);
+        session.reply_status(&request, ExecutionState::Busy);
+        session.reply_status(&request, ExecutionState::Idle);
+        assert!(matches!(task.await, Ok(ExecutionStatus::Finished)));
+
+        // Errors raised by the code fail the wait.
+        let (task, request) = execute_and_wait(&mut session, 6..11);
+        session.reply_status(&request, ExecutionState::Busy);
+        session.reply(
+            &request,
+            ErrorOutput {
+                ename: "ZeroDivisionError".to_string(),
+                evalue: "division by zero".to_string(),
+                traceback: Vec::new(),
+            },
+        );
+        session.reply_status(&request, ExecutionState::Idle);
+        assert_eq!(
+            task.await.unwrap_err().to_string(),
+            "ZeroDivisionError: division by zero"
+        );
+
+        // Restarting resolves executions the old kernel was still running.
+        // New ones run on the new kernel.
+        let (task, request) = execute_and_wait(&mut session, 12..17);
+        session.reply_status(&request, ExecutionState::Busy);
+        session
+            .session
+            .update(session.cx, |session, cx| session.restart(false, cx));
+        assert!(matches!(task.await, Ok(ExecutionStatus::KernelErrored(_))));
+        let editor = session.editor.clone();
+        let task = session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let anchor_range = snapshot.anchor_before(12)..snapshot.anchor_after(17);
+            session.execute_and_wait("b = 2".to_string(), anchor_range, cx)
+        });
+        let (kernel, requests) = FakeKernel::new();
+        session.requests = requests;
+        session.session.update(session.cx, |session, cx| {
+            session.kernel(Kernel::RunningKernel(Box::new(kernel)), cx)
+        });
+        let request = session.next_request();
+        session.reply_status(&request, ExecutionState::Busy);
+        session.reply_status(&request, ExecutionState::Idle);
+        assert!(matches!(task.await, Ok(ExecutionStatus::Finished)));
+    }
+
+    #[gpui::test]
+    async fn test_reconnecting(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\n", cx).await;
+
+        let (task, first) = execute_and_wait(&mut session, 0..5);
+        session.reply_status(&first, ExecutionState::Busy);
+        session.session.update(session.cx, |session, cx| {
+            session.kernel_reconnecting(true, cx)
+        });
+        assert!(matches!(
+            execution_statuses(&mut session)[0].1,
+            ExecutionStatus::Reconnecting
+        ));
+
+        // Code run while reconnecting is sent once the connection is back.
+        let second = session.execute(6..11);
+        assert!(matches!(
+            execution_statuses(&mut session)[1].1,
+            ExecutionStatus::Queued
+        ));
+
+        // The replies to the first execution were lost with the connection.
+        session.session.update(session.cx, |session, cx| {
+            session.kernel_reconnecting(false, cx)
+        });
+        let statuses = execution_statuses(&mut session);
+        assert!(matches!(statuses[0].1, ExecutionStatus::KernelErrored(_)));
+        assert!(matches!(statuses[1].1, ExecutionStatus::Queued));
+        assert!(matches!(task.await, Ok(ExecutionStatus::KernelErrored(_))));
+
+        session.reply_status(&second, ExecutionState::Busy);
+        session.reply_status(&second, ExecutionState::Idle);
+        assert!(matches!(
+            execution_statuses(&mut session)[1].1,
+            ExecutionStatus::Finished
+        ));
+    }
+
+    #[gpui::test]
+    async fn test_restart(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\n", cx).await;
+
+        let first = session.execute(0..5);
+        session.reply_status(&first, ExecutionState::Busy);
+        session.reply(&first, stdout("done"));
+        session.reply_status(&first, ExecutionState::Idle);
+        let (task, second) = execute_and_wait(&mut session, 6..11);
+        session.reply_status(&second, ExecutionState::Busy);
+
+        // Finished outputs are kept, and what the old kernel was running fails.
+        session
+            .session
+            .update(session.cx, |session, cx| session.restart(false, cx));
+        let statuses = execution_statuses(&mut session);
+        assert_eq!(statuses.len(), 2);
+        assert!(matches!(statuses[0].1, ExecutionStatus::Finished));
+        assert!(matches!(statuses[1].1, ExecutionStatus::KernelErrored(_)));
+        assert!(matches!(task.await, Ok(ExecutionStatus::KernelErrored(_))));
+        session.session.update(session.cx, |session, _| {
+            assert!(session.execution_waiters.is_empty());
+        });
+
+        let (kernel, _requests) = FakeKernel::new();
+        session.session.update(session.cx, |session, cx| {
+            session.kernel = Kernel::RunningKernel(Box::new(kernel));
+            session.restart(true, cx);
+        });
+        assert!(execution_statuses(&mut session).is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_interrupt(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("while True: pass\n", cx).await;
+
+        let request = session.execute(0..16);
+        session.reply_status(&request, ExecutionState::Busy);
+        session
+            .session
+            .update(session.cx, |session, cx| session.interrupt(cx));
+        session.cx.run_until_parked();
+        let interrupt = session.next_request();
+        assert!(
+            matches!(
+                interrupt.content,
+                JupyterMessageContent::InterruptRequest(_)
+            ),
+            "expected an interrupt request, got {:?}",
+            interrupt.content
+        );
+
+        // The kernel going idle in time cancels the restart.
+        session.reply_status(&request, ExecutionState::Idle);
+        session.cx.executor().advance_clock(Duration::from_secs(60));
+        session.cx.run_until_parked();
+        session.session.update(session.cx, |session, _| {
+            assert!(session.pending_interrupt.is_none());
+            assert!(matches!(session.kernel, Kernel::RunningKernel(_)));
+        });
+        session.assert_no_requests();
+    }
+
+    #[gpui::test]
+    async fn test_queued_executions_run_once_the_kernel_is_running(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut session = FakeSession::new("a = 1\nb = 2\n", cx).await;
+
+        let editor = session.editor.clone();
+        session.session.update(session.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            session.kernel = Kernel::Restarting;
+            session.queued_executions = [0..5, 6..11]
+                .into_iter()
+                .map(|range| {
+                    let code = snapshot.text_for_range(range.clone()).collect::<String>();
+                    let range =
+                        snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+                    (code, range)
+                })
+                .collect();
+        });
+
+        let (kernel, mut requests) = FakeKernel::new();
+        session.session.update(session.cx, |session, cx| {
+            session.kernel(Kernel::RunningKernel(Box::new(kernel)), cx);
+            assert!(session.queued_executions.is_empty());
+        });
+        session.cx.run_until_parked();
+
+        let codes = std::iter::from_fn(|| requests.try_next().ok().flatten())
+            .map(|request| match request.content {
+                JupyterMessageContent::ExecuteRequest(request) => request.code,
+                content => panic!("expected an execute request, got {content:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["a = 1", "b = 2"]);
+        assert_eq!(execution_statuses(&mut session).len(), 2);
+    }
+
+    #[test]
+    fn test_output_area_colors_fall_back_when_transparent() {
+        let mut colors = ThemeColors::dark();
+        assert_eq!(
+            OutputAreaColors::new(&colors),
+            OutputAreaColors {
+                border: colors.border,
+                background: colors.background,
+                icon: colors.text_muted,
+            }
+        );
+
+        colors.border = gpui::transparent_black();
+        colors.background = gpui::transparent_black();
+        assert_eq!(
+            OutputAreaColors::new(&colors),
+            OutputAreaColors {
+                border: colors.border_variant,
+                background: colors.surface_background,
+                icon: colors.text_muted,
+            }
+        );
+
+        colors.border_variant = gpui::transparent_black();
+        colors.surface_background = gpui::transparent_black();
+        let output_area_colors = OutputAreaColors::new(&colors);
+        assert!(output_area_colors.border.a > 0.);
+        assert_eq!(output_area_colors.background, colors.editor_background);
+
+        // A theme that leaves every candidate transparent still gets an
+        // opaque background, with borders and buttons that stand out on it.
+        strip_colors(&mut colors);
+        let output_area_colors = OutputAreaColors::new(&colors);
+        assert_eq!(output_area_colors.background.a, 1.);
+        assert!(output_area_colors.border.a > 0.);
+        assert_eq!(output_area_colors.icon.a, 1.);
+        assert!((output_area_colors.icon.l - output_area_colors.background.l).abs() > 0.5);
+    }
+
+    /// Makes every color an output area could be drawn with transparent.
+    fn strip_colors(colors: &mut ThemeColors) {
+        for color in [
+            &mut colors.border,
+            &mut colors.border_variant,
+            &mut colors.background,
+            &mut colors.surface_background,
+            &mut colors.editor_background,
+            &mut colors.text,
+            &mut colors.text_muted,
+        ] {
+            *color = gpui::transparent_black();
+        }
+    }
+
+    #[gpui::test]
+    async fn test_output_area_renders_under_a_minimal_theme(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, _| {
+                let mut settings = store.get::<ThemeSettings>(None).clone();
+                let mut theme = (*settings.active_theme).clone();
+                strip_colors(&mut theme.styles.colors);
+                settings.active_theme = Arc::new(theme);
+                store.override_global(settings);
+            });
+        });
+        let mut session = FakeSession::new("print(1)\n", cx).await;
+
+        let request = session.execute(0..8);
+        session.reply(&request, stdout("1"));
+        session.reply_status(&request, ExecutionState::Idle);
+
+        let editor = session.editor.clone();
+        session
+            .cx
+            .draw(point(px(0.), px(0.)), size(px(800.), px(600.)), |_| editor);
+        let close_button = session.cx.debug_bounds("ICON-Close").unwrap();
+        assert!(close_button.size.width > px(0.) && close_button.size.height > px(0.));
+    }
+}
@@ -0,0 +1,303 @@
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use editor::{
+    display_map::{BlockId, DisplayRow},
+    test::editor_test_context::EditorTestContext,
+    Editor,
+};
+use futures::channel::mpsc;
+use gpui::{AppContext, Task, TestAppContext, View, VisualTestContext, WindowContext};
+use language::{Language, LanguageConfig};
+use project::{FakeFs, Project};
+use runtimelib::{ExecutionState, JupyterMessage, KernelInfoReply, Status};
+use settings::Settings as _;
+use workspace::Workspace;
+
+use crate::kernels::{KernelSpecification, LocalKernelSpecification, RunningKernel};
+use crate::repl_store::ReplStore;
+use crate::{JupyterSettings, ReplSettings, Session};
+
+/// REPL-specific assertions for editors in tests.
+pub trait ReplEditorTestContext {
+    /// Asserts that the editor shows exactly the given output blocks of its
+    /// REPL session, in display order. Each is identified by the offset range
+    /// of the code that produced it and the text of its outputs, without
+    /// trailing whitespace.
+    fn assert_output_blocks(&mut self, expected: &[(Range<usize>, &str)]);
+}
+
+impl ReplEditorTestContext for EditorTestContext {
+    #[track_caller]
+    fn assert_output_blocks(&mut self, expected: &[(Range<usize>, &str)]) {
+        let editor = self.editor.clone();
+        let actual = self.cx.update(|cx| output_blocks(&editor, cx));
+        assert_eq!(
+            actual,
+            expected_output_blocks(expected),
+            "{}",
+            self.assertion_context()
+        );
+    }
+}
+
+impl ReplEditorTestContext for FakeSession<'_> {
+    #[track_caller]
+    fn assert_output_blocks(&mut self, expected: &[(Range<usize>, &str)]) {
+        let editor = self.editor.clone();
+        let actual = self.cx.update(|cx| output_blocks(&editor, cx));
+        assert_eq!(actual, expected_output_blocks(expected));
+    }
+}
+
+/// Returns the output blocks of the editor's session that are among the
+/// editor's custom blocks, in display order.
+fn output_blocks(editor: &View<Editor>, cx: &mut WindowContext) -> Vec<(Range<usize>, String)> {
+    let Some(session) = ReplStore::global(cx)
+        .read(cx)
+        .get_session(editor.entity_id())
+        .cloned()
+    else {
+        return Vec::new();
+    };
+    let block_ids = editor.update(cx, |editor, cx| {
+        editor
+            .snapshot(cx)
+            .blocks_in_range(DisplayRow(0)..DisplayRow(u32::MAX))
+            .filter_map(|(_, block)| match block.id() {
+                BlockId::Custom(block_id) => Some(block_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let cx: &WindowContext = cx;
+    let session = session.read(cx);
+    block_ids
+        .into_iter()
+        .filter_map(|block_id| session.output_block(block_id, cx))
+        .map(|(range, text)| (range, text.trim_end().to_string()))
+        .collect()
+}
+
+fn expected_output_blocks(expected: &[(Range<usize>, &str)]) -> Vec<(Range<usize>, String)> {
+    expected
+        .iter()
+        .map(|(range, text)| (range.clone(), text.to_string()))
+        .collect()
+}
+
+/// A kernel that runs no process. The requests a session sends it are handed
+/// to the test, which scripts the kernel's side by routing replies back to the
+/// session, as [`FakeSession::reply`] does.
+#[derive(Debug)]
+pub struct FakeKernel {
+    request_tx: mpsc::Sender<JupyterMessage>,
+    working_directory: PathBuf,
+    execution_state: ExecutionState,
+    kernel_info: Option<KernelInfoReply>,
+    reconnecting: bool,
+}
+
+impl FakeKernel {
+    /// Returns the kernel, along with the receiving end of its requests.
+    pub fn new() -> (Self, mpsc::Receiver<JupyterMessage>) {
+        let (request_tx, request_rx) = mpsc::channel(100);
+        let kernel = Self {
+            request_tx,
+            working_directory: PathBuf::from("/fake"),
+            execution_state: ExecutionState::Idle,
+            kernel_info: None,
+            reconnecting: false,
+        };
+        (kernel, request_rx)
+    }
+}
+
+impl RunningKernel for FakeKernel {
+    fn request_tx(&self) -> mpsc::Sender<JupyterMessage> {
+        self.request_tx.clone()
+    }
+
+    fn working_directory(&self) -> &PathBuf {
+        &self.working_directory
+    }
+
+    fn execution_state(&self) -> &ExecutionState {
+        &self.execution_state
+    }
+
+    fn set_execution_state(&mut self, state: ExecutionState) {
+        self.execution_state = state;
+    }
+
+    fn kernel_info(&self) -> Option<&KernelInfoReply> {
+        self.kernel_info.as_ref()
+    }
+
+    fn set_kernel_info(&mut self, info: KernelInfoReply) {
+        self.kernel_info = Some(info);
+    }
+
+    fn is_reconnecting(&self) -> bool {
+        self.reconnecting
+    }
+
+    fn set_reconnecting(&mut self, reconnecting: bool) {
+        self.reconnecting = reconnecting;
+    }
+
+    fn force_shutdown(&mut self, _cx: &mut WindowContext) -> Task<anyhow::Result<()>> {
+        Task::ready(Ok(()))
+    }
+}
+
+/// A Python kernelspec that's never spawned, for sessions on a [`FakeKernel`].
+pub fn fake_kernel_specification() -> KernelSpecification {
+    KernelSpecification::Jupyter(LocalKernelSpecification {
+        name: "fake".to_string(),
+        path: PathBuf::from("/fake"),
+        kernelspec: serde_json::from_value(serde_json::json!({
+            "display_name": "Fake",
+            "language": "python",
+            "argv": ["fake-kernel", "-f", "{connection_file}"],
+            "env": {}
+        }))
+        .unwrap(),
+    })
+}
+
+/// Registers what sessions need in tests. Unlike [`crate::init`], no kernels
+/// are looked for.
+pub fn init_test(project: &Project, cx: &mut AppContext) {
+    JupyterSettings::register(cx);
+    ReplSettings::register(cx);
+    command_palette_hooks::init(cx);
+    ReplStore::init_test(
+        project.fs().clone(),
+        project.client().telemetry().clone(),
+        cx,
+    );
+}
+
+/// A session on a [`FakeKernel`], for an editor in a test workspace.
+pub struct FakeSession<'a> {
+    pub cx: &'a mut VisualTestContext,
+    pub editor: View<Editor>,
+    pub session: View<Session>,
+    /// The requests the session sent to the kernel, oldest first.
+    pub requests: mpsc::Receiver<JupyterMessage>,
+}
+
+impl<'a> FakeSession<'a> {
+    /// Opens an editor with `text` in a new workspace, and attaches a session
+    /// on a fake kernel to it. Settings, themes and languages must already be
+    /// initialized.
+    pub async fn new(text: &str, cx: &'a mut TestAppContext) -> FakeSession<'a> {
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        cx.update(|cx| init_test(project.read(cx), cx));
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text(text, cx);
+            editor
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+        });
+
+        let (kernel, requests) = FakeKernel::new();
+        let session = cx.new_view(|cx| {
+            let fs = project.read(cx).fs().clone();
+            let telemetry = project.read(cx).client().telemetry().clone();
+            Session::test(
+                editor.downgrade(),
+                fs,
+                telemetry,
+                fake_kernel_specification(),
+                Box::new(kernel),
+                cx,
+            )
+        });
+        cx.update(|cx| {
+            ReplStore::global(cx).update(cx, |store, cx| {
+                store.insert_session(editor.entity_id(), session.clone(), cx);
+            })
+        });
+
+        FakeSession {
+            cx,
+            editor,
+            session,
+            requests,
+        }
+    }
+
+    /// Gives the editor's buffer a language with `# ` line comments, so that
+    /// its code is split into cells at `# %%` lines.
+    pub fn set_cell_language(&mut self) {
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "TestLang".into(),
+                line_comments: vec!["# ".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+        self.editor.update(self.cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            buffer.update(cx, |buffer, cx| buffer.set_language(Some(language), cx));
+        });
+        self.cx.run_until_parked();
+    }
+
+    /// Executes the code at `range` in the editor, returning the request the
+    /// kernel received for it.
+    pub fn execute(&mut self, range: Range<usize>) -> JupyterMessage {
+        let editor = self.editor.clone();
+        self.session.update(self.cx, |session, cx| {
+            let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+            let code = snapshot.text_for_range(range.clone()).collect::<String>();
+            let anchor_range =
+                snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            session.execute(code, anchor_range, None, false, cx);
+        });
+        self.cx.run_until_parked();
+        self.next_request()
+    }
+
+    /// Returns the next request the kernel received.
+    #[track_caller]
+    pub fn next_request(&mut self) -> JupyterMessage {
+        self.requests
+            .try_next()
+            .expect("the kernel received no request")
+            .expect("the kernel's request channel is closed")
+    }
+
+    /// Asserts that the kernel received no further requests.
+    #[track_caller]
+    pub fn assert_no_requests(&mut self) {
+        if let Ok(Some(request)) = self.requests.try_next() {
+            panic!("unexpected request: {:?}", request.content);
+        }
+    }
+
+    /// Routes a message from the kernel, sent in reply to `request`, to the
+    /// session.
+    pub fn reply(&mut self, request: &JupyterMessage, content: impl Into<JupyterMessage>) {
+        let mut message = content.into();
+        message.parent_header = Some(request.header.clone());
+        self.session
+            .update(self.cx, |session, cx| session.route(&message, cx));
+        self.cx.run_until_parked();
+    }
+
+    /// Reports the kernel's execution state while handling `request`.
+    pub fn reply_status(&mut self, request: &JupyterMessage, execution_state: ExecutionState) {
+        self.reply(request, Status { execution_state });
+    }
+}
@@ -104,6 +104,7 @@ pub enum Event {
     Edit(EditEvent),
     Action(ActionEvent),
     Repl(ReplEvent),
+    ReplExecution(ReplExecutionEvent),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -223,6 +224,16 @@ pub struct ReplEvent {
     pub repl_session_id: String,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplExecutionEvent {
+    pub kernel_language: String,
+    pub repl_session_id: String,
+    /// Whether the execution completed without an error
+    pub success: bool,
+    /// How long the kernel spent executing, in milliseconds
+    pub duration_ms: u64,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BacktraceFrame {
     pub ip: usize,
@@ -3,13 +3,17 @@ use alacritty_terminal::vte::ansi::{
 };
 use collections::HashMap;
 use gpui::{
-    px, AbsoluteLength, AppContext, FontFallbacks, FontFeatures, FontWeight, Pixels, SharedString,
+    px, AbsoluteLength, AppContext, FontFallbacks, FontFeatures, FontWeight, Global, Pixels,
+    SharedString,
 };
 use schemars::{gen::SchemaGenerator, schema::RootSchema, JsonSchema};
 use serde_derive::{Deserialize, Serialize};
-use settings::{add_references_to_properties, SettingsJsonSchemaParams, SettingsSources};
+use settings::{
+    add_references_to_properties, Settings as _, SettingsJsonSchemaParams, SettingsSources,
+};
 use std::path::PathBuf;
 use task::Shell;
+use theme::ThemeSettings;
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -189,6 +193,48 @@ pub struct TerminalSettingsContent {
     pub toolbar: Option<ToolbarContent>,
 }
 
+#[derive(Default)]
+struct AdjustedTerminalFontSize(Pixels);
+
+impl Global for AdjustedTerminalFontSize {}
+
+/// Returns the terminal font size from the settings, falling back to the
+/// (adjusted) buffer font size when no terminal font size is configured.
+fn terminal_font_size_from_settings(cx: &AppContext) -> Pixels {
+    let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size(cx);
+    TerminalSettings::get_global(cx)
+        .font_size
+        .map_or(buffer_font_size, |size| theme::adjusted_font_size(size, cx))
+}
+
+/// Returns the adjusted terminal font size.
+pub fn get_terminal_font_size(cx: &AppContext) -> Pixels {
+    cx.try_global::<AdjustedTerminalFontSize>()
+        .map_or_else(|| terminal_font_size_from_settings(cx), |size| size.0)
+}
+
+/// Adjusts the terminal font size.
+pub fn adjust_terminal_font_size(cx: &mut AppContext, f: fn(&mut Pixels)) {
+    let mut adjusted_size = get_terminal_font_size(cx);
+    f(&mut adjusted_size);
+    adjusted_size = adjusted_size.max(theme::MIN_FONT_SIZE);
+    cx.set_global(AdjustedTerminalFontSize(adjusted_size));
+    cx.refresh();
+}
+
+/// Returns whether the terminal font size has been adjusted.
+pub fn has_adjusted_terminal_font_size(cx: &AppContext) -> bool {
+    cx.has_global::<AdjustedTerminalFontSize>()
+}
+
+/// Resets the terminal font size to the value from the settings.
+pub fn reset_terminal_font_size(cx: &mut AppContext) {
+    if cx.has_global::<AdjustedTerminalFontSize>() {
+        cx.remove_global::<AdjustedTerminalFontSize>();
+        cx.refresh();
+    }
+}
+
 impl settings::Settings for TerminalSettings {
     const KEY: Option<&'static str> = Some("terminal");
 
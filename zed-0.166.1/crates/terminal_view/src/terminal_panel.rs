@@ -1,4 +1,10 @@
-use std::{cmp, ops::ControlFlow, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    cmp,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{
     default_working_directory,
@@ -52,6 +58,7 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
+            workspace.register_action(TerminalPanel::run_in_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 if workspace
                     .panel::<TerminalPanel>(cx)
@@ -448,6 +455,63 @@ impl TerminalPanel {
             .detach_and_log_err(cx);
     }
 
+    pub fn run_in_terminal(
+        workspace: &mut Workspace,
+        action: &workspace::RunInTerminal,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+
+        let text = action.text.trim_end().to_string();
+        let existing_terminal = terminal_panel.update(cx, |panel, cx| {
+            let (item_index, pane, terminal_view) =
+                panel.shell_in_directory(&action.working_directory, cx)?;
+            panel.activate_terminal_view(&pane, item_index, true, cx);
+            Some(terminal_view.read(cx).terminal().clone())
+        });
+
+        if let Some(terminal) = existing_terminal {
+            workspace.focus_panel::<Self>(cx);
+            terminal.update(cx, |terminal, _| run_text(terminal, &text));
+            return;
+        }
+
+        let new_terminal = terminal_panel.update(cx, |panel, cx| {
+            panel.add_terminal(
+                TerminalKind::Shell(Some(action.working_directory.clone())),
+                RevealStrategy::Always,
+                cx,
+            )
+        });
+        cx.spawn(|_, mut cx| async move {
+            let terminal = new_terminal.await?;
+            terminal.update(&mut cx, |terminal, _| run_text(terminal, &text))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Finds a shell terminal, not running a task, whose working directory is `working_directory`.
+    fn shell_in_directory(
+        &self,
+        working_directory: &Path,
+        cx: &mut AppContext,
+    ) -> Option<(usize, View<Pane>, View<TerminalView>)> {
+        self.center.panes().into_iter().find_map(|pane| {
+            pane.read(cx)
+                .items()
+                .enumerate()
+                .filter_map(|(index, item)| Some((index, item.act_as::<TerminalView>(cx)?)))
+                .find(|(_, terminal_view)| {
+                    let terminal = terminal_view.read(cx).terminal().read(cx);
+                    terminal.task().is_none()
+                        && terminal.working_directory().as_deref() == Some(working_directory)
+                })
+                .map(|(index, terminal_view)| (index, pane.clone(), terminal_view))
+        })
+    }
+
     fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
         let mut spawn_task = spawn_in_terminal.clone();
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
@@ -1378,3 +1442,9 @@ enum WindowsShellType {
     Cmd,
     Other,
 }
+
+/// Pastes `text` into the terminal and submits it.
+fn run_text(terminal: &mut Terminal, text: &str) {
+    terminal.paste(text);
+    terminal.input("\r".to_string());
+}
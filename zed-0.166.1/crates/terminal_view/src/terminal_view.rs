@@ -19,7 +19,7 @@ use terminal::{
         index::Point,
         term::{search::RegexSearch, TermMode},
     },
-    terminal_settings::{CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
+    terminal_settings::{self, CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
     ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
     TerminalSize, ToggleViMode,
@@ -79,7 +79,17 @@ pub fn init(cx: &mut AppContext) {
     register_serializable_item::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
-        workspace.register_action(TerminalView::deploy);
+        workspace
+            .register_action(TerminalView::deploy)
+            .register_action(|_, _: &zed_actions::IncreaseTerminalFontSize, cx| {
+                terminal_settings::adjust_terminal_font_size(cx, |size| *size += px(1.0))
+            })
+            .register_action(|_, _: &zed_actions::DecreaseTerminalFontSize, cx| {
+                terminal_settings::adjust_terminal_font_size(cx, |size| *size -= px(1.0))
+            })
+            .register_action(|_, _: &zed_actions::ResetTerminalFontSize, cx| {
+                terminal_settings::reset_terminal_font_size(cx)
+            });
     })
     .detach();
 }
@@ -18,7 +18,8 @@ use settings::{add_references_to_properties, Settings, SettingsJsonSchemaParams,
 use std::sync::Arc;
 use util::ResultExt as _;
 
-const MIN_FONT_SIZE: Pixels = px(6.0);
+/// The smallest size any adjusted font size is clamped to.
+pub const MIN_FONT_SIZE: Pixels = px(6.0);
 const MIN_LINE_HEIGHT: f32 = 1.0;
 
 #[derive(
@@ -8,18 +8,22 @@ use gpui::{
 use picker::{Picker, PickerDelegate};
 use settings::{update_settings_file, SettingsStore};
 use std::sync::Arc;
-use theme::{Appearance, Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
+use theme::{
+    Appearance, Theme, ThemeMeta, ThemeMode, ThemeRegistry, ThemeSelection, ThemeSettings,
+};
 use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
 use util::ResultExt;
 use workspace::{ui::HighlightedLabel, ModalView, Workspace};
-use zed_actions::theme_selector::Toggle;
+use zed_actions::theme_selector::{Toggle, ToggleMode};
 
 actions!(theme_selector, [Reload]);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
-            workspace.register_action(toggle);
+            workspace
+                .register_action(toggle)
+                .register_action(toggle_mode);
         },
     )
     .detach();
@@ -40,6 +44,38 @@ pub fn toggle(workspace: &mut Workspace, toggle: &Toggle, cx: &mut ViewContext<W
     });
 }
 
+/// Switches between the configured light and dark themes.
+///
+/// If only a single theme is configured, it is kept for its own appearance and
+/// the default theme is used for the other one.
+pub fn toggle_mode(workspace: &mut Workspace, _: &ToggleMode, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let appearance = cx.theme().appearance();
+    let current_theme = cx.theme().name.to_string();
+    let mode = match appearance {
+        Appearance::Light => ThemeMode::Dark,
+        Appearance::Dark => ThemeMode::Light,
+    };
+
+    update_settings_file::<ThemeSettings>(fs, cx, move |settings, _| {
+        if let Some(ThemeSelection::Dynamic { .. }) = settings.theme {
+            settings.set_mode(mode);
+        } else {
+            let (light, dark) = match appearance {
+                Appearance::Light => (
+                    current_theme,
+                    ThemeSettings::default_theme(Appearance::Dark).into(),
+                ),
+                Appearance::Dark => (
+                    ThemeSettings::default_theme(Appearance::Light).into(),
+                    current_theme,
+                ),
+            };
+            settings.theme = Some(ThemeSelection::Dynamic { mode, light, dark });
+        }
+    });
+}
+
 impl ModalView for ThemeSelector {}
 
 pub struct ThemeSelector {
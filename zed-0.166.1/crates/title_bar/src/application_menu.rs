@@ -1,35 +1,152 @@
-use ui::{prelude::*, ContextMenu, NumericStepper, PopoverMenu, PopoverMenuHandle, Tooltip};
+use editor::{
+    actions::{DecreaseTabSize, IncreaseTabSize, ResetTabSize},
+    Editor, LineNumbersControl,
+};
+use fs::Fs;
+use gpui::{AnchorCorner, AppContext, FocusableView, View, WeakView};
+use i18n::t;
+use settings::{update_settings_file, EditableSettingControl, Settings};
+use std::path::PathBuf;
+use terminal::terminal_settings::{get_terminal_font_size, has_adjusted_terminal_font_size};
+use ui::{
+    prelude::*, Checkbox, ContextMenu, NumericStepper, PopoverMenu, PopoverMenuHandle, Selection,
+    Tooltip,
+};
+use util::{paths::PathExt, ResultExt};
+use vim_mode_setting::VimModeSetting;
+use welcome::BaseKeymap;
+use workspace::{SerializedWorkspaceLocation, Workspace, WorkspaceId, WORKSPACE_DB};
+
+/// The maximum number of recent projects listed directly in the menu.
+const MAX_RECENT_PROJECTS: usize = 8;
 
 pub struct ApplicationMenu {
+    workspace: Option<WeakView<Workspace>>,
     context_menu_handle: PopoverMenuHandle<ContextMenu>,
+    recent_workspaces: Vec<(WorkspaceId, Vec<PathBuf>)>,
 }
 
 impl ApplicationMenu {
-    pub fn new(_: &mut ViewContext<Self>) -> Self {
-        Self {
+    pub fn new(workspace: Option<WeakView<Workspace>>, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            workspace,
             context_menu_handle: PopoverMenuHandle::default(),
-        }
+            recent_workspaces: Vec::new(),
+        };
+        this.refresh_recent_workspaces(cx);
+        this
+    }
+
+    fn refresh_recent_workspaces(&mut self, cx: &mut ViewContext<Self>) {
+        cx.spawn(|this, mut cx| async move {
+            let workspaces = WORKSPACE_DB
+                .recent_workspaces_on_disk()
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.recent_workspaces = workspaces
+                    .into_iter()
+                    .filter_map(|(id, location)| match location {
+                        SerializedWorkspaceLocation::Local(paths, _) => {
+                            Some((id, paths.paths().to_vec()))
+                        }
+                        SerializedWorkspaceLocation::Ssh(_) => None,
+                    })
+                    .collect();
+                cx.notify();
+            })
+            .ok()
+        })
+        .detach();
+    }
+
+    fn clear_recent_workspaces(&mut self, cx: &mut ViewContext<Self>) {
+        let current_workspace_id = self.current_workspace_id(cx);
+        let workspace_ids = self
+            .recent_workspaces
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| Some(*id) != current_workspace_id)
+            .collect::<Vec<_>>();
+        self.recent_workspaces
+            .retain(|(id, _)| Some(*id) == current_workspace_id);
+        cx.background_executor()
+            .spawn(async move {
+                for workspace_id in workspace_ids {
+                    WORKSPACE_DB
+                        .delete_workspace_by_id(workspace_id)
+                        .await
+                        .log_err();
+                }
+            })
+            .detach();
+        cx.notify();
+    }
+
+    fn current_workspace_id(&self, cx: &AppContext) -> Option<WorkspaceId> {
+        self.workspace.as_ref()?.upgrade()?.read(cx).database_id()
+    }
+
+    /// Recent local projects, excluding the one open in this window, paired
+    /// with the label shown for them in the menu.
+    fn recent_projects(&self, cx: &AppContext) -> Vec<(SharedString, Vec<PathBuf>)> {
+        let current_workspace_id = self.current_workspace_id(cx);
+        self.recent_workspaces
+            .iter()
+            .filter(|(id, _)| Some(*id) != current_workspace_id)
+            .take(MAX_RECENT_PROJECTS)
+            .map(|(_, paths)| {
+                let label = paths
+                    .iter()
+                    .map(|path| path.compact().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (label.into(), paths.clone())
+            })
+            .collect()
     }
 }
 
+fn active_editor(workspace: Option<&WeakView<Workspace>>, cx: &AppContext) -> Option<View<Editor>> {
+    workspace?.upgrade()?.read(cx).active_item_as::<Editor>(cx)
+}
+
 impl Render for ApplicationMenu {
-    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let workspace = self.workspace.clone();
+        let recent_projects = self.recent_projects(cx);
+        let this = cx.view().downgrade();
         PopoverMenu::new("application-menu")
             .menu(move |cx| {
+                let active_editor = active_editor(workspace.as_ref(), cx);
+                let recent_projects = recent_projects.clone();
+                let workspace = workspace.clone();
+                let this = this.clone();
+                // Pick up projects opened or removed since the menu was last shown.
+                this.update(cx, |this, cx| this.refresh_recent_workspaces(cx))
+                    .ok();
                 ContextMenu::build(cx, move |menu, cx| {
-                    menu.header("工作区")
+                    // Keybindings are shown for the context actions are dispatched in,
+                    // falling back to the workspace when nothing is focused.
+                    let action_context = cx.focused().or_else(|| {
+                        let workspace = workspace.as_ref()?.upgrade()?;
+                        Some(workspace.focus_handle(cx))
+                    });
+                    menu.rtl(i18n::is_rtl())
+                        .when_some(action_context, |menu, focused| menu.context(focused))
+                        .header(t!("application_menu.workspace"))
                         .action(
-                            "打开命令面板",
+                            t!("application_menu.open_command_palette"),
                             Box::new(zed_actions::command_palette::Toggle),
                         )
-                        .when_some(cx.focused(), |menu, focused| menu.context(focused))
                         .custom_row(move |cx| {
                             h_flex()
                                 .gap_2()
                                 .w_full()
                                 .justify_between()
                                 .cursor(gpui::CursorStyle::Arrow)
-                                .child(Label::new("编辑器字体大小"))
+                                .child(Label::new(t!("application_menu.buffer_font_size")))
                                 .child(
                                     NumericStepper::new(
                                         "buffer-font-size",
@@ -65,7 +182,7 @@ impl Render for ApplicationMenu {
                                 .w_full()
                                 .justify_between()
                                 .cursor(gpui::CursorStyle::Arrow)
-                                .child(Label::new("界面字体大小"))
+                                .child(Label::new(t!("application_menu.ui_font_size")))
                                 .child(
                                     NumericStepper::new(
                                         "ui-font-size",
@@ -95,39 +212,237 @@ impl Render for ApplicationMenu {
                                 )
                                 .into_any_element()
                         })
-                        .header("项目")
+                        .custom_row(move |cx| {
+                            h_flex()
+                                .gap_2()
+                                .w_full()
+                                .justify_between()
+                                .cursor(gpui::CursorStyle::Arrow)
+                                .child(Label::new(t!("application_menu.terminal_font_size")))
+                                .child(
+                                    NumericStepper::new(
+                                        "terminal-font-size",
+                                        get_terminal_font_size(cx).to_string(),
+                                        |_, cx| {
+                                            cx.dispatch_action(Box::new(
+                                                zed_actions::DecreaseTerminalFontSize,
+                                            ))
+                                        },
+                                        |_, cx| {
+                                            cx.dispatch_action(Box::new(
+                                                zed_actions::IncreaseTerminalFontSize,
+                                            ))
+                                        },
+                                    )
+                                    .reserve_space_for_reset(true)
+                                    .when(
+                                        has_adjusted_terminal_font_size(cx),
+                                        |stepper| {
+                                            stepper.on_reset(|_, cx| {
+                                                cx.dispatch_action(Box::new(
+                                                    zed_actions::ResetTerminalFontSize,
+                                                ))
+                                            })
+                                        },
+                                    ),
+                                )
+                                .into_any_element()
+                        })
+                        .when_some(active_editor, |menu, editor| {
+                            menu.custom_row(move |cx| {
+                                let tab_size = editor.read(cx).tab_size(cx);
+                                let has_adjusted_tab_size =
+                                    editor.read(cx).has_adjusted_tab_size(cx);
+                                h_flex()
+                                    .gap_2()
+                                    .w_full()
+                                    .justify_between()
+                                    .cursor(gpui::CursorStyle::Arrow)
+                                    .child(Label::new(t!("application_menu.tab_size")))
+                                    .child(
+                                        NumericStepper::new(
+                                            "tab-size",
+                                            tab_size.to_string(),
+                                            {
+                                                let editor = editor.clone();
+                                                move |_, cx| {
+                                                    editor.update(cx, |editor, cx| {
+                                                        editor
+                                                            .decrease_tab_size(&DecreaseTabSize, cx)
+                                                    })
+                                                }
+                                            },
+                                            {
+                                                let editor = editor.clone();
+                                                move |_, cx| {
+                                                    editor.update(cx, |editor, cx| {
+                                                        editor
+                                                            .increase_tab_size(&IncreaseTabSize, cx)
+                                                    })
+                                                }
+                                            },
+                                        )
+                                        .reserve_space_for_reset(true)
+                                        .when(
+                                            has_adjusted_tab_size,
+                                            |stepper| {
+                                                let editor = editor.clone();
+                                                stepper.on_reset(move |_, cx| {
+                                                    editor.update(cx, |editor, cx| {
+                                                        editor.reset_tab_size(&ResetTabSize, cx)
+                                                    })
+                                                })
+                                            },
+                                        ),
+                                    )
+                                    .into_any_element()
+                            })
+                        })
+                        .custom_row(move |cx| {
+                            let line_numbers = LineNumbersControl::read(cx);
+                            h_flex()
+                                .gap_2()
+                                .w_full()
+                                .justify_between()
+                                .cursor(gpui::CursorStyle::Arrow)
+                                .child(Label::new(t!("application_menu.line_numbers")))
+                                .child(Checkbox::new("line-numbers", line_numbers.into()).on_click(
+                                    |selection, cx| {
+                                        LineNumbersControl::write(
+                                            *selection == Selection::Selected,
+                                            cx,
+                                        );
+                                    },
+                                ))
+                                .into_any_element()
+                        })
+                        .custom_row(move |cx| {
+                            let is_dark = !cx.theme().appearance().is_light();
+                            h_flex()
+                                .gap_2()
+                                .w_full()
+                                .justify_between()
+                                .cursor(gpui::CursorStyle::Arrow)
+                                .child(Label::new(t!("application_menu.dark_theme")))
+                                .child(Checkbox::new("dark-theme", is_dark.into()).on_click(
+                                    |_, cx| {
+                                        cx.dispatch_action(Box::new(
+                                            zed_actions::theme_selector::ToggleMode,
+                                        ))
+                                    },
+                                ))
+                                .into_any_element()
+                        })
+                        .custom_row(move |cx| {
+                            let vim_mode_enabled = VimModeSetting::get_global(cx).0;
+                            h_flex()
+                                .gap_2()
+                                .w_full()
+                                .justify_between()
+                                .cursor(gpui::CursorStyle::Arrow)
+                                .child(Label::new(t!("application_menu.vim_mode")))
+                                .child(Checkbox::new("vim-mode", vim_mode_enabled.into()).on_click(
+                                    |selection, cx| {
+                                        let enabled = *selection == Selection::Selected;
+                                        update_settings_file::<VimModeSetting>(
+                                            <dyn Fs>::global(cx),
+                                            cx,
+                                            move |setting, _| *setting = Some(enabled),
+                                        );
+                                    },
+                                ))
+                                .into_any_element()
+                        })
+                        .header(t!("application_menu.base_keymap"))
+                        .map(|mut menu| {
+                            let active_keymap = *BaseKeymap::get_global(cx);
+                            for (name, keymap) in BaseKeymap::OPTIONS {
+                                menu = menu.toggleable_entry(
+                                    name,
+                                    keymap == active_keymap,
+                                    IconPosition::Start,
+                                    None,
+                                    move |cx| {
+                                        update_settings_file::<BaseKeymap>(
+                                            <dyn Fs>::global(cx),
+                                            cx,
+                                            move |setting, _| *setting = Some(keymap),
+                                        );
+                                    },
+                                );
+                            }
+                            menu
+                        })
+                        .header(t!("application_menu.project"))
                         .action(
-                            "添加文件夹到项目...",
+                            t!("application_menu.add_folder_to_project"),
                             Box::new(workspace::AddFolderToProject),
                         )
-                        .action("打开新项目...", Box::new(workspace::Open))
                         .action(
-                            "打开最近项目...",
+                            t!("application_menu.open_new_project"),
+                            Box::new(workspace::Open),
+                        )
+                        .header(t!("application_menu.recent_projects"))
+                        .map(|mut menu| {
+                            if recent_projects.is_empty() {
+                                return menu.label(t!("application_menu.no_recent_projects"));
+                            }
+                            for (label, paths) in recent_projects {
+                                let workspace = workspace.clone();
+                                menu = menu.entry(label, None, move |cx| {
+                                    if let Some(workspace) =
+                                        workspace.as_ref().and_then(|workspace| workspace.upgrade())
+                                    {
+                                        workspace.update(cx, |workspace, cx| {
+                                            workspace
+                                                .open_workspace_for_paths(false, paths.clone(), cx)
+                                                .detach_and_log_err(cx)
+                                        });
+                                    }
+                                });
+                            }
+                            menu.entry(
+                                t!("application_menu.clear_recent_projects"),
+                                None,
+                                move |cx| {
+                                    this.update(cx, |this, cx| this.clear_recent_workspaces(cx))
+                                        .ok();
+                                },
+                            )
+                        })
+                        .action(
+                            t!("application_menu.more_recent_projects"),
                             Box::new(zed_actions::OpenRecent {
                                 create_new_window: false,
                             }),
                         )
-                        .header("帮助")
-                        .action("关于Zed", Box::new(zed_actions::About))
-                        .action("欢迎页", Box::new(workspace::Welcome))
+                        .header(t!("application_menu.help"))
+                        .action(t!("application_menu.about"), Box::new(zed_actions::About))
+                        .action(t!("application_menu.welcome"), Box::new(workspace::Welcome))
                         .link(
-                            "文档",
+                            t!("application_menu.documentation"),
                             Box::new(zed_actions::OpenBrowser {
                                 url: "https://zed.dev/docs".into(),
                             }),
                         )
                         .action(
-                            "提供反馈",
+                            t!("application_menu.give_feedback"),
                             Box::new(zed_actions::feedback::GiveFeedback),
                         )
-                        .action("检查升级", Box::new(auto_update::Check))
-                        .action("查看遥测数据", Box::new(zed_actions::OpenTelemetryLog))
                         .action(
-                            "查看依赖项许可证",
+                            t!("application_menu.check_for_updates"),
+                            Box::new(auto_update::Check),
+                        )
+                        .action(
+                            t!("application_menu.view_telemetry"),
+                            Box::new(zed_actions::OpenTelemetryLog),
+                        )
+                        .action(
+                            t!("application_menu.view_dependency_licenses"),
                             Box::new(zed_actions::OpenLicenses),
                         )
                         .separator()
-                        .action("退出", Box::new(zed_actions::Quit))
+                        .action(t!("application_menu.quit"), Box::new(zed_actions::Quit))
                 })
                 .into()
             })
@@ -136,9 +451,10 @@ impl Render for ApplicationMenu {
                     .style(ButtonStyle::Subtle)
                     .icon_size(IconSize::Small)
                     .when(!self.context_menu_handle.is_deployed(), |this| {
-                        this.tooltip(|cx| Tooltip::text("打开应用菜单", cx))
+                        this.tooltip(|cx| Tooltip::text(t!("application_menu.tooltip"), cx))
                     }),
             )
+            .when(i18n::is_rtl(), |this| this.anchor(AnchorCorner::TopRight))
             .with_handle(self.context_menu_handle.clone())
             .into_any_element()
     }
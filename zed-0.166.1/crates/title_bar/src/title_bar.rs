@@ -13,9 +13,10 @@ use call::ActiveCall;
 use client::{Client, UserStore};
 use feature_flags::{FeatureFlagAppExt, ZedPro};
 use gpui::{
-    actions, div, px, Action, AnyElement, AppContext, Decorations, Element, InteractiveElement,
-    Interactivity, IntoElement, Model, MouseButton, ParentElement, Render, Stateful,
-    StatefulInteractiveElement, Styled, Subscription, View, ViewContext, VisualContext, WeakView,
+    actions, div, px, Action, AnyElement, AnyView, AppContext, Decorations, Element,
+    InteractiveElement, Interactivity, IntoElement, Model, MouseButton, ParentElement, Render,
+    Stateful, StatefulInteractiveElement, Styled, Subscription, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
 };
 use project::{Project, RepositoryEntry};
 use rpc::proto;
@@ -28,7 +29,7 @@ use ui::{
     IconSize, IconWithIndicator, Indicator, PopoverMenu, Tooltip,
 };
 use util::ResultExt;
-use workspace::{notifications::NotifyResultExt, Workspace};
+use workspace::{item::ItemHandle, notifications::NotifyResultExt, StatusItemView, Workspace};
 use zed_actions::{OpenBrowser, OpenRecent, OpenRemote};
 
 #[cfg(feature = "stories")]
@@ -68,6 +69,8 @@ pub struct TitleBar {
     workspace: WeakView<Workspace>,
     should_move: bool,
     application_menu: Option<View<ApplicationMenu>>,
+    status_items: Vec<Box<dyn TitleBarItemHandle>>,
+    active_item: Option<Box<dyn ItemHandle>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -138,6 +141,7 @@ impl Render for TitleBar {
                             .children(self.render_project_host(cx))
                             .child(self.render_project_name(cx))
                             .children(self.render_project_branch(cx))
+                            .children(self.status_items.iter().map(|item| item.to_any()))
                             .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation()),
                     )
                     .child(self.render_collaborator_list(cx))
@@ -218,7 +222,8 @@ impl TitleBar {
         let application_menu = match platform_style {
             PlatformStyle::Mac => None,
             PlatformStyle::Linux | PlatformStyle::Windows => {
-                Some(cx.new_view(ApplicationMenu::new))
+                let workspace = workspace.weak_handle();
+                Some(cx.new_view(|cx| ApplicationMenu::new(Some(workspace), cx)))
             }
         };
 
@@ -232,12 +237,25 @@ impl TitleBar {
         subscriptions.push(cx.observe(&active_call, |this, _, cx| this.active_call_changed(cx)));
         subscriptions.push(cx.observe_window_activation(Self::window_activation_changed));
         subscriptions.push(cx.observe(&user_store, |_, _, cx| cx.notify()));
+        subscriptions.push(cx.subscribe(
+            &workspace.weak_handle().upgrade().unwrap(),
+            |this, workspace, event, cx| {
+                if let workspace::Event::ActiveItemChanged = event {
+                    this.active_item = workspace.read(cx).active_item(cx);
+                    for item in &this.status_items {
+                        item.set_active_pane_item(this.active_item.as_deref(), cx);
+                    }
+                }
+            },
+        ));
 
         Self {
             platform_style,
             content: div().id(id.into()),
             children: SmallVec::new(),
             application_menu,
+            status_items: Vec::new(),
+            active_item: workspace.active_item(cx),
             workspace: workspace.weak_handle(),
             should_move: false,
             project,
@@ -247,6 +265,17 @@ impl TitleBar {
         }
     }
 
+    /// Shows `item` after the project's name and branch. Like the items of the
+    /// status bar, it's told whenever the workspace's active item changes.
+    pub fn add_status_item<T>(&mut self, item: View<T>, cx: &mut ViewContext<Self>)
+    where
+        T: 'static + StatusItemView,
+    {
+        item.set_active_pane_item(self.active_item.as_deref(), cx);
+        self.status_items.push(Box::new(item));
+        cx.notify();
+    }
+
     #[cfg(not(target_os = "windows"))]
     pub fn height(cx: &mut WindowContext) -> Pixels {
         (1.75 * cx.rem_size()).max(px(34.))
@@ -667,3 +696,28 @@ impl ParentElement for TitleBar {
         self.children.extend(elements)
     }
 }
+
+trait TitleBarItemHandle {
+    fn to_any(&self) -> AnyView;
+    fn set_active_pane_item(
+        &self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut WindowContext,
+    );
+}
+
+impl<T: StatusItemView> TitleBarItemHandle for View<T> {
+    fn to_any(&self) -> AnyView {
+        self.clone().into()
+    }
+
+    fn set_active_pane_item(
+        &self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut WindowContext,
+    ) {
+        self.update(cx, |this, cx| {
+            this.set_active_pane_item(active_pane_item, cx)
+        });
+    }
+}
@@ -39,6 +39,7 @@ pub struct ContextMenu {
     selected_index: Option<usize>,
     delayed: bool,
     clicked: bool,
+    rtl: bool,
     _on_blur_subscription: Subscription,
 }
 
@@ -71,6 +72,7 @@ impl ContextMenu {
                     selected_index: None,
                     delayed: false,
                     clicked: false,
+                    rtl: false,
                     _on_blur_subscription,
                 },
                 cx,
@@ -83,6 +85,13 @@ impl ContextMenu {
         self
     }
 
+    /// Mirrors the horizontal layout of the menu's items, for locales that are
+    /// written right to left.
+    pub fn rtl(mut self, rtl: bool) -> Self {
+        self.rtl = rtl;
+        self
+    }
+
     pub fn header(mut self, title: impl Into<SharedString>) -> Self {
         self.items.push(ContextMenuItem::Header(title.into()));
         self
@@ -217,6 +226,44 @@ impl ContextMenu {
         self
     }
 
+    /// Removes the entries that dispatch any of the named actions, along with
+    /// the separators that would be left doubled up or at either end.
+    pub fn hide_actions(mut self, action_names: &[String]) -> Self {
+        if action_names.is_empty() {
+            return self;
+        }
+        self.items.retain(|item| match item {
+            ContextMenuItem::Entry {
+                action: Some(action),
+                ..
+            } => !action_names.iter().any(|name| name == action.name()),
+            _ => true,
+        });
+
+        let mut after_separator = true;
+        self.items.retain(|item| {
+            let is_separator = matches!(item, ContextMenuItem::Separator);
+            let keep = !(is_separator && after_separator);
+            after_separator = is_separator;
+            keep
+        });
+        if let Some(ContextMenuItem::Separator) = self.items.last() {
+            self.items.pop();
+        }
+        self
+    }
+
+    /// Returns the labels of the menu's entries, in display order.
+    pub fn entry_labels(&self) -> Vec<SharedString> {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                ContextMenuItem::Entry { label, .. } => Some(label.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
         let context = self.action_context.as_ref();
         if let Some(
@@ -355,6 +402,7 @@ impl ContextMenuItem {
 impl Render for ContextMenu {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size;
+        let rtl = self.rtl;
 
         div().occlude().elevation_2(cx).flex().flex_row().child(
             WithRemSize::new(ui_font_size).flex().child(
@@ -401,7 +449,12 @@ impl Render for ContextMenu {
                                 ContextMenuItem::Label(label) => ListItem::new(ix)
                                     .inset(true)
                                     .disabled(true)
-                                    .child(Label::new(label.clone()))
+                                    .child(
+                                        h_flex()
+                                            .w_full()
+                                            .when(rtl, |this| this.justify_end())
+                                            .child(Label::new(label.clone())),
+                                    )
                                     .into_any_element(),
                                 ContextMenuItem::Entry {
                                     toggle,
@@ -422,6 +475,7 @@ impl Render for ContextMenu {
                                     let label_element = if let Some(icon_name) = icon {
                                         h_flex()
                                             .gap_1()
+                                            .when(rtl, |this| this.flex_row_reverse())
                                             .child(Label::new(label.clone()).color(color))
                                             .child(
                                                 Icon::new(*icon_name).size(*icon_size).color(color),
@@ -445,6 +499,11 @@ impl Render for ContextMenu {
                                                     .flex_none()
                                                     .size(IconSize::default().rems())
                                             };
+                                            let position = match (position, rtl) {
+                                                (IconPosition::Start, true) => IconPosition::End,
+                                                (IconPosition::End, true) => IconPosition::Start,
+                                                (position, false) => position,
+                                            };
                                             match position {
                                                 IconPosition::Start => {
                                                     list_item.start_slot(contents)
@@ -456,6 +515,7 @@ impl Render for ContextMenu {
                                             h_flex()
                                                 .w_full()
                                                 .justify_between()
+                                                .when(rtl, |this| this.flex_row_reverse())
                                                 .child(label_element)
                                                 .debug_selector(|| format!("MENU_ITEM-{}", label))
                                                 .children(action.as_ref().and_then(|action| {
@@ -469,7 +529,17 @@ impl Render for ContextMenu {
                                                         .unwrap_or_else(|| {
                                                             KeyBinding::for_action(&**action, cx)
                                                         })
-                                                        .map(|binding| div().ml_4().child(binding))
+                                                        .map(|binding| {
+                                                            div()
+                                                                .map(|this| {
+                                                                    if rtl {
+                                                                        this.mr_4()
+                                                                    } else {
+                                                                        this.ml_4()
+                                                                    }
+                                                                })
+                                                                .child(binding)
+                                                        })
                                                 })),
                                         )
                                         .on_click({
@@ -157,12 +157,20 @@ actions!(
         ToggleCenteredLayout,
         ToggleLeftDock,
         ToggleRightDock,
+        ToggleZenMode,
         ToggleZoom,
         Unfollow,
         Welcome,
     ]
 );
 
+/// The layout from before zen mode was turned on.
+struct ZenMode {
+    /// Whether the left, bottom and right docks were open, in that order.
+    open_docks: [bool; 3],
+    centered_layout: bool,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct OpenPaths {
     pub paths: Vec<PathBuf>,
@@ -226,8 +234,10 @@ impl_actions!(
         ActivatePaneInDirection,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
+        NewFileWithLanguage,
         OpenTerminal,
         Reload,
+        RunInTerminal,
         Save,
         SaveAll,
         SwapPaneInDirection,
@@ -291,6 +301,21 @@ pub struct OpenTerminal {
     pub working_directory: PathBuf,
 }
 
+/// Opens a new untitled buffer in `language`, or in a language picked from a
+/// list if none is given.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NewFileWithLanguage {
+    pub language: Option<String>,
+}
+
+/// Runs `text` in a terminal in `working_directory`, reusing an existing one if possible.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct RunInTerminal {
+    pub working_directory: PathBuf,
+    pub text: String,
+}
+
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct WorkspaceId(i64);
 
@@ -760,6 +785,8 @@ pub struct Workspace {
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    /// The layout to restore on leaving zen mode, while it's on.
+    zen_mode: Option<ZenMode>,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
@@ -1065,6 +1092,7 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            zen_mode: None,
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
@@ -4473,6 +4501,7 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::toggle_centered_layout))
+            .on_action(cx.listener(Workspace::toggle_zen_mode))
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4549,6 +4578,32 @@ impl Workspace {
         cx.notify();
     }
 
+    /// Hides the docks and centers the editor, or restores the layout from
+    /// before if zen mode is already on.
+    pub fn toggle_zen_mode(&mut self, _: &ToggleZenMode, cx: &mut ViewContext<Self>) {
+        let docks = [&self.left_dock, &self.bottom_dock, &self.right_dock];
+        match self.zen_mode.take() {
+            Some(previous) => {
+                for (dock, was_open) in docks.into_iter().zip(previous.open_docks) {
+                    dock.update(cx, |dock, cx| dock.set_open(was_open, cx));
+                }
+                self.centered_layout = previous.centered_layout;
+            }
+            None => {
+                self.zen_mode = Some(ZenMode {
+                    open_docks: docks.map(|dock| dock.read(cx).is_open()),
+                    centered_layout: self.centered_layout,
+                });
+                for dock in docks {
+                    dock.update(cx, |dock, cx| dock.set_open(false, cx));
+                }
+                self.centered_layout = true;
+                cx.focus_self();
+            }
+        }
+        cx.notify();
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -6816,6 +6871,40 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_toggle_zen_mode(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        workspace.update(cx, |workspace, cx| {
+            let left_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Left, cx));
+            workspace.add_panel(left_panel, cx);
+            let right_panel = cx.new_view(|cx| TestPanel::new(DockPosition::Right, cx));
+            workspace.add_panel(right_panel, cx);
+            workspace
+                .left_dock()
+                .update(cx, |dock, cx| dock.set_open(true, cx));
+        });
+
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(!workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(workspace.centered_layout);
+        });
+
+        // Leaving zen mode only reopens the docks that were open before.
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_zen_mode(&ToggleZenMode, cx);
+            assert!(workspace.left_dock().read(cx).is_open());
+            assert!(!workspace.right_dock().read(cx).is_open());
+            assert!(!workspace.centered_layout);
+        });
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
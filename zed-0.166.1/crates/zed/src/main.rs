@@ -254,6 +254,7 @@ fn main() {
         handle_settings_file_changes(user_settings_file_rx, cx, handle_settings_changed);
         handle_keymap_file_changes(user_keymap_file_rx, cx, handle_keymap_changed);
         client::init_settings(cx);
+        i18n::init(fs.clone(), cx);
         let user_agent = format!(
             "Zed/{} ({}; {})",
             AppVersion::global(cx),
@@ -501,7 +502,7 @@ fn main() {
         watch_languages(fs.clone(), app_state.languages.clone(), cx);
         watch_file_types(fs.clone(), cx);
 
-        cx.set_menus(app_menus());
+        cx.set_menus(app_menus(cx));
         initialize_workspace(app_state.clone(), prompt_builder, cx);
 
         cx.activate(true);
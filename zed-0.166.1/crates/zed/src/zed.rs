@@ -22,8 +22,8 @@ use editor::{scroll::Autoscroll, Editor, MultiBuffer};
 use feature_flags::FeatureFlagAppExt;
 use futures::{channel::mpsc, select_biased, StreamExt};
 use gpui::{
-    actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, MenuItem,
-    PathPromptOptions, PromptLevel, ReadGlobal, Task, TitlebarOptions, View, ViewContext,
+    actions, impl_actions, point, px, AppContext, AsyncAppContext, Context, FocusableView,
+    MenuItem, PathPromptOptions, PromptLevel, ReadGlobal, Task, TitlebarOptions, View, ViewContext,
     VisualContext, WindowKind, WindowOptions,
 };
 pub use open_listener::*;
@@ -31,11 +31,12 @@ use outline_panel::OutlinePanel;
 use paths::{local_settings_file_relative_path, local_tasks_file_relative_path};
 use project::{DirectoryLister, ProjectItem};
 use project_panel::ProjectPanel;
-use quick_action_bar::QuickActionBar;
+use quick_action_bar::{QuickActionBar, ReplStatus};
 use recent_projects::open_ssh_project;
 use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
+use serde::Deserialize;
 use settings::{
     initial_project_settings_content, initial_tasks_content, KeymapFile, Settings, SettingsStore,
     DEFAULT_KEYMAP_PATH,
@@ -45,6 +46,7 @@ use std::path::PathBuf;
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::ActiveTheme;
+use title_bar::TitleBar;
 use util::{asset_str, ResultExt};
 use uuid::Uuid;
 use vim_mode_setting::VimModeSetting;
@@ -79,6 +81,13 @@ actions!(
     ]
 );
 
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct ActivateWindow {
+    pub window_id: u64,
+}
+
+impl_actions!(zed, [ActivateWindow]);
+
 pub fn init(cx: &mut AppContext) {
     #[cfg(target_os = "macos")]
     cx.on_action(|_: &Hide, cx| cx.hide());
@@ -87,6 +96,11 @@ pub fn init(cx: &mut AppContext) {
     #[cfg(target_os = "macos")]
     cx.on_action(|_: &ShowAll, cx| cx.unhide_other_apps());
     cx.on_action(quit);
+    cx.on_action(activate_window);
+    cx.observe_global::<i18n::ActiveLocale>(refresh_app_menus)
+        .detach();
+    cx.observe_global::<command_palette::RecentCommands>(refresh_app_menus)
+        .detach();
 
     if ReleaseChannel::global(cx) == ReleaseChannel::Dev {
         cx.on_action(test_panic);
@@ -221,8 +235,21 @@ pub fn initialize_workspace(
             status_bar.add_right_item(cursor_position, cx);
         });
 
+        if let Some(title_bar) = workspace
+            .titlebar_item()
+            .and_then(|item| item.downcast::<TitleBar>().ok())
+        {
+            let repl_status = cx.new_view(ReplStatus::new);
+            title_bar.update(cx, |title_bar, cx| title_bar.add_status_item(repl_status, cx));
+        }
+
         auto_update_ui::notify_of_any_new_update(cx);
 
+        refresh_app_menus(cx);
+        cx.observe_window_activation(|_, cx| refresh_app_menus(cx))
+            .detach();
+        cx.on_release(|_, _, cx| refresh_app_menus(cx)).detach();
+
         let handle = cx.view().downgrade();
         cx.on_window_should_close(move |cx| {
 
@@ -251,6 +278,10 @@ pub fn initialize_workspace(
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let cell_outline_panel =
+                repl::CellOutlinePanel::load(workspace_handle.clone(), cx.clone());
+            let repl_output_panel =
+                repl::ReplOutputPanel::load(workspace_handle.clone(), cx.clone());
 
             let (
                 project_panel,
@@ -259,6 +290,8 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                cell_outline_panel,
+                repl_output_panel,
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
@@ -266,6 +299,8 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                cell_outline_panel,
+                repl_output_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -275,6 +310,8 @@ pub fn initialize_workspace(
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(cell_outline_panel, cx);
+                workspace.add_panel(repl_output_panel, cx);
             })?;
             let is_assistant2_enabled =
                 if cfg!(test) || release_channel != ReleaseChannel::Dev {
@@ -557,6 +594,20 @@ pub fn initialize_workspace(
                         .toggle_panel_focus::<collab_ui::notification_panel::NotificationPanel>(cx);
                 },
             )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &repl::cell_outline_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<repl::CellOutlinePanel>(cx);
+                },
+            )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &repl::output_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<repl::ReplOutputPanel>(cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &terminal_panel::ToggleFocus,
@@ -885,12 +936,32 @@ pub fn handle_keymap_file_changes(
     .detach();
 }
 
+fn activate_window(action: &ActivateWindow, cx: &mut AppContext) {
+    let Some(window) = cx
+        .windows()
+        .into_iter()
+        .find(|window| window.window_id().as_u64() == action.window_id)
+    else {
+        return;
+    };
+    window.update(cx, |_, cx| cx.activate_window()).log_err();
+}
+
+/// Rebuilds the application menus on the next tick, once no window is being
+/// updated, so that the "Window" menu reflects the current set of windows.
+pub fn refresh_app_menus(cx: &mut AppContext) {
+    cx.defer(|cx| cx.set_menus(app_menus(cx)));
+}
+
 fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
     keymap_content.clone().add_to_cx(cx).log_err();
-    cx.set_menus(app_menus());
-    cx.set_dock_menu(vec![MenuItem::action("New Window", workspace::NewWindow)]);
+    cx.set_menus(app_menus(cx));
+    cx.set_dock_menu(vec![MenuItem::action(
+        i18n::t!("menu.file.new_window"),
+        workspace::NewWindow,
+    )]);
 }
 
 pub fn load_default_keymap(cx: &mut AppContext) {
@@ -1165,7 +1236,8 @@ mod tests {
     use editor::{display_map::DisplayRow, scroll::Autoscroll, DisplayPoint, Editor};
     use gpui::{
         actions, Action, AnyWindowHandle, AppContext, AssetSource, BorrowAppContext, Entity,
-        SemanticVersion, TestAppContext, UpdateGlobal, VisualTestContext, WindowHandle,
+        SemanticVersion, SharedString, TestAppContext, UpdateGlobal, VisualTestContext,
+        WindowHandle,
     };
     use language::{LanguageMatcher, LanguageRegistry};
     use project::{project_settings::ProjectSettings, Project, ProjectPath, WorktreeSettings};
@@ -3410,6 +3482,56 @@ mod tests {
         assert!(has_default_theme);
     }
 
+    #[gpui::test]
+    fn test_app_menus_are_translated(cx: &mut AppContext) {
+        fn collect_untranslated(
+            items: &[MenuItem],
+            translations: &HashSet<SharedString>,
+            untranslated: &mut Vec<SharedString>,
+        ) {
+            for item in items {
+                match item {
+                    MenuItem::Separator => {}
+                    MenuItem::Submenu(menu) => {
+                        // The platform finds the services menu by this name.
+                        if menu.name != "Services" && !translations.contains(&menu.name) {
+                            untranslated.push(menu.name.clone());
+                        }
+                        collect_untranslated(&menu.items, translations, untranslated);
+                    }
+                    MenuItem::Action { name, .. } => {
+                        if !translations.contains(name) {
+                            untranslated.push(name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        // A label is translated if it's the translation of a key that differs
+        // from the key's English text, so English left in the table is caught.
+        // Proper names read the same in both.
+        let same_in_english = ["menu.help.twitter"];
+        let english = i18n::bundled_translations(i18n::FALLBACK_LOCALE).unwrap();
+        let translations = i18n::bundled_translations(i18n::DEFAULT_LOCALE)
+            .unwrap()
+            .into_iter()
+            .filter(|(key, translation)| {
+                english.get(key) != Some(translation) || same_in_english.contains(&key.as_str())
+            })
+            .map(|(_, translation)| translation)
+            .collect::<HashSet<_>>();
+        let mut untranslated = Vec::new();
+        // Top-level menus keep their English names, which the platform relies on.
+        for menu in app_menus(cx) {
+            collect_untranslated(&menu.items, &translations, &mut untranslated);
+        }
+        assert!(
+            untranslated.is_empty(),
+            "menu items without a translation: {untranslated:?}"
+        );
+    }
+
     #[gpui::test]
     async fn test_bundled_languages(cx: &mut TestAppContext) {
         env_logger::builder().is_test(true).try_init().ok();
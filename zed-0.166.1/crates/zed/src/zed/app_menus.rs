@@ -1,30 +1,44 @@
 use collab_ui::collab_panel;
-use gpui::{Menu, MenuItem, OsAction};
+use gpui::{AppContext, Menu, MenuItem, OsAction};
+use i18n::t;
 use terminal_view::terminal_panel;
+use workspace::Workspace;
 
-pub fn app_menus() -> Vec<Menu> {
+pub fn app_menus(cx: &AppContext) -> Vec<Menu> {
     use zed_actions::Quit;
 
     vec![
         Menu {
             name: "Zed".into(),
             items: vec![
-                MenuItem::action("关于Zed…", zed_actions::About),
-                MenuItem::action("检查更新", auto_update::Check),
+                MenuItem::action(t!("menu.zed.about"), zed_actions::About),
+                MenuItem::action(t!("menu.zed.check_for_updates"), auto_update::Check),
                 MenuItem::separator(),
                 MenuItem::submenu(Menu {
-                    name: "Settings".into(),
+                    name: t!("menu.zed.settings"),
                     items: vec![
-                        MenuItem::action("打开设置", super::OpenSettings),
-                        MenuItem::action("打开按键绑定", zed_actions::OpenKeymap),
-                        MenuItem::action("打开默认设置", super::OpenDefaultSettings),
                         MenuItem::action(
-                            "打开默认按键绑定",
+                            t!("menu.zed.settings.open_settings"),
+                            super::OpenSettings,
+                        ),
+                        MenuItem::action(
+                            t!("menu.zed.settings.open_key_bindings"),
+                            zed_actions::OpenKeymap,
+                        ),
+                        MenuItem::action(
+                            t!("menu.zed.settings.open_default_settings"),
+                            super::OpenDefaultSettings,
+                        ),
+                        MenuItem::action(
+                            t!("menu.zed.settings.open_default_key_bindings"),
                             zed_actions::OpenDefaultKeymap,
                         ),
-                        MenuItem::action("打开项目设置", super::OpenProjectSettings),
                         MenuItem::action(
-                            "选择主题...",
+                            t!("menu.zed.settings.open_project_settings"),
+                            super::OpenProjectSettings,
+                        ),
+                        MenuItem::action(
+                            t!("menu.zed.settings.select_theme"),
                             zed_actions::theme_selector::Toggle::default(),
                         ),
                     ],
@@ -35,166 +49,398 @@ pub fn app_menus() -> Vec<Menu> {
                     items: vec![],
                 }),
                 MenuItem::separator(),
-                MenuItem::action("扩展", zed_actions::Extensions),
-                MenuItem::action("安装CLI", install_cli::Install),
+                MenuItem::action(t!("menu.zed.extensions"), zed_actions::Extensions),
+                MenuItem::action(t!("menu.zed.install_cli"), install_cli::Install),
                 MenuItem::separator(),
-                MenuItem::action("隐藏Zed", super::Hide),
-                MenuItem::action("隐藏其他", super::HideOthers),
-                MenuItem::action("显示所有", super::ShowAll),
-                MenuItem::action("退出", Quit),
+                MenuItem::action(t!("menu.zed.hide"), super::Hide),
+                MenuItem::action(t!("menu.zed.hide_others"), super::HideOthers),
+                MenuItem::action(t!("menu.zed.show_all"), super::ShowAll),
+                MenuItem::action(t!("menu.zed.quit"), Quit),
             ],
         },
         Menu {
             name: "File".into(),
             items: vec![
-                MenuItem::action("新建", workspace::NewFile),
-                MenuItem::action("新建窗口", workspace::NewWindow),
+                MenuItem::action(t!("menu.file.new"), workspace::NewFile),
+                MenuItem::action(
+                    t!("menu.file.new_with_language"),
+                    workspace::NewFileWithLanguage::default(),
+                ),
+                MenuItem::action(t!("menu.file.new_window"), workspace::NewWindow),
                 MenuItem::separator(),
-                MenuItem::action("打开…", workspace::Open),
+                MenuItem::action(t!("menu.file.open"), workspace::Open),
                 MenuItem::action(
-                    "打开最近...",
+                    t!("menu.file.open_recent"),
                     zed_actions::OpenRecent {
                         create_new_window: true,
                     },
                 ),
                 MenuItem::separator(),
-                MenuItem::action("添加文件夹到项目…", workspace::AddFolderToProject),
-                MenuItem::action("保存", workspace::Save { save_intent: None }),
-                MenuItem::action("另存为…", workspace::SaveAs),
-                MenuItem::action("保存全部", workspace::SaveAll { save_intent: None }),
                 MenuItem::action(
-                    "关闭编辑器",
+                    t!("menu.file.add_folder_to_project"),
+                    workspace::AddFolderToProject,
+                ),
+                MenuItem::action(t!("menu.file.save"), workspace::Save { save_intent: None }),
+                MenuItem::action(
+                    t!("menu.file.save_without_format"),
+                    workspace::SaveWithoutFormat,
+                ),
+                MenuItem::action(t!("menu.file.save_as"), workspace::SaveAs),
+                MenuItem::action(
+                    t!("menu.file.save_all"),
+                    workspace::SaveAll { save_intent: None },
+                ),
+                MenuItem::action(
+                    t!("menu.file.close_editor"),
                     workspace::CloseActiveItem { save_intent: None },
                 ),
-                MenuItem::action("关闭窗口", workspace::CloseWindow),
+                MenuItem::action(
+                    t!("menu.file.reopen_closed_editor"),
+                    workspace::ReopenClosedItem,
+                ),
+                MenuItem::action(t!("menu.file.close_window"), workspace::CloseWindow),
             ],
         },
         Menu {
             name: "Edit".into(),
             items: vec![
-                MenuItem::os_action("撤销", editor::actions::Undo, OsAction::Undo),
-                MenuItem::os_action("重做", editor::actions::Redo, OsAction::Redo),
+                MenuItem::os_action(t!("menu.edit.undo"), editor::actions::Undo, OsAction::Undo),
+                MenuItem::os_action(t!("menu.edit.redo"), editor::actions::Redo, OsAction::Redo),
                 MenuItem::separator(),
-                MenuItem::os_action("剪切", editor::actions::Cut, OsAction::Cut),
-                MenuItem::os_action("复制", editor::actions::Copy, OsAction::Copy),
-                MenuItem::os_action("粘贴", editor::actions::Paste, OsAction::Paste),
+                MenuItem::os_action(t!("menu.edit.cut"), editor::actions::Cut, OsAction::Cut),
+                MenuItem::os_action(t!("menu.edit.copy"), editor::actions::Copy, OsAction::Copy),
+                MenuItem::os_action(
+                    t!("menu.edit.paste"),
+                    editor::actions::Paste,
+                    OsAction::Paste,
+                ),
                 MenuItem::separator(),
-                MenuItem::action("查找", search::buffer_search::Deploy::find()),
-                MenuItem::action("在项目中查找", workspace::DeploySearch::find()),
+                MenuItem::action(t!("menu.edit.find"), search::buffer_search::Deploy::find()),
+                MenuItem::action(
+                    t!("menu.edit.find_in_project"),
+                    workspace::DeploySearch::find(),
+                ),
                 MenuItem::separator(),
                 MenuItem::action(
-                    "切换行注释",
+                    t!("menu.edit.toggle_line_comment"),
                     editor::actions::ToggleComments::default(),
                 ),
+                MenuItem::action(
+                    t!("menu.edit.toggle_read_only"),
+                    editor::actions::ToggleReadOnly,
+                ),
+                MenuItem::separator(),
+                MenuItem::action(
+                    t!("menu.edit.transpose_characters"),
+                    editor::actions::Transpose,
+                ),
+                MenuItem::action(
+                    t!("menu.edit.transpose_lines"),
+                    editor::actions::TransposeLines,
+                ),
             ],
         },
         Menu {
             name: "Selection".into(),
             items: vec![
                 MenuItem::os_action(
-                    "选择全部",
+                    t!("menu.selection.select_all"),
                     editor::actions::SelectAll,
                     OsAction::SelectAll,
                 ),
-                MenuItem::action("扩展选择", editor::actions::SelectLargerSyntaxNode),
-                MenuItem::action("收缩选择", editor::actions::SelectSmallerSyntaxNode),
+                MenuItem::action(
+                    t!("menu.selection.expand_selection"),
+                    editor::actions::SelectLargerSyntaxNode,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.shrink_selection"),
+                    editor::actions::SelectSmallerSyntaxNode,
+                ),
                 MenuItem::separator(),
-                MenuItem::action("在上方添加光标", editor::actions::AddSelectionAbove),
-                MenuItem::action("在下方添加光标", editor::actions::AddSelectionBelow),
                 MenuItem::action(
-                    "选择下一个匹配项",
+                    t!("menu.selection.add_cursor_above"),
+                    editor::actions::AddSelectionAbove,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.add_cursor_below"),
+                    editor::actions::AddSelectionBelow,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.select_next_occurrence"),
                     editor::actions::SelectNext {
                         replace_newest: false,
                     },
                 ),
                 MenuItem::separator(),
-                MenuItem::action("向上移动行", editor::actions::MoveLineUp),
-                MenuItem::action("向下移动行", editor::actions::MoveLineDown),
-                MenuItem::action("复制选择", editor::actions::DuplicateLineDown),
+                MenuItem::action(
+                    t!("menu.selection.move_line_up"),
+                    editor::actions::MoveLineUp,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.move_line_down"),
+                    editor::actions::MoveLineDown,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.duplicate_selection"),
+                    editor::actions::DuplicateLineDown,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.duplicate_selection_up"),
+                    editor::actions::DuplicateLineUp,
+                ),
+                MenuItem::separator(),
+                MenuItem::action(
+                    t!("menu.selection.sort_lines"),
+                    editor::actions::SortLinesCaseSensitive,
+                ),
+                MenuItem::action(
+                    t!("menu.selection.sort_lines_case_insensitive"),
+                    editor::actions::SortLinesCaseInsensitive,
+                ),
+                MenuItem::submenu(Menu {
+                    name: t!("menu.selection.convert_case"),
+                    items: vec![
+                        MenuItem::action(
+                            t!("menu.selection.convert_case.upper_case"),
+                            editor::actions::ConvertToUpperCase,
+                        ),
+                        MenuItem::action(
+                            t!("menu.selection.convert_case.lower_case"),
+                            editor::actions::ConvertToLowerCase,
+                        ),
+                        MenuItem::action(
+                            t!("menu.selection.convert_case.toggle_case"),
+                            editor::actions::ConvertToOppositeCase,
+                        ),
+                    ],
+                }),
             ],
         },
         Menu {
             name: "View".into(),
             items: vec![
-                MenuItem::action("放大", zed_actions::IncreaseBufferFontSize),
-                MenuItem::action("缩小", zed_actions::DecreaseBufferFontSize),
-                MenuItem::action("重置缩放", zed_actions::ResetBufferFontSize),
-                MenuItem::separator(),
-                MenuItem::action("切换左侧面板", workspace::ToggleLeftDock),
-                MenuItem::action("切换右侧面板", workspace::ToggleRightDock),
-                MenuItem::action("切换底部面板", workspace::ToggleBottomDock),
-                MenuItem::action("关闭所有面板", workspace::CloseAllDocks),
+                MenuItem::action(t!("menu.view.zoom_in"), zed_actions::IncreaseBufferFontSize),
+                MenuItem::action(
+                    t!("menu.view.zoom_out"),
+                    zed_actions::DecreaseBufferFontSize,
+                ),
+                MenuItem::action(t!("menu.view.reset_zoom"), zed_actions::ResetBufferFontSize),
+                MenuItem::separator(),
+                MenuItem::action(t!("menu.view.zoom_in_ui"), zed_actions::IncreaseUiFontSize),
+                MenuItem::action(t!("menu.view.zoom_out_ui"), zed_actions::DecreaseUiFontSize),
+                MenuItem::action(t!("menu.view.reset_ui_zoom"), zed_actions::ResetUiFontSize),
+                MenuItem::separator(),
+                MenuItem::action(t!("menu.view.toggle_left_dock"), workspace::ToggleLeftDock),
+                MenuItem::action(
+                    t!("menu.view.toggle_right_dock"),
+                    workspace::ToggleRightDock,
+                ),
+                MenuItem::action(
+                    t!("menu.view.toggle_bottom_dock"),
+                    workspace::ToggleBottomDock,
+                ),
+                MenuItem::action(t!("menu.view.close_all_docks"), workspace::CloseAllDocks),
+                MenuItem::action(t!("menu.view.toggle_zen_mode"), workspace::ToggleZenMode),
                 MenuItem::submenu(Menu {
-                    name: "Editor Layout".into(),
+                    name: t!("menu.view.editor_layout"),
                     items: vec![
-                        MenuItem::action("向上拆分", workspace::SplitUp),
-                        MenuItem::action("向下拆分", workspace::SplitDown),
-                        MenuItem::action("向左拆分", workspace::SplitLeft),
-                        MenuItem::action("向右拆分", workspace::SplitRight),
+                        MenuItem::action(
+                            t!("menu.view.editor_layout.split_up"),
+                            workspace::SplitUp,
+                        ),
+                        MenuItem::action(
+                            t!("menu.view.editor_layout.split_down"),
+                            workspace::SplitDown,
+                        ),
+                        MenuItem::action(
+                            t!("menu.view.editor_layout.split_left"),
+                            workspace::SplitLeft,
+                        ),
+                        MenuItem::action(
+                            t!("menu.view.editor_layout.split_right"),
+                            workspace::SplitRight,
+                        ),
                     ],
                 }),
+                MenuItem::action(
+                    t!("menu.view.toggle_soft_wrap"),
+                    editor::actions::ToggleSoftWrap,
+                ),
+                MenuItem::action(
+                    t!("menu.view.toggle_inlay_hints"),
+                    editor::actions::ToggleInlayHints,
+                ),
                 MenuItem::separator(),
-                MenuItem::action("项目面板", project_panel::ToggleFocus),
-                MenuItem::action("大纲面板", outline_panel::ToggleFocus),
-                MenuItem::action("协作面板", collab_panel::ToggleFocus),
-                MenuItem::action("终端面板", terminal_panel::ToggleFocus),
+                MenuItem::action(t!("menu.view.project_panel"), project_panel::ToggleFocus),
+                MenuItem::action(
+                    t!("menu.view.reveal_in_project_panel"),
+                    workspace::RevealInProjectPanel::default(),
+                ),
+                MenuItem::action(t!("menu.view.outline_panel"), outline_panel::ToggleFocus),
+                MenuItem::action(t!("menu.view.collab_panel"), collab_panel::ToggleFocus),
+                MenuItem::action(t!("menu.view.terminal_panel"), terminal_panel::ToggleFocus),
                 MenuItem::separator(),
-                MenuItem::action("诊断", diagnostics::Deploy),
+                MenuItem::action(t!("menu.view.diagnostics"), diagnostics::Deploy),
                 MenuItem::separator(),
             ],
         },
         Menu {
             name: "Go".into(),
             items: vec![
-                MenuItem::action("后退", workspace::GoBack),
-                MenuItem::action("前进", workspace::GoForward),
+                MenuItem::action(t!("menu.go.back"), workspace::GoBack),
+                MenuItem::action(t!("menu.go.forward"), workspace::GoForward),
                 MenuItem::separator(),
-                MenuItem::action("命令面板...", zed_actions::command_palette::Toggle),
+                MenuItem::action(
+                    t!("menu.go.command_palette"),
+                    zed_actions::command_palette::Toggle,
+                ),
+                MenuItem::submenu(Menu {
+                    name: t!("menu.go.recent_commands"),
+                    items: recent_command_menu_items(cx),
+                }),
                 MenuItem::separator(),
-                MenuItem::action("跳转到文件...", workspace::ToggleFileFinder::default()),
+                MenuItem::action(
+                    t!("menu.go.go_to_file"),
+                    workspace::ToggleFileFinder::default(),
+                ),
                 // MenuItem::action("Go to Symbol in Project", project_symbols::Toggle),
-                MenuItem::action("在编辑器中跳转到字符...", editor::actions::ToggleOutline),
-                MenuItem::action("转到行/列...", editor::actions::ToggleGoToLine),
+                MenuItem::action(
+                    t!("menu.go.go_to_symbol_in_editor"),
+                    editor::actions::ToggleOutline,
+                ),
+                MenuItem::action(t!("menu.go.go_to_line"), editor::actions::ToggleGoToLine),
                 MenuItem::separator(),
-                MenuItem::action("转到定义", editor::actions::GoToDefinition),
-                MenuItem::action("转到声明", editor::actions::GoToDeclaration),
-                MenuItem::action("转到类型定义", editor::actions::GoToTypeDefinition),
-                MenuItem::action("查找所有引用", editor::actions::FindAllReferences),
+                MenuItem::action(
+                    t!("menu.go.go_to_definition"),
+                    editor::actions::GoToDefinition,
+                ),
+                MenuItem::action(
+                    t!("menu.go.go_to_declaration"),
+                    editor::actions::GoToDeclaration,
+                ),
+                MenuItem::action(
+                    t!("menu.go.go_to_type_definition"),
+                    editor::actions::GoToTypeDefinition,
+                ),
+                MenuItem::action(
+                    t!("menu.go.find_all_references"),
+                    editor::actions::FindAllReferences,
+                ),
                 MenuItem::separator(),
-                MenuItem::action("下一个问题", editor::actions::GoToDiagnostic),
-                MenuItem::action("上一个问题", editor::actions::GoToPrevDiagnostic),
+                MenuItem::action(t!("menu.go.next_problem"), editor::actions::GoToDiagnostic),
+                MenuItem::action(
+                    t!("menu.go.previous_problem"),
+                    editor::actions::GoToPrevDiagnostic,
+                ),
+                MenuItem::separator(),
+                MenuItem::submenu(Menu {
+                    name: t!("menu.go.bookmarks"),
+                    items: vec![
+                        MenuItem::action(
+                            t!("menu.go.bookmarks.toggle_bookmark"),
+                            editor::actions::ToggleBookmark,
+                        ),
+                        MenuItem::action(
+                            t!("menu.go.bookmarks.next_bookmark"),
+                            editor::actions::GoToNextBookmark,
+                        ),
+                        MenuItem::action(
+                            t!("menu.go.bookmarks.previous_bookmark"),
+                            editor::actions::GoToPrevBookmark,
+                        ),
+                        MenuItem::action(
+                            t!("menu.go.bookmarks.clear_bookmarks"),
+                            editor::actions::ClearBookmarks,
+                        ),
+                    ],
+                }),
             ],
         },
         Menu {
-            name: "Window".into(),
+            name: "Git".into(),
             items: vec![
-                MenuItem::action("最小化", super::Minimize),
-                MenuItem::action("缩放", super::Zoom),
+                MenuItem::action(t!("menu.git.next_hunk"), editor::actions::GoToHunk),
+                MenuItem::action(t!("menu.git.previous_hunk"), editor::actions::GoToPrevHunk),
+                MenuItem::separator(),
+                MenuItem::action(
+                    t!("menu.git.toggle_hunk_diff"),
+                    editor::actions::ToggleHunkDiff,
+                ),
+                MenuItem::action(
+                    t!("menu.git.expand_all_hunk_diffs"),
+                    editor::actions::ExpandAllHunkDiffs,
+                ),
+                MenuItem::action(
+                    t!("menu.git.revert_selected_hunks"),
+                    editor::actions::RevertSelectedHunks,
+                ),
+                MenuItem::action(t!("menu.git.revert_file"), editor::actions::RevertFile),
                 MenuItem::separator(),
+                MenuItem::action(
+                    t!("menu.git.toggle_git_blame"),
+                    editor::actions::ToggleGitBlame,
+                ),
+                MenuItem::action(
+                    t!("menu.git.toggle_inline_git_blame"),
+                    editor::actions::ToggleGitBlameInline,
+                ),
+                MenuItem::separator(),
+                MenuItem::action(
+                    t!("menu.git.copy_permalink"),
+                    editor::actions::CopyPermalinkToLine,
+                ),
+                MenuItem::action(
+                    t!("menu.git.open_permalink"),
+                    editor::actions::OpenPermalinkToLine,
+                ),
             ],
         },
+        Menu {
+            name: "Window".into(),
+            items: vec![
+                MenuItem::action(t!("menu.window.minimize"), super::Minimize),
+                MenuItem::action(t!("menu.window.zoom"), super::Zoom),
+                MenuItem::action(
+                    t!("menu.window.toggle_full_screen"),
+                    super::ToggleFullScreen,
+                ),
+                MenuItem::separator(),
+            ]
+            .into_iter()
+            .chain(window_menu_items(cx))
+            .collect(),
+        },
         Menu {
             name: "Help".into(),
             items: vec![
-                MenuItem::action("查看遥测数据", zed_actions::OpenTelemetryLog),
-                MenuItem::action("查看依赖项许可证", zed_actions::OpenLicenses),
-                MenuItem::action("显示欢迎页", workspace::Welcome),
-                MenuItem::action("提供反馈...", zed_actions::feedback::GiveFeedback),
+                MenuItem::action(
+                    t!("menu.help.view_telemetry"),
+                    zed_actions::OpenTelemetryLog,
+                ),
+                MenuItem::action(
+                    t!("menu.help.view_dependency_licenses"),
+                    zed_actions::OpenLicenses,
+                ),
+                MenuItem::action(t!("menu.help.show_welcome"), workspace::Welcome),
+                MenuItem::action(
+                    t!("menu.help.give_feedback"),
+                    zed_actions::feedback::GiveFeedback,
+                ),
                 MenuItem::separator(),
                 MenuItem::action(
-                    "文档",
+                    t!("menu.help.documentation"),
                     super::OpenBrowser {
                         url: "https://zed.dev/docs".into(),
                     },
                 ),
                 MenuItem::action(
-                    "Zed Twitter",
+                    t!("menu.help.twitter"),
                     super::OpenBrowser {
                         url: "https://twitter.com/zeddotdev".into(),
                     },
                 ),
                 MenuItem::action(
-                    "加入团队",
+                    t!("menu.help.join_the_team"),
                     super::OpenBrowser {
                         url: "https://zed.dev/jobs".into(),
                     },
@@ -203,3 +449,50 @@ pub fn app_menus() -> Vec<Menu> {
         },
     ]
 }
+
+/// Lists every open workspace window, marking the active one, so that the
+/// user can switch between windows from the "Window" menu.
+fn window_menu_items(cx: &AppContext) -> Vec<MenuItem> {
+    let active_window = cx.active_window();
+    cx.windows()
+        .into_iter()
+        .filter_map(|window| {
+            let workspace = window.downcast::<Workspace>()?.read(cx).ok()?;
+            let root_names = workspace
+                .project()
+                .read(cx)
+                .worktree_root_names(cx)
+                .collect::<Vec<_>>();
+            let title = if root_names.is_empty() {
+                t!("menu.window.empty_window").to_string()
+            } else {
+                root_names.join(", ")
+            };
+            let name = if active_window == Some(window) {
+                format!("✓ {title}")
+            } else {
+                title
+            };
+            Some(MenuItem::action(
+                name,
+                super::ActivateWindow {
+                    window_id: window.window_id().as_u64(),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn recent_command_menu_items(cx: &AppContext) -> Vec<MenuItem> {
+    let Some(recent_commands) = cx.try_global::<command_palette::RecentCommands>() else {
+        return Vec::new();
+    };
+    recent_commands
+        .iter()
+        .map(|(name, action)| MenuItem::Action {
+            name: name.to_string().into(),
+            action: action.boxed_clone(),
+            os_action: None,
+        })
+        .collect()
+}
@@ -1,5 +1,8 @@
 mod markdown_preview;
 mod repl_menu;
+mod repl_status;
+
+pub use repl_status::ReplStatus;
 
 use assistant::assistant_settings::AssistantSettings;
 use assistant::AssistantPanel;
@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use gpui::ElementId;
 use gpui::{percentage, Animation, AnimationExt, AnyElement, Transformation, View};
+use i18n::t;
 use picker::Picker;
 use repl::{
     components::{KernelPickerDelegate, KernelSelector},
@@ -75,6 +76,9 @@ impl QuickActionBar {
         };
 
         let menu_state = session_state(session.clone(), cx);
+        let has_connection_info = session.read(cx).kernel_connection_info().is_some();
+        let has_working_directory_override =
+            session.read(cx).working_directory_override().is_some();
 
         let id = "repl-menu".to_string();
 
@@ -145,6 +149,20 @@ impl QuickActionBar {
                             }
                         },
                     )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.run_and_advance"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::run_and_advance(editor.clone(), cx).log_err();
+                            }
+                        },
+                    )
                     .custom_entry(
                         move |_cx| {
                             Label::new("Interrupt")
@@ -161,7 +179,49 @@ impl QuickActionBar {
                     )
                     .custom_entry(
                         move |_cx| {
-                            Label::new("Clear Outputs")
+                            Label::new(t!("repl.insert_cell"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::insert_cell(editor.clone(), cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.merge_cell_above"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::merge_cell(editor.clone(), true, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.merge_cell_below"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::merge_cell(editor.clone(), false, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.clear_all_outputs"))
                                 .size(LabelSize::Small)
                                 .color(Color::Muted)
                                 .into_any_element()
@@ -173,6 +233,48 @@ impl QuickActionBar {
                             }
                         },
                     )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.collapse_all_outputs"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::set_all_outputs_collapsed(editor.clone(), true, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.expand_all_outputs"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::set_all_outputs_collapsed(editor.clone(), false, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.copy_cell_as_markdown"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::copy_cell_as_markdown(editor.clone(), cx);
+                            }
+                        },
+                    )
                     .separator()
                     .custom_entry(
                         move |_cx| {
@@ -190,7 +292,35 @@ impl QuickActionBar {
                     )
                     .custom_entry(
                         move |_cx| {
-                            Label::new("Restart Kernel")
+                            Label::new(t!("repl.restart_kernel"))
+                                .size(LabelSize::Small)
+                                .color(Color::Error)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::restart(editor.clone(), false, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.restart_and_clear_outputs"))
+                                .size(LabelSize::Small)
+                                .color(Color::Error)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::restart(editor.clone(), true, cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.restart_and_run_all"))
                                 .size(LabelSize::Small)
                                 .color(Color::Error)
                                 .into_any_element()
@@ -198,14 +328,60 @@ impl QuickActionBar {
                         {
                             let editor = editor.clone();
                             move |cx| {
-                                repl::restart(editor.clone(), cx);
+                                repl::restart_and_run_all(editor.clone(), cx);
+                            }
+                        },
+                    )
+                    .custom_entry(
+                        move |_cx| {
+                            Label::new(t!("repl.set_working_directory"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .into_any_element()
+                        },
+                        {
+                            let editor = editor.clone();
+                            move |cx| {
+                                repl::set_working_directory(editor.clone(), cx);
                             }
                         },
                     )
+                    .when(has_working_directory_override, |menu| {
+                        menu.custom_entry(
+                            move |_cx| {
+                                Label::new(t!("repl.reset_working_directory"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .into_any_element()
+                            },
+                            {
+                                let editor = editor.clone();
+                                move |cx| {
+                                    repl::reset_working_directory(editor.clone(), cx);
+                                }
+                            },
+                        )
+                    })
+                    .when(has_connection_info, |menu| {
+                        menu.custom_entry(
+                            move |_cx| {
+                                Label::new(t!("repl.copy_kernel_connection_info"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .into_any_element()
+                            },
+                            {
+                                let editor = editor.clone();
+                                move |cx| {
+                                    repl::copy_kernel_connection_info(editor.clone(), cx);
+                                }
+                            },
+                        )
+                    })
                     .separator()
                     .action("View Sessions", Box::new(repl::Sessions))
-                    // TODO: Add shut down all kernels action
-                    // .action("Shut Down all Kernels", Box::new(gpui::NoAction))
+                    .action(t!("repl.shutdown_all_kernels"), Box::new(repl::ShutdownAll))
+                    .action(t!("repl.open_scratchpad"), Box::new(repl::OpenScratchpad))
                 })
                 .into()
             })
@@ -392,6 +568,12 @@ fn session_state(session: View<Session>, cx: &WindowContext) -> ReplMenuState {
     };
 
     match &session.kernel {
+        Kernel::NotStarted => ReplMenuState {
+            tooltip: format!("Run code to start {} ({})", kernel_name, kernel_language).into(),
+            indicator: Some(Indicator::dot().color(Color::Muted)),
+            status: session.kernel.status(),
+            ..fill_fields()
+        },
         Kernel::Restarting => ReplMenuState {
             tooltip: format!("Restarting {}", kernel_name).into(),
             icon_is_animating: true,
@@ -401,6 +583,14 @@ fn session_state(session: View<Session>, cx: &WindowContext) -> ReplMenuState {
             status: session.kernel.status(),
             ..fill_fields()
         },
+        Kernel::RunningKernel(kernel) if kernel.is_reconnecting() => ReplMenuState {
+            tooltip: format!("Reconnecting to {}", kernel_name).into(),
+            icon_is_animating: true,
+            popover_disabled: false,
+            indicator: Some(Indicator::dot().color(Color::Warning)),
+            status: session.kernel.status(),
+            ..fill_fields()
+        },
         Kernel::RunningKernel(kernel) => match &kernel.execution_state() {
             ExecutionState::Idle => ReplMenuState {
                 tooltip: format!("Run code on {} ({})", kernel_name, kernel_language).into(),
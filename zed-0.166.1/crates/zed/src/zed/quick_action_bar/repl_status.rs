@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use editor::Editor;
+use gpui::{
+    percentage, Action, Animation, AnimationExt, Subscription, Transformation, View, WeakView,
+};
+use i18n::t;
+use repl::{JupyterSettings, KernelStatus, ReplStore, Session, SessionSupport};
+use ui::{prelude::*, ButtonLike, Indicator, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+/// The title bar's REPL status: the kernel status of the active editor's
+/// session, and a spinner while the kernel of any session is executing code.
+pub struct ReplStatus {
+    active_editor: Option<WeakView<Editor>>,
+    session: Option<View<Session>>,
+    _observe_session: Option<Subscription>,
+    _observe_store: Option<Subscription>,
+}
+
+impl ReplStatus {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        // Sessions start and shut down while their editor stays active.
+        let observe_store = ReplStore::try_global(cx)
+            .map(|store| cx.observe(&store, |this, _, cx| this.update_session(cx)));
+        Self {
+            active_editor: None,
+            session: None,
+            _observe_session: None,
+            _observe_store: observe_store,
+        }
+    }
+
+    fn update_session(&mut self, cx: &mut ViewContext<Self>) {
+        let session =
+            self.active_editor
+                .clone()
+                .and_then(|editor| match repl::session(editor, cx) {
+                    SessionSupport::ActiveSession(session) => Some(session),
+                    _ => None,
+                });
+
+        if session.as_ref().map(|session| session.entity_id())
+            != self.session.as_ref().map(|session| session.entity_id())
+        {
+            self._observe_session = session
+                .as_ref()
+                .map(|session| cx.observe(session, |_, _, cx| cx.notify()));
+            self.session = session;
+        }
+        cx.notify();
+    }
+
+    fn render_kernel_status(&self, cx: &WindowContext) -> Option<impl IntoElement> {
+        let session = self.session.as_ref()?.read(cx);
+        let color = session.status_color();
+        let kernel_name = session.kernel_specification.name();
+        let status = KernelStatus::from(&session.kernel).to_string();
+
+        Some(
+            ButtonLike::new("repl_kernel_status")
+                .style(ButtonStyle::Subtle)
+                .child(Indicator::dot().color(color))
+                .tooltip(move |cx| {
+                    Tooltip::with_meta(
+                        format!("{}: {kernel_name}", t!("repl.kernel")),
+                        Some(&repl::Sessions),
+                        status.clone(),
+                        cx,
+                    )
+                })
+                .on_click(|_, cx| cx.dispatch_action(repl::Sessions.boxed_clone())),
+        )
+    }
+
+    fn render_busy_kernels_indicator(&self, cx: &WindowContext) -> Option<impl IntoElement> {
+        if !ReplStore::try_global(cx)?.read(cx).any_kernel_busy() {
+            return None;
+        }
+
+        Some(
+            div()
+                .id("repl_kernels_busy")
+                .child(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted)
+                        .with_animation(
+                            "repl_kernels_busy_spinner",
+                            Animation::new(Duration::from_secs(3)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        ),
+                )
+                .tooltip(|cx| Tooltip::text(t!("repl.kernels_busy"), cx)),
+        )
+    }
+}
+
+impl Render for ReplStatus {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        if !JupyterSettings::enabled(cx) {
+            return h_flex();
+        }
+
+        h_flex()
+            .gap_1()
+            .children(self.render_kernel_status(cx))
+            .children(self.render_busy_kernels_indicator(cx))
+    }
+}
+
+impl StatusItemView for ReplStatus {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.active_editor = active_pane_item
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .map(|editor| editor.downgrade());
+        self.update_session(cx);
+    }
+}
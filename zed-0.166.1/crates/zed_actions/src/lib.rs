@@ -40,7 +40,10 @@ actions!(
         ResetBufferFontSize,
         DecreaseUiFontSize,
         IncreaseUiFontSize,
-        ResetUiFontSize
+        ResetUiFontSize,
+        DecreaseTerminalFontSize,
+        IncreaseTerminalFontSize,
+        ResetTerminalFontSize
     ]
 );
 
@@ -63,7 +66,7 @@ pub mod feedback {
 }
 
 pub mod theme_selector {
-    use gpui::impl_actions;
+    use gpui::{actions, impl_actions};
     use serde::Deserialize;
 
     #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
@@ -73,6 +76,8 @@ pub mod theme_selector {
     }
 
     impl_actions!(theme_selector, [Toggle]);
+
+    actions!(theme_selector, [ToggleMode]);
 }
 
 #[derive(Clone, Default, Deserialize, PartialEq)]